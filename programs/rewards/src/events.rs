@@ -0,0 +1,255 @@
+//! Program events
+
+use crate::state::PoolStatus;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::log::sol_log_data;
+use solana_program::pubkey::Pubkey;
+
+/// Events emitted by the program
+#[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq, Eq)]
+pub enum RewardsEvent {
+    /// Reward pool parameters were updated
+    PoolUpdated {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// staking lock time
+        lock_time_sec: u64,
+        /// max stakers
+        max_stakers: u64,
+        /// Min amount of a single deposit
+        min_deposit_amount: u64,
+        /// Max amount of a single deposit
+        max_deposit_amount: u64,
+        /// Min position size
+        min_stake_amount: u64,
+        /// Max position size per wallet
+        max_stake_amount: u64,
+        /// Unbonding period
+        unbonding_period_sec: u64,
+        /// Unbonding amount is released linearly
+        is_linear_unbonding: bool,
+        /// Pool status
+        status: PoolStatus,
+        /// Share of the referee rewards accrued for the referrer
+        referral_share_bps: u16,
+        /// Warm-up period of new deposits
+        warmup_period_sec: u64,
+        /// Reward rate during the warm-up
+        warmup_rate_bps: u16,
+        /// Max lock a funder can enforce on a position
+        max_funder_lock_sec: u64,
+    },
+    /// Position below the pool min stake amount was returned to the owner
+    MiningEvicted {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Mining owner
+        owner: Pubkey,
+        /// Returned amount
+        amount: u64,
+    },
+    /// Smallest position of a full competitive pool was displaced by a larger one
+    MiningDisplaced {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Displaced mining account
+        mining: Pubkey,
+        /// Displaced mining owner
+        owner: Pubkey,
+        /// Returned amount
+        amount: u64,
+        /// Mining account that took the slot
+        displaced_by: Pubkey,
+    },
+    /// Reward pool fees were updated
+    PoolFeesUpdated {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Treasury wallet
+        treasury: Pubkey,
+        /// Deposit fee in basis points
+        deposit_fee_bps: u16,
+        /// Withdraw fee in basis points
+        withdraw_fee_bps: u16,
+        /// Claim fee in basis points
+        claim_fee_bps: u16,
+        /// Deposit and withdraw fees fill the liquidity mint reward vault
+        is_fee_to_vault: bool,
+    },
+    /// Reward vault was filled
+    VaultFilled {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Reward mint
+        reward_mint: Pubkey,
+        /// Funder
+        funder: Pubkey,
+        /// Filled amount
+        amount: u64,
+        /// Total amount the vault was filled with
+        total_funded: u64,
+    },
+    /// New mining was referred
+    MiningReferred {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Referrer wallet
+        referrer: Pubkey,
+        /// Share of the mining rewards accrued for the referrer
+        referral_share_bps: u16,
+    },
+    /// Referral rewards were moved to the referrer mining
+    ReferralRewardsSettled {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Referred mining account
+        mining: Pubkey,
+        /// Referrer wallet
+        referrer: Pubkey,
+        /// Reward mint
+        reward_mint: Pubkey,
+        /// Settled amount
+        amount: u64,
+    },
+    /// Boost of the mining was refreshed from held boost sources
+    BoostRefreshed {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Multiplier added to the reward tier ratio
+        boost_bps: u16,
+    },
+    /// Reward tier was bought by burning tokens
+    TierPurchased {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Bought reward tier
+        tier: u8,
+        /// Burned amount
+        burned_amount: u64,
+        /// End of the tier, zero means the tier doesn't expire
+        tier_expires_at: u64,
+    },
+    /// Mining amount was synced to the receipt balance of the owner
+    ReceiptSynced {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Mining owner
+        owner: Pubkey,
+        /// Synced amount
+        amount: u64,
+    },
+    /// Position was tokenized into an NFT
+    PositionTokenized {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Position NFT mint
+        position_mint: Pubkey,
+        /// Position amount
+        amount: u64,
+    },
+    /// Pool started closing
+    PoolClosingStarted {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// End of the grace period
+        closes_at: u64,
+    },
+    /// Position left in a closed pool was unstaked to its owner
+    PositionForceUnstaked {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Wallet receiving the tokens
+        owner: Pubkey,
+        /// Staked and pending unstake amount
+        amount: u64,
+        /// Paid rewards by reward mint
+        rewards: Vec<(Pubkey, u64)>,
+    },
+    /// Position was withdrawn to its owner by the root authority
+    PositionForceWithdrawn {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Wallet receiving the tokens
+        owner: Pubkey,
+        /// Root authority
+        authority: Pubkey,
+        /// Staked and pending unstake amount
+        amount: u64,
+        /// Paid rewards by reward mint
+        rewards: Vec<(Pubkey, u64)>,
+    },
+    /// Position was frozen by the root authority
+    MiningFrozen {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Mining owner
+        owner: Pubkey,
+        /// Reason code
+        reason: u8,
+        /// Rewards don't accrue while frozen
+        is_accrual_paused: bool,
+    },
+    /// Position was unfrozen by the root authority
+    MiningUnfrozen {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Mining owner
+        owner: Pubkey,
+    },
+    /// Wallet was added to or removed from the denylist
+    DenylistUpdated {
+        /// Rewards root
+        rewards_root: Pubkey,
+        /// Wallet
+        wallet: Pubkey,
+        /// Wallet is denied from depositing
+        is_denied: bool,
+    },
+    /// Position was staked on behalf of its owner
+    PositionFunded {
+        /// Reward pool
+        reward_pool: Pubkey,
+        /// Mining account
+        mining: Pubkey,
+        /// Wallet supplying the tokens
+        funder: Pubkey,
+        /// Mining owner
+        owner: Pubkey,
+        /// Deposited amount
+        amount: u64,
+        /// End of the lock of the position
+        lock_ends_at: u64,
+    },
+    /// Pool was closed
+    PoolClosed {
+        /// Reward pool
+        reward_pool: Pubkey,
+    },
+}
+
+impl RewardsEvent {
+    /// Logs the event as program data
+    pub fn emit(&self) {
+        sol_log_data(&[&borsh::to_vec(self).unwrap()]);
+    }
+}
@@ -6,7 +6,16 @@ use solana_program::pubkey::Pubkey;
 use solana_program::sysvar::clock;
 use solana_program::{system_program, sysvar};
 
-use crate::state::RewardTier;
+use crate::{
+    find_boost_source_program_address, find_denylist_program_address,
+    find_funder_contribution_program_address, find_mining_position_address,
+    find_mining_program_address, find_position_mint_program_address,
+    find_receipt_mint_program_address, find_referrer_program_address,
+    find_stakers_index_program_address, find_unwrap_program_address,
+    find_vault_spl_token_account,
+};
+use crate::state::{BoostKind, PoolStatus, RewardTier, VaultKind};
+use everlend_utils::find_program_address;
 
 /// Instructions supported by the program
 #[derive(Debug, BorshDeserialize, BorshSerialize, PartialEq, Eq)]
@@ -17,6 +26,10 @@ pub enum RewardsInstruction {
         lock_time_sec: u64,
         /// max stakers
         max_stakers: u64,
+        /// Max number of vaults
+        vaults_capacity: u8,
+        /// Max number of reward tiers per vault
+        tiers_capacity: u8,
     },
 
     /// Creates a new vault account and adds it to the reward pool
@@ -27,6 +40,8 @@ pub enum RewardsInstruction {
         is_enabled: bool,
         /// reward tiers
         tiers: Vec<RewardTier>,
+        /// Vault kind
+        kind: VaultKind,
     },
 
     /// Updates vault parameters
@@ -37,6 +52,22 @@ pub enum RewardsInstruction {
         is_enabled: Option<bool>,
         /// reward tiers
         tiers: Option<Vec<RewardTier>>,
+        /// Vault manager allowed to fill a permissioned vault
+        manager: Option<Pubkey>,
+        /// Only the root authority and the vault manager can fill the vault
+        is_fill_permissioned: Option<bool>,
+        /// Vault kind
+        kind: Option<VaultKind>,
+        /// Max amount a mint vault can mint in total
+        max_mint_amount: Option<u64>,
+        /// Max amount a mint vault can mint per reward period
+        max_mint_amount_per_period: Option<u64>,
+        /// Reward periods are epochs aligned to the epoch anchor
+        is_epoch_aligned: Option<bool>,
+        /// Start of any epoch
+        epoch_anchor: Option<u64>,
+        /// Partial epochs pay nothing instead of being pro-rated
+        is_partial_epoch_excluded: Option<bool>,
     },
 
     /// Fills the reward pool with rewards
@@ -51,7 +82,8 @@ pub enum RewardsInstruction {
         amount: u64,
     },
 
-    /// Withdraws amount of supply to the mining account
+    /// Withdraws amount of supply to the mining account,
+    /// referred positions settle the referral rewards on the way out
     WithdrawMining,
 
     /// Claims amount of rewards
@@ -66,19 +98,209 @@ pub enum RewardsInstruction {
     /// Creates and initializes a reward root
     InitializeRoot,
 
-    /// Migrates reward pool
-    MigratePool {
+    /// Migrates reward pool to the zero-copy layout
+    MigratePool,
+
+    /// Migrates mining to the zero-copy layout
+    MigrateMining,
+
+    /// Grows vaults and reward tiers capacity of the reward pool (admin method)
+    ResizePool {
+        /// Max number of vaults
+        vaults_capacity: u8,
+        /// Max number of reward tiers per vault
+        tiers_capacity: u8,
+    },
+
+    /// Updates reward pool parameters (admin method)
+    UpdatePool {
+        /// staking lock time
+        lock_time_sec: Option<u64>,
         /// max stakers
-        max_stakers: u64,
-        /// total stakers
-        total_stakers: u64,
+        max_stakers: Option<u64>,
+        /// Min amount of a single deposit
+        min_deposit_amount: Option<u64>,
+        /// Max amount of a single deposit, zero means no limit
+        max_deposit_amount: Option<u64>,
+        /// Min position size
+        min_stake_amount: Option<u64>,
+        /// Max position size per wallet, zero means no limit
+        max_stake_amount: Option<u64>,
+        /// Unbonding period, zero means withdrawals don't need unbonding
+        unbonding_period_sec: Option<u64>,
+        /// Unbonding amount is released linearly
+        is_linear_unbonding: Option<bool>,
+        /// Pool status
+        status: Option<PoolStatus>,
+        /// Share of the referee rewards accrued for the referrer in basis points
+        referral_share_bps: Option<u16>,
+        /// Warm-up period of new deposits, zero means deposits earn right away
+        warmup_period_sec: Option<u64>,
+        /// Reward rate during the warm-up in basis points
+        warmup_rate_bps: Option<u16>,
+        /// Max lock a funder can enforce on a position, zero means funders can't enforce locks
+        max_funder_lock_sec: Option<u64>,
     },
 
-    /// Migrates mining
-    MigrateMining,
+    /// Returns a position below the pool min stake amount to the owner or the NFT holder
+    /// (permissionless)
+    EvictDust,
+
+    /// Creates stakers index and makes the pool competitive (admin method)
+    InitializeStakersIndex,
+
+    /// Moves amount of the mining to unbonding, it stops accruing rewards
+    RequestUnstake {
+        /// Amount to unstake
+        amount: u64,
+    },
+
+    /// Withdraws the unbonded amount
+    FinalizeUnstake,
+
+    /// Stakes the pending unbonding amount again
+    CancelUnstake,
+
+    /// Sets deposit, withdraw and claim fees of the reward pool (admin method)
+    SetPoolFees {
+        /// Treasury wallet, fees go to its associated token accounts
+        treasury: Pubkey,
+        /// Deposit fee in basis points
+        deposit_fee_bps: u16,
+        /// Withdraw fee in basis points
+        withdraw_fee_bps: u16,
+        /// Claim fee in basis points
+        claim_fee_bps: u16,
+        /// Deposit and withdraw fees fill the liquidity mint reward vault instead of the treasury
+        is_fee_to_vault: bool,
+    },
+
+    /// Moves rewards accrued for the referrer to the referrer mining (permissionless)
+    SettleReferralRewards,
+
+    /// Registers a partner token or NFT mint list boosting rewards of holders (admin method)
+    AddBoostSource {
+        /// Boost kind
+        kind: BoostKind,
+        /// Multiplier added to the reward tier ratio in basis points
+        multiplier_bps: u16,
+        /// Min balance of a token boost
+        min_amount: u64,
+        /// Token mint or NFT mints
+        mints: Vec<Pubkey>,
+    },
+
+    /// Updates boost source parameters (admin method)
+    UpdateBoostSource {
+        /// Multiplier added to the reward tier ratio in basis points, zero disables the source
+        multiplier_bps: Option<u16>,
+        /// Min balance of a token boost
+        min_amount: Option<u64>,
+    },
+
+    /// Recalculates the mining boost from held boost sources (permissionless),
+    /// mint list sources are only dropped when the owner refreshes
+    RefreshBoost,
+
+    /// Sets burn prices of reward tiers (admin method)
+    SetTierPrices {
+        /// Burn price of each reward tier starting from the base one, zero means not for sale
+        prices: Vec<u64>,
+        /// How long a bought tier lasts, zero means forever
+        tier_duration_sec: u64,
+    },
+
+    /// Buys a higher reward tier by burning its price
+    BuyTier {
+        /// Reward tier
+        tier: u8,
+    },
+
+    /// Creates the transferable receipt mint of staked amounts (admin method)
+    InitializeReceiptMint,
+
+    /// Syncs the mining amount to the receipt balance of the owner (permissionless)
+    SyncReceipt,
+
+    /// Creates another position of the user in the pool
+    OpenPosition {
+        /// Position index, zero is the default position
+        index: u16,
+    },
+
+    /// Moves amount of the position to another position of the user
+    SplitPosition {
+        /// Amount to move
+        amount: u64,
+    },
+
+    /// Moves the whole position to another position of the user
+    MergePosition,
+
+    /// Mints the position as an NFT, its holder claims and withdraws the position
+    TokenizePosition,
+
+    /// Starts closing the pool, deposits stop and stakers can exit freely (admin method)
+    StartPoolClosing {
+        /// Time stakers have to exit before positions can be force unstaked,
+        /// at least the pool lock and never zero
+        grace_period_sec: u64,
+    },
+
+    /// Unstakes a position left after the closing grace period with its rewards to its owner
+    /// (permissionless), every position including empty ones has to be unstaked before closing,
+    /// rewards the vaults can't cover are forfeited
+    ForceUnstake,
+
+    /// Sweeps the vaults and closes the pool accounts once every mining is gone (admin method),
+    /// minting of the reward mints goes back to the root authority
+    ClosePool,
+
+    /// Withdraws the position with its rewards to the owner regardless of the lock (admin method)
+    ForceWithdraw,
+
+    /// Freezes the position, it can't claim, withdraw or move the stake (admin method)
+    FreezeMining {
+        /// Reason code, zero isn't allowed
+        reason: u8,
+        /// Rewards don't accrue while frozen
+        pause_accrual: bool,
+    },
+
+    /// Unfreezes the position (admin method)
+    UnfreezeMining,
+
+    /// Adds the wallet to or removes it from the denylist checked on deposits, deposits into
+    /// the pools passed after the fixed accounts are checked against it from then on
+    /// (admin method)
+    UpdateDenylist {
+        /// Wallet
+        wallet: Pubkey,
+        /// Wallet is denied from depositing
+        is_denied: bool,
+    },
+
+    /// Deposits tokens of the signer into an empty position of the beneficiary
+    DepositFor {
+        /// Mining owner
+        beneficiary: Pubkey,
+        /// Amount to deposit
+        amount: u64,
+        /// Min lock up to the max funder lock of the pool, pool lock updates can't shorten it
+        min_lock_sec: Option<u64>,
+    },
+
+    /// Deposits amount of supply to the mining account recording the referrer of a new mining
+    DepositMiningWithReferrer {
+        /// Amount to deposit
+        amount: u64,
+        /// Referrer wallet, recorded on the first deposit only
+        referrer: Pubkey,
+    },
 }
 
 /// Creates 'InitializePool' instruction.
+#[allow(clippy::too_many_arguments)]
 pub fn initialize_pool(
     program_id: &Pubkey,
     root_account: &Pubkey,
@@ -89,6 +311,8 @@ pub fn initialize_pool(
     payer: &Pubkey,
     lock_time_sec: u64,
     max_stakers: u64,
+    vaults_capacity: u8,
+    tiers_capacity: u8,
 ) -> Instruction {
     let accounts = vec![
         AccountMeta::new_readonly(*root_account, false),
@@ -107,6 +331,8 @@ pub fn initialize_pool(
         &RewardsInstruction::InitializePool {
             lock_time_sec,
             max_stakers,
+            vaults_capacity,
+            tiers_capacity,
         },
         accounts,
     )
@@ -142,6 +368,41 @@ pub fn add_vault(
             reward_period_sec,
             is_enabled: true,
             tiers,
+            kind: VaultKind::Transfer,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'AddVault' instruction for a vault paying rewards in lamports.
+pub fn add_native_vault(
+    program_id: &Pubkey,
+    rewards_root: &Pubkey,
+    reward_pool: &Pubkey,
+    vault: &Pubkey,
+    authority: &Pubkey,
+    reward_period_sec: u32,
+    tiers: Vec<RewardTier>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(spl_token::native_mint::id(), false),
+        AccountMeta::new(*vault, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::AddVault {
+            reward_period_sec,
+            is_enabled: true,
+            tiers,
+            kind: VaultKind::Native,
         },
         accounts,
     )
@@ -158,6 +419,14 @@ pub fn update_vault(
     reward_period_sec: Option<u32>,
     is_enabled: Option<bool>,
     tiers: Option<Vec<RewardTier>>,
+    manager: Option<Pubkey>,
+    is_fill_permissioned: Option<bool>,
+    kind: Option<VaultKind>,
+    max_mint_amount: Option<u64>,
+    max_mint_amount_per_period: Option<u64>,
+    is_epoch_aligned: Option<bool>,
+    epoch_anchor: Option<u64>,
+    is_partial_epoch_excluded: Option<bool>,
 ) -> Instruction {
     let accounts = vec![
         AccountMeta::new_readonly(*rewards_root, false),
@@ -172,6 +441,14 @@ pub fn update_vault(
             reward_period_sec,
             is_enabled,
             tiers,
+            manager,
+            is_fill_permissioned,
+            kind,
+            max_mint_amount,
+            max_mint_amount_per_period,
+            is_epoch_aligned,
+            epoch_anchor,
+            is_partial_epoch_excluded,
         },
         accounts,
     )
@@ -180,6 +457,7 @@ pub fn update_vault(
 #[allow(clippy::too_many_arguments)]
 pub fn fill_vault(
     program_id: &Pubkey,
+    rewards_root: &Pubkey,
     reward_pool: &Pubkey,
     reward_mint: &Pubkey,
     vault: &Pubkey,
@@ -187,6 +465,9 @@ pub fn fill_vault(
     authority: &Pubkey,
     amount: u64,
 ) -> Instruction {
+    let (funder_contribution, _) =
+        find_funder_contribution_program_address(program_id, reward_pool, reward_mint, authority);
+
     let accounts = vec![
         AccountMeta::new(*reward_pool, false),
         AccountMeta::new_readonly(*reward_mint, false),
@@ -194,6 +475,10 @@ pub fn fill_vault(
         AccountMeta::new(*from_token_account, false),
         AccountMeta::new(*authority, true),
         AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(funder_contribution, false),
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
     ];
 
     Instruction::new_with_borsh(
@@ -203,10 +488,12 @@ pub fn fill_vault(
     )
 }
 
-/// Creates 'DepositMining' instruction.
+/// Creates 'DepositMining' instruction, the denylist of the rewards root is only passed for
+/// pools guarded by it.
 #[allow(clippy::too_many_arguments)]
 pub fn deposit_mining(
     program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
     reward_pool: &Pubkey,
     reward_pool_spl: &Pubkey,
     liquidity_mint: &Pubkey,
@@ -215,7 +502,7 @@ pub fn deposit_mining(
     user: &Pubkey,
     amount: u64,
 ) -> Instruction {
-    let accounts = vec![
+    let mut accounts = vec![
         AccountMeta::new(*reward_pool, false),
         AccountMeta::new(*reward_pool_spl, false),
         AccountMeta::new_readonly(*liquidity_mint, false),
@@ -227,6 +514,13 @@ pub fn deposit_mining(
         AccountMeta::new_readonly(clock::id(), false),
         AccountMeta::new_readonly(sysvar::rent::id(), false),
     ];
+    if let Some(denylist) = denylist {
+        accounts.push(AccountMeta::new_readonly(*denylist, false));
+    }
+    accounts.push(AccountMeta::new(
+        find_stakers_index_program_address(program_id, reward_pool).0,
+        false,
+    ));
 
     Instruction::new_with_borsh(
         *program_id,
@@ -235,6 +529,143 @@ pub fn deposit_mining(
     )
 }
 
+/// Creates 'DepositMiningWithReferrer' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_mining_referred(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    referrer: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if let Some(denylist) = denylist {
+        accounts.push(AccountMeta::new_readonly(*denylist, false));
+    }
+    accounts.extend([
+        AccountMeta::new(
+            find_referrer_program_address(program_id, reward_pool, referrer).0,
+            false,
+        ),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ]);
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::DepositMiningWithReferrer {
+            amount,
+            referrer: *referrer,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'DepositMining' instruction wrapping lamports of the user into a native pool.
+pub fn deposit_mining_native(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    deposit_mining(
+        program_id,
+        denylist,
+        reward_pool,
+        reward_pool_spl,
+        &spl_token::native_mint::id(),
+        mining,
+        user,
+        user,
+        amount,
+    )
+}
+
+/// Creates 'DepositMining' instruction which displaces the smallest position of a full competitive pool.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_mining_displacing(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    displaced_mining: &Pubkey,
+    displaced_owner_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = deposit_mining(
+        program_id,
+        denylist,
+        reward_pool,
+        reward_pool_spl,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+        amount,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new_readonly(find_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*displaced_mining, false),
+        AccountMeta::new(*displaced_owner_token_account, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'DepositMining' instruction paying the deposit fee to the fee token account.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_mining_with_fee(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    fee_token_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = deposit_mining(
+        program_id,
+        denylist,
+        reward_pool,
+        reward_pool_spl,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+        amount,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new(*fee_token_account, false));
+
+    instruction
+}
+
 /// Creates 'WithdrawMining' instruction.
 #[allow(clippy::too_many_arguments)]
 pub fn withdraw_mining(
@@ -258,11 +689,97 @@ pub fn withdraw_mining(
         AccountMeta::new_readonly(spl_token::id(), false),
         AccountMeta::new_readonly(system_program::id(), false),
         AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
     ];
 
     Instruction::new_with_borsh(*program_id, &RewardsInstruction::WithdrawMining, accounts)
 }
 
+/// Creates 'WithdrawMining' instruction unwrapping a native pool withdrawal to the user wallet.
+pub fn withdraw_mining_native(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    withdraw_mining(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        &spl_token::native_mint::id(),
+        mining,
+        &find_unwrap_program_address(program_id, reward_pool, user).0,
+        user,
+    )
+}
+
+/// Creates 'WithdrawMining' instruction paying the withdraw fee to the fee token account.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_mining_with_fee(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    fee_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = withdraw_mining(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new(*fee_token_account, false));
+
+    instruction
+}
+
+/// Creates 'WithdrawMining' instruction for a referred position settling the referral rewards.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_mining_referred(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    referrer: &Pubkey,
+) -> Instruction {
+    let mut instruction = withdraw_mining(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(
+            find_mining_program_address(program_id, referrer, reward_pool).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*referrer, false),
+    ]);
+
+    instruction
+}
+
 /// Creates 'UpgradeMining' instruction.
 #[allow(clippy::too_many_arguments)]
 pub fn upgrade_mining(
@@ -281,6 +798,7 @@ pub fn upgrade_mining(
         AccountMeta::new(*user, false),
         AccountMeta::new(*authority, true),
         AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
     ];
 
     Instruction::new_with_borsh(
@@ -310,7 +828,7 @@ pub fn claim(
 
     let accounts = vec![
         AccountMeta::new(*reward_pool, false),
-        AccountMeta::new_readonly(*reward_mint, false),
+        AccountMeta::new(*reward_mint, false),
         AccountMeta::new(*vault, false),
         AccountMeta::new(*mining, false),
         AccountMeta::new(*user, true),
@@ -323,6 +841,34 @@ pub fn claim(
     Instruction::new_with_borsh(*program_id, &RewardsInstruction::Claim, accounts)
 }
 
+/// Creates 'Claim' instruction paying the claim fee to the treasury token account.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_with_fee(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_mint: &Pubkey,
+    vault: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    user_reward_token: &Pubkey,
+    fee_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = claim(
+        program_id,
+        reward_pool,
+        reward_mint,
+        vault,
+        mining,
+        user,
+        user_reward_token,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new(*fee_token_account, false));
+
+    instruction
+}
+
 /// Creates 'InitializeRoot' instruction.
 pub fn initialize_root(
     program_id: &Pubkey,
@@ -346,8 +892,6 @@ pub fn migrate_pool(
     reward_pool: &Pubkey,
     payer: &Pubkey,
     liquidity_mint: &Pubkey,
-    max_stakers: u64,
-    total_stakers: u64,
 ) -> Instruction {
     let accounts = vec![
         AccountMeta::new_readonly(*root_account, false),
@@ -358,7 +902,7 @@ pub fn migrate_pool(
         AccountMeta::new_readonly(sysvar::rent::id(), false),
     ];
 
-    Instruction::new_with_borsh(*program_id, &RewardsInstruction::MigratePool{ max_stakers, total_stakers }, accounts)
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::MigratePool, accounts)
 }
 
 /// Creates 'MigrateMining' instruction.
@@ -373,7 +917,7 @@ pub fn migrate_mining(
     let accounts = vec![
         AccountMeta::new(*mining, false),
         AccountMeta::new_readonly(*root_account, false),
-        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*reward_pool, false),
         AccountMeta::new_readonly(*liquidity_mint, false),
         AccountMeta::new(*payer, true),
         AccountMeta::new_readonly(system_program::id(), false),
@@ -382,3 +926,1186 @@ pub fn migrate_mining(
 
     Instruction::new_with_borsh(*program_id, &RewardsInstruction::MigrateMining, accounts)
 }
+
+/// Creates 'ResizePool' instruction.
+pub fn resize_pool(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    vaults_capacity: u8,
+    tiers_capacity: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::ResizePool {
+            vaults_capacity,
+            tiers_capacity,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'UpdatePool' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn update_pool(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    lock_time_sec: Option<u64>,
+    max_stakers: Option<u64>,
+    min_deposit_amount: Option<u64>,
+    max_deposit_amount: Option<u64>,
+    min_stake_amount: Option<u64>,
+    max_stake_amount: Option<u64>,
+    unbonding_period_sec: Option<u64>,
+    is_linear_unbonding: Option<bool>,
+    status: Option<PoolStatus>,
+    referral_share_bps: Option<u16>,
+    warmup_period_sec: Option<u64>,
+    warmup_rate_bps: Option<u16>,
+    max_funder_lock_sec: Option<u64>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::UpdatePool {
+            lock_time_sec,
+            max_stakers,
+            min_deposit_amount,
+            max_deposit_amount,
+            min_stake_amount,
+            max_stake_amount,
+            unbonding_period_sec,
+            is_linear_unbonding,
+            status,
+            referral_share_bps,
+            warmup_period_sec,
+            warmup_rate_bps,
+            max_funder_lock_sec,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'EvictDust' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn evict_dust(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner_token_account: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*reward_pool_authority, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*owner_token_account, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::EvictDust, accounts)
+}
+
+/// Creates 'EvictDust' instruction for a tokenized position.
+#[allow(clippy::too_many_arguments)]
+pub fn evict_dust_tokenized(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    holder_liquidity_account: &Pubkey,
+    payer: &Pubkey,
+    holder_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = evict_dust(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        holder_liquidity_account,
+        payer,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*holder_token_account, false));
+
+    instruction
+}
+
+/// Creates 'InitializeStakersIndex' instruction.
+pub fn initialize_stakers_index(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::InitializeStakersIndex,
+        accounts,
+    )
+}
+
+/// Creates 'RequestUnstake' instruction.
+pub fn request_unstake(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::RequestUnstake { amount },
+        accounts,
+    )
+}
+
+/// Creates 'FinalizeUnstake' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn finalize_unstake(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*reward_pool_authority, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::FinalizeUnstake, accounts)
+}
+
+/// Creates 'FinalizeUnstake' instruction paying the withdraw fee to the fee token account.
+#[allow(clippy::too_many_arguments)]
+pub fn finalize_unstake_with_fee(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    fee_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = finalize_unstake(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new(*fee_token_account, false));
+
+    instruction
+}
+
+/// Creates 'CancelUnstake' instruction.
+pub fn cancel_unstake(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::CancelUnstake, accounts)
+}
+
+/// Creates 'SetPoolFees' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn set_pool_fees(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    treasury: &Pubkey,
+    deposit_fee_bps: u16,
+    withdraw_fee_bps: u16,
+    claim_fee_bps: u16,
+    is_fee_to_vault: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::SetPoolFees {
+            treasury: *treasury,
+            deposit_fee_bps,
+            withdraw_fee_bps,
+            claim_fee_bps,
+            is_fee_to_vault,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'SettleReferralRewards' instruction.
+pub fn settle_referral_rewards(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    referrer: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(
+            find_mining_program_address(program_id, referrer, reward_pool).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*referrer, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::SettleReferralRewards,
+        accounts,
+    )
+}
+
+/// Creates 'AddBoostSource' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn add_boost_source(
+    program_id: &Pubkey,
+    rewards_root: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    index: u8,
+    kind: BoostKind,
+    multiplier_bps: u16,
+    min_amount: u64,
+    mints: Vec<Pubkey>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(
+            find_boost_source_program_address(program_id, reward_pool, index).0,
+            false,
+        ),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::AddBoostSource {
+            kind,
+            multiplier_bps,
+            min_amount,
+            mints,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'UpdateBoostSource' instruction.
+pub fn update_boost_source(
+    program_id: &Pubkey,
+    rewards_root: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    index: u8,
+    multiplier_bps: Option<u16>,
+    min_amount: Option<u64>,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(
+            find_boost_source_program_address(program_id, reward_pool, index).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::UpdateBoostSource {
+            multiplier_bps,
+            min_amount,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'RefreshBoost' instruction.
+///
+/// `holder_token_accounts` go in the order of the pool boost sources.
+pub fn refresh_boost(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    payer: &Pubkey,
+    holder_token_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+    for (index, holder_token_account) in holder_token_accounts.iter().enumerate() {
+        accounts.push(AccountMeta::new_readonly(
+            find_boost_source_program_address(program_id, reward_pool, index as u8).0,
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*holder_token_account, false));
+    }
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::RefreshBoost, accounts)
+}
+
+/// Creates 'SetTierPrices' instruction.
+pub fn set_tier_prices(
+    program_id: &Pubkey,
+    rewards_root: &Pubkey,
+    reward_pool: &Pubkey,
+    burn_mint: &Pubkey,
+    authority: &Pubkey,
+    prices: Vec<u64>,
+    tier_duration_sec: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(*burn_mint, false),
+        AccountMeta::new_readonly(*authority, true),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::SetTierPrices {
+            prices,
+            tier_duration_sec,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'BuyTier' instruction.
+pub fn buy_tier(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    burn_mint: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    tier: u8,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*burn_mint, false),
+        AccountMeta::new(*user_token_account, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::BuyTier { tier },
+        accounts,
+    )
+}
+
+/// Creates 'InitializeReceiptMint' instruction.
+pub fn initialize_receipt_mint(
+    program_id: &Pubkey,
+    rewards_root: &Pubkey,
+    reward_pool: &Pubkey,
+    liquidity_mint: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*rewards_root, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(find_receipt_mint_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::InitializeReceiptMint,
+        accounts,
+    )
+}
+
+/// Creates 'DepositMining' instruction minting receipts to the user receipt token account.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_mining_with_receipt(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    user_receipt_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = deposit_mining(
+        program_id,
+        denylist,
+        reward_pool,
+        reward_pool_spl,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+        amount,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(find_receipt_mint_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*user_receipt_account, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'WithdrawMining' instruction burning receipts from the user receipt token account.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_mining_with_receipt(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    user_receipt_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = withdraw_mining(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(find_receipt_mint_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*user_receipt_account, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'RequestUnstake' instruction burning receipts from the user receipt token account.
+pub fn request_unstake_with_receipt(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    user_receipt_account: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut instruction = request_unstake(program_id, reward_pool, mining, user, amount);
+    instruction.accounts.extend([
+        AccountMeta::new(find_receipt_mint_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*user_receipt_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'CancelUnstake' instruction minting receipts back to the user receipt token account.
+pub fn cancel_unstake_with_receipt(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    user_receipt_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = cancel_unstake(program_id, reward_pool, mining, user);
+    instruction.accounts.extend([
+        AccountMeta::new(find_receipt_mint_program_address(program_id, reward_pool).0, false),
+        AccountMeta::new(*user_receipt_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'SyncReceipt' instruction.
+pub fn sync_receipt(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    owner: &Pubkey,
+    owner_receipt_account: &Pubkey,
+    payer: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(
+            find_receipt_mint_program_address(program_id, reward_pool).0,
+            false,
+        ),
+        AccountMeta::new(find_mining_program_address(program_id, owner, reward_pool).0, false),
+        AccountMeta::new_readonly(*owner_receipt_account, false),
+        AccountMeta::new_readonly(*owner, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::SyncReceipt, accounts)
+}
+
+/// Creates 'OpenPosition' instruction.
+pub fn open_position(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    user: &Pubkey,
+    index: u16,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(
+            find_mining_position_address(program_id, user, reward_pool, index).0,
+            false,
+        ),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::OpenPosition { index },
+        accounts,
+    )
+}
+
+/// Creates 'SplitPosition' instruction.
+pub fn split_position(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    source_mining: &Pubkey,
+    destination_mining: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*source_mining, false),
+        AccountMeta::new(*destination_mining, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::SplitPosition { amount },
+        accounts,
+    )
+}
+
+/// Creates 'MergePosition' instruction.
+pub fn merge_position(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    source_mining: &Pubkey,
+    destination_mining: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*source_mining, false),
+        AccountMeta::new(*destination_mining, false),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::MergePosition, accounts)
+}
+
+/// Creates 'TokenizePosition' instruction.
+pub fn tokenize_position(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    user_position_account: &Pubkey,
+    user: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(find_position_mint_program_address(program_id, mining).0, false),
+        AccountMeta::new(*user_position_account, true),
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::TokenizePosition, accounts)
+}
+
+/// Creates 'Claim' instruction for the holder of a tokenized position.
+#[allow(clippy::too_many_arguments)]
+pub fn claim_tokenized(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_mint: &Pubkey,
+    vault: &Pubkey,
+    mining: &Pubkey,
+    user: &Pubkey,
+    user_reward_token: &Pubkey,
+    holder_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = claim(
+        program_id,
+        reward_pool,
+        reward_mint,
+        vault,
+        mining,
+        user,
+        user_reward_token,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*holder_token_account, false));
+
+    instruction
+}
+
+/// Creates 'WithdrawMining' instruction for the holder of a tokenized position.
+#[allow(clippy::too_many_arguments)]
+pub fn withdraw_mining_tokenized(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    user_token_account: &Pubkey,
+    user: &Pubkey,
+    holder_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = withdraw_mining(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        user_token_account,
+        user,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(find_position_mint_program_address(program_id, mining).0, false),
+        AccountMeta::new(*holder_token_account, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'StartPoolClosing' instruction.
+pub fn start_pool_closing(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    authority: &Pubkey,
+    grace_period_sec: u64,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(clock::id(), false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::StartPoolClosing { grace_period_sec },
+        accounts,
+    )
+}
+
+/// Creates 'ForceUnstake' instruction, rewards are pairs of the reward mint and the owner
+/// reward account in the pool order.
+#[allow(clippy::too_many_arguments)]
+pub fn force_unstake(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    owner_token_account: &Pubkey,
+    payer: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*reward_pool_authority, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new(*owner_token_account, false),
+        AccountMeta::new(*payer, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+    for (reward_mint, destination) in rewards {
+        let (vault, _) = find_vault_spl_token_account(program_id, reward_pool, reward_mint);
+        accounts.push(AccountMeta::new(vault, false));
+        accounts.push(AccountMeta::new(*reward_mint, false));
+        accounts.push(AccountMeta::new(*destination, false));
+    }
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::ForceUnstake, accounts)
+}
+
+/// Creates 'ForceUnstake' instruction for a tokenized position.
+#[allow(clippy::too_many_arguments)]
+pub fn force_unstake_tokenized(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    holder_liquidity_account: &Pubkey,
+    payer: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+    holder_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = force_unstake(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        owner,
+        holder_liquidity_account,
+        payer,
+        rewards,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*holder_token_account, false));
+
+    instruction
+}
+
+/// Creates 'ForceUnstake' instruction for a referred position settling the referral rewards.
+#[allow(clippy::too_many_arguments)]
+pub fn force_unstake_referred(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    owner_token_account: &Pubkey,
+    payer: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+    referrer: &Pubkey,
+) -> Instruction {
+    let mut instruction = force_unstake(
+        program_id,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        owner,
+        owner_token_account,
+        payer,
+        rewards,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(
+            find_mining_program_address(program_id, referrer, reward_pool).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*referrer, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'ClosePool' instruction, vaults are pairs of the reward mint and its sweep
+/// destination in the pool order.
+#[allow(clippy::too_many_arguments)]
+pub fn close_pool(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    authority: &Pubkey,
+    authority_token_account: &Pubkey,
+    vaults: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*reward_pool_authority, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new(*authority_token_account, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+    for (reward_mint, destination) in vaults {
+        let (vault, _) = find_vault_spl_token_account(program_id, reward_pool, reward_mint);
+        accounts.push(AccountMeta::new(vault, false));
+        accounts.push(AccountMeta::new(*reward_mint, false));
+        accounts.push(AccountMeta::new(*destination, false));
+    }
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::ClosePool, accounts)
+}
+
+/// Creates 'ForceWithdraw' instruction, rewards are pairs of the reward mint and the owner
+/// reward account in the pool order.
+#[allow(clippy::too_many_arguments)]
+pub fn force_withdraw(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    owner_token_account: &Pubkey,
+    authority: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*reward_pool_authority, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*owner, false),
+        AccountMeta::new(*owner_token_account, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+    for (reward_mint, destination) in rewards {
+        let (vault, _) = find_vault_spl_token_account(program_id, reward_pool, reward_mint);
+        accounts.push(AccountMeta::new(vault, false));
+        accounts.push(AccountMeta::new(*reward_mint, false));
+        accounts.push(AccountMeta::new(*destination, false));
+    }
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::ForceWithdraw, accounts)
+}
+
+/// Creates 'ForceWithdraw' instruction for a tokenized position.
+#[allow(clippy::too_many_arguments)]
+pub fn force_withdraw_tokenized(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    holder_liquidity_account: &Pubkey,
+    authority: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+    holder_token_account: &Pubkey,
+) -> Instruction {
+    let mut instruction = force_withdraw(
+        program_id,
+        root_account,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        owner,
+        holder_liquidity_account,
+        authority,
+        rewards,
+    );
+    instruction
+        .accounts
+        .push(AccountMeta::new_readonly(*holder_token_account, false));
+
+    instruction
+}
+
+/// Creates 'ForceWithdraw' instruction for a referred position settling the referral rewards.
+#[allow(clippy::too_many_arguments)]
+pub fn force_withdraw_referred(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    reward_pool_authority: &Pubkey,
+    liquidity_mint: &Pubkey,
+    mining: &Pubkey,
+    owner: &Pubkey,
+    owner_token_account: &Pubkey,
+    authority: &Pubkey,
+    rewards: &[(Pubkey, Pubkey)],
+    referrer: &Pubkey,
+) -> Instruction {
+    let mut instruction = force_withdraw(
+        program_id,
+        root_account,
+        reward_pool,
+        reward_pool_spl,
+        reward_pool_authority,
+        liquidity_mint,
+        mining,
+        owner,
+        owner_token_account,
+        authority,
+        rewards,
+    );
+    instruction.accounts.extend([
+        AccountMeta::new(
+            find_mining_program_address(program_id, referrer, reward_pool).0,
+            false,
+        ),
+        AccountMeta::new_readonly(*referrer, false),
+    ]);
+
+    instruction
+}
+
+/// Creates 'FreezeMining' instruction.
+pub fn freeze_mining(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    authority: &Pubkey,
+    reason: u8,
+    pause_accrual: bool,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::FreezeMining {
+            reason,
+            pause_accrual,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'UnfreezeMining' instruction.
+pub fn unfreeze_mining(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    reward_pool: &Pubkey,
+    mining: &Pubkey,
+    authority: &Pubkey,
+) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new_readonly(*reward_pool, false),
+        AccountMeta::new(*mining, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new(find_stakers_index_program_address(program_id, reward_pool).0, false),
+    ];
+
+    Instruction::new_with_borsh(*program_id, &RewardsInstruction::UnfreezeMining, accounts)
+}
+
+/// Creates 'UpdateDenylist' instruction, deposits into the reward pools are checked against
+/// the denylist from then on.
+pub fn update_denylist(
+    program_id: &Pubkey,
+    root_account: &Pubkey,
+    authority: &Pubkey,
+    wallet: &Pubkey,
+    is_denied: bool,
+    reward_pools: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*root_account, false),
+        AccountMeta::new(find_denylist_program_address(program_id, root_account).0, false),
+        AccountMeta::new(*authority, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    accounts.extend(
+        reward_pools
+            .iter()
+            .map(|reward_pool| AccountMeta::new(*reward_pool, false)),
+    );
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::UpdateDenylist {
+            wallet: *wallet,
+            is_denied,
+        },
+        accounts,
+    )
+}
+
+/// Creates 'DepositFor' instruction.
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_for(
+    program_id: &Pubkey,
+    denylist: Option<&Pubkey>,
+    reward_pool: &Pubkey,
+    reward_pool_spl: &Pubkey,
+    liquidity_mint: &Pubkey,
+    funder_token_account: &Pubkey,
+    funder: &Pubkey,
+    beneficiary: &Pubkey,
+    amount: u64,
+    min_lock_sec: Option<u64>,
+) -> Instruction {
+    let (mining, _) = find_mining_program_address(program_id, beneficiary, reward_pool);
+    let mut accounts = vec![
+        AccountMeta::new(*reward_pool, false),
+        AccountMeta::new(*reward_pool_spl, false),
+        AccountMeta::new_readonly(*liquidity_mint, false),
+        AccountMeta::new(mining, false),
+        AccountMeta::new(*funder_token_account, false),
+        AccountMeta::new(*funder, true),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(clock::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ];
+    if let Some(denylist) = denylist {
+        accounts.push(AccountMeta::new_readonly(*denylist, false));
+    }
+    accounts.push(AccountMeta::new(
+        find_stakers_index_program_address(program_id, reward_pool).0,
+        false,
+    ));
+
+    Instruction::new_with_borsh(
+        *program_id,
+        &RewardsInstruction::DepositFor {
+            beneficiary: *beneficiary,
+            amount,
+            min_lock_sec,
+        },
+        accounts,
+    )
+}
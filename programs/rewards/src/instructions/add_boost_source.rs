@@ -0,0 +1,106 @@
+use crate::find_boost_source_program_address;
+use crate::state::{BoostKind, BoostSource, RewardPool, RewardsRoot, ZeroCopy, MAX_BOOST_SOURCES};
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError, BPS_SCALER};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::Sysvar;
+
+/// Instruction context
+pub struct AddBoostSourceContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    boost_source: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> AddBoostSourceContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<AddBoostSourceContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let boost_source = AccountLoader::next_uninitialized(account_info_iter)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+
+        Ok(AddBoostSourceContext {
+            rewards_root,
+            reward_pool,
+            boost_source,
+            authority,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(
+        &self,
+        program_id: &Pubkey,
+        kind: BoostKind,
+        multiplier_bps: u16,
+        min_amount: u64,
+        mints: Vec<Pubkey>,
+    ) -> ProgramResult {
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        if multiplier_bps as u128 > BPS_SCALER {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let index = reward_pool.boost_sources_len;
+        if index >= MAX_BOOST_SOURCES {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let (boost_source_pubkey, bump) =
+            find_boost_source_program_address(program_id, self.reward_pool.key, index);
+        assert_account_key(self.boost_source, &boost_source_pubkey)?;
+
+        let boost_source = BoostSource::initialize(
+            *self.reward_pool.key,
+            bump,
+            index,
+            kind,
+            multiplier_bps,
+            min_amount,
+            &mints,
+        )?;
+
+        let signers_seeds = &[
+            "boost".as_bytes(),
+            &self.reward_pool.key.to_bytes(),
+            &[index],
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.authority.clone(),
+            self.boost_source.clone(),
+            &[signers_seeds],
+            &Rent::get()?,
+            BoostSource::LEN,
+        )?;
+
+        *BoostSource::load_unchecked_mut(&mut self.boost_source.data.borrow_mut())? = boost_source;
+
+        reward_pool.boost_sources_len = index.checked_add(1).ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+}
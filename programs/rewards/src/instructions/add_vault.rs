@@ -1,17 +1,18 @@
 use crate::find_vault_spl_token_account;
-use everlend_utils::{assert_account_key, AccountLoader};
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError};
 use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
+use solana_program::program_option::COption;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::system_program;
 use solana_program::sysvar::{clock, Sysvar, SysvarId};
-use spl_token::state::Account;
+use spl_token::state::{Account, Mint};
 
-use crate::state::{RewardPool, RewardTier, RewardVault, RewardsRoot};
+use crate::state::{RewardPool, RewardTier, RewardVault, RewardsRoot, VaultKind, ZeroCopy};
 
 /// Instruction context
 pub struct AddVaultContext<'a, 'b> {
@@ -61,9 +62,13 @@ impl<'a, 'b> AddVaultContext<'a, 'b> {
         reward_period_sec: u32,
         is_enabled: bool,
         reward_tiers: Vec<RewardTier>,
+        kind: VaultKind,
     ) -> ProgramResult {
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
-        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+        }
 
         {
             let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
@@ -83,23 +88,57 @@ impl<'a, 'b> AddVaultContext<'a, 'b> {
 
         let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
 
-        self.create_spl_acc(vault_bump)?;
+        match kind {
+            VaultKind::Native => {
+                assert_account_key(self.reward_mint, &spl_token::native_mint::id())?;
+                self.create_native_acc(program_id, vault_bump)?;
+            }
+            VaultKind::Mint => {
+                let mint = Mint::unpack(&self.reward_mint.data.borrow())?;
+                if mint.mint_authority != COption::Some(*self.reward_pool.key) {
+                    return Err(EverlendError::InvalidMintAuthority.into());
+                }
+                self.create_spl_acc(vault_bump)?;
+            }
+            VaultKind::Transfer => self.create_spl_acc(vault_bump)?,
+        }
 
-        reward_pool.add_vault(RewardVault {
+        let vault = RewardVault {
             vault_token_account_bump: vault_bump,
             reward_period_sec,
             reward_mint: *self.reward_mint.key,
-            is_enabled,
+            is_enabled: is_enabled.into(),
             claimed_total_amount: 0,
             enabled_at: if is_enabled { timestamp as u64 } else { 0 },
-            reward_tiers,
-        })?;
+            kind: kind as u8,
+            ..Default::default()
+        };
 
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        RewardPool::load_account_mut(&mut reward_pool_data)?.add_vault(vault, &reward_tiers)?;
 
         Ok(())
     }
 
+    /// creates program owned vault account holding lamports
+    pub fn create_native_acc(&self, program_id: &Pubkey, bump: u8) -> ProgramResult {
+        let signers_seeds = &[
+            b"vault".as_ref(),
+            self.reward_pool.key.as_ref(),
+            self.reward_mint.key.as_ref(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.payer.clone(),
+            self.vault.clone(),
+            &[signers_seeds],
+            &Rent::from_account_info(self.rent)?,
+            0,
+        )
+    }
+
     /// creates vault spl token account
     pub fn create_spl_acc(&self, bump: u8) -> ProgramResult {
         let signers_seeds = &[
@@ -0,0 +1,142 @@
+use crate::events::RewardsEvent;
+use crate::state::{Mining, RewardPool};
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use std::cmp;
+
+/// Instruction context
+pub struct BuyTierContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    burn_mint: &'a AccountInfo<'b>,
+    user_token_account: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> BuyTierContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<BuyTierContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let burn_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let user_token_account =
+            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        Ok(BuyTierContext {
+            reward_pool,
+            mining,
+            burn_mint,
+            user_token_account,
+            user,
+            clock,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey, tier: u8) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.user, &Rent::get()?, mining_len)?;
+        }
+
+        let (price, tier_expires_at) = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load_account(&reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            {
+                assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+                assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+                assert_account_key(self.user, &mining.mining.owner)?;
+                assert_account_key(self.burn_mint, &reward_pool.pool.burn_mint)?;
+            }
+
+            let price = reward_pool
+                .pool
+                .tier_price(tier)
+                .ok_or(ProgramError::InvalidArgument)?;
+
+            // settle rewards at the current tier, an expired tier falls back to the base one
+            mining.refresh_rewards(reward_pool.iter_vaults(), timestamp)?;
+
+            // a bought tier can be extended, a permanent one can only be upgraded
+            let is_extension =
+                tier == mining.mining.reward_tier && mining.mining.tier_expires_at != 0;
+            if tier < mining.mining.reward_tier
+                || (tier == mining.mining.reward_tier && !is_extension)
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            let tier_duration_sec = reward_pool.pool.tier_duration_sec;
+            let tier_expires_at = if tier_duration_sec == 0 {
+                0
+            } else {
+                let starts_at = if is_extension {
+                    cmp::max(mining.mining.tier_expires_at, timestamp)
+                } else {
+                    timestamp
+                };
+                starts_at
+                    .checked_add(tier_duration_sec)
+                    .ok_or(EverlendError::MathOverflow)?
+            };
+
+            mining.mining.reward_tier = tier;
+            mining.mining.tier_expires_at = tier_expires_at;
+            // tiers bought without a duration are kept
+            if tier_expires_at == 0 {
+                mining.mining.base_tier = tier;
+            }
+
+            (price, tier_expires_at)
+        };
+
+        everlend_utils::cpi::spl_token::burn(
+            self.burn_mint.clone(),
+            self.user_token_account.clone(),
+            self.user.clone(),
+            price,
+            &[],
+        )?;
+
+        RewardsEvent::TierPurchased {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            tier,
+            burned_amount: price,
+            tier_expires_at,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
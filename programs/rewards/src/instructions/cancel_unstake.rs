@@ -0,0 +1,173 @@
+use crate::state::{Mining, RewardPool, StakersIndex, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+
+/// Instruction context
+pub struct CancelUnstakeContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    receipt: Option<ReceiptAccounts<'a, 'b>>,
+}
+
+/// Receipt mint and receipt associated token account of the user
+struct ReceiptAccounts<'a, 'b> {
+    receipt_mint: &'a AccountInfo<'b>,
+    user_receipt_account: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> CancelUnstakeContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<CancelUnstakeContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        // receipt pools take the receipt accounts last
+        let receipt_accounts = {
+            let reward_pool_data = reward_pool.data.borrow();
+            RewardPool::load(&reward_pool_data)?.has_receipt() as usize * 3
+        };
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter) > receipt_accounts {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+        let receipt = if receipt_accounts > 0 {
+            let receipt = ReceiptAccounts {
+                receipt_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                user_receipt_account: AccountLoader::next_with_owner(
+                    account_info_iter,
+                    &spl_token::id(),
+                )?,
+            };
+            let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+            Some(receipt)
+        } else {
+            None
+        };
+
+        Ok(CancelUnstakeContext {
+            reward_pool,
+            mining,
+            user,
+            clock,
+            stakers_index,
+            receipt,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.user, &Rent::get()?, mining_len)?;
+        }
+
+        let amount = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            {
+                assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+                assert_account_key(self.user, &mining.mining.owner)?;
+                assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+            }
+
+            let amount = mining.mining.unbonding_amount;
+            reward_pool.cancel_unstake(&mut mining, timestamp)?;
+
+            if bool::from(reward_pool.pool.is_competitive) {
+                let stakers_index = self
+                    .stakers_index
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let stakers_index_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"stakers".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        &[reward_pool.pool.stakers_index_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+                StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                    .upsert(*self.mining.key, mining.mining.amount)?;
+            }
+
+            amount
+        };
+
+        self.mint_receipt(amount)
+    }
+
+    /// Mints receipts of the staked amount back to the user in a receipt pool
+    fn mint_receipt(&self, amount: u64) -> ProgramResult {
+        let receipt = match self.receipt.as_ref() {
+            Some(receipt) => receipt,
+            None => return Ok(()),
+        };
+
+        let (rewards_root, liquidity_mint, bump) = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(receipt.receipt_mint, &reward_pool.receipt_mint)?;
+
+            let (user_receipt_account, _) =
+                find_associated_token_account(self.user.key, &reward_pool.receipt_mint);
+            assert_account_key(receipt.user_receipt_account, &user_receipt_account)?;
+
+            (
+                reward_pool.rewards_root,
+                reward_pool.liquidity_mint,
+                reward_pool.bump,
+            )
+        };
+
+        let reward_pool_seeds = &[
+            b"reward_pool".as_ref(),
+            rewards_root.as_ref(),
+            liquidity_mint.as_ref(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::spl_token::mint_to(
+            receipt.receipt_mint.clone(),
+            receipt.user_receipt_account.clone(),
+            self.reward_pool.clone(),
+            amount,
+            &[reward_pool_seeds],
+        )
+    }
+}
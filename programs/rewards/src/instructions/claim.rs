@@ -1,13 +1,19 @@
-use crate::state::{Mining, RewardPool};
-use everlend_utils::{assert_account_key, AccountLoader};
+use crate::state::{Mining, RewardPool, VaultKind, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{
+    assert_account_key, assert_owned_by, bps_floor, AccountLoader, EverlendError,
+};
 use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
 use solana_program::system_program;
 use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::Account;
 
 /// Instruction context
 pub struct ClaimContext<'a, 'b> {
@@ -18,6 +24,8 @@ pub struct ClaimContext<'a, 'b> {
     user: &'a AccountInfo<'b>,
     user_reward_token_account: &'a AccountInfo<'b>,
     clock: &'a AccountInfo<'b>,
+    holder_token_account: Option<&'a AccountInfo<'b>>,
+    fee_token_account: Option<&'a AccountInfo<'b>>,
 }
 
 impl<'a, 'b> ClaimContext<'a, 'b> {
@@ -30,16 +38,37 @@ impl<'a, 'b> ClaimContext<'a, 'b> {
 
         let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
         let reward_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
-        let vault = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        // token accounts or lamports accounts depending on the vault kind
+        let vault = AccountLoader::next_unchecked(account_info_iter)?;
         let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
         let user = AccountLoader::next_signer(account_info_iter)?;
-        let user_reward_token_account =
-            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let user_reward_token_account = AccountLoader::next_unchecked(account_info_iter)?;
         let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
         let _system_program =
             AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
         let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
 
+        // tokenized positions only
+        let holder_token_account = if bool::from(Mining::load(&mining.data.borrow())?.is_tokenized)
+        {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+
+        // pools with claim fee only
+        let fee_token_account = if AccountLoader::has_more(account_info_iter) {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+
         Ok(ClaimContext {
             reward_pool,
             reward_mint,
@@ -48,84 +77,234 @@ impl<'a, 'b> ClaimContext<'a, 'b> {
             user,
             user_reward_token_account,
             clock,
+            holder_token_account,
+            fee_token_account,
         })
     }
 
     /// Process instruction
     pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
         let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
-        let mut mining = Mining::unpack(&self.mining.data.borrow())?;
 
-        {
-            let mining_pubkey = Pubkey::create_program_address(
-                &[
-                    b"mining".as_ref(),
-                    self.user.key.as_ref(),
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.user, &Rent::get()?, mining_len)?;
+        }
+
+        let (reward_amount, fee, vault_kind, rewards_root, liquidity_mint, bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+
+            {
+                let reward_pool_seeds = &[
+                    b"reward_pool".as_ref(),
+                    reward_pool.pool.rewards_root.as_ref(),
+                    reward_pool.pool.liquidity_mint.as_ref(),
+                    &[reward_pool.pool.bump],
+                ];
+
+                self.assert_controller(program_id, mining.mining)?;
+                mining.mining.assert_not_frozen()?;
+                assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+                assert_account_key(
+                    self.reward_pool,
+                    &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+                )?;
+
+                let bump = reward_pool
+                    .to_ref()
+                    .find_vault(self.reward_mint.key)
+                    .ok_or(ProgramError::InvalidArgument)?
+                    .vault_token_account_bump;
+
+                let vault_seeds = &[
+                    b"vault".as_ref(),
                     self.reward_pool.key.as_ref(),
-                    &[mining.bump],
-                ],
-                program_id,
+                    self.reward_mint.key.as_ref(),
+                    &[bump],
+                ];
+
+                assert_account_key(
+                    self.vault,
+                    &Pubkey::create_program_address(vault_seeds, program_id)?,
+                )?;
+            }
+
+            mining.refresh_rewards(reward_pool.to_ref().iter_vaults(), timestamp as u64)?;
+
+            // mint vaults pay out up to their caps, the rest stays accrued
+            let vault = reward_pool
+                .to_ref()
+                .find_vault(self.reward_mint.key)
+                .ok_or(ProgramError::InvalidArgument)?;
+            let vault_kind = vault.vault_kind();
+            let reward_amount = mining.flush_rewards(
+                *self.reward_mint.key,
+                vault.claim_allowance(timestamp as u64),
             )?;
-            assert_account_key(self.mining, &mining_pubkey)?;
-        }
+            reward_pool.update_vault_totals(
+                *self.reward_mint.key,
+                reward_amount,
+                timestamp as u64,
+            )?;
+
+            let fee = bps_floor(reward_amount, reward_pool.pool.claim_fee_bps)?;
+            if fee > 0 {
+                let fee_token_account = self
+                    .fee_token_account
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let (fee_pubkey, _) =
+                    find_associated_token_account(&reward_pool.pool.treasury, self.reward_mint.key);
+                assert_account_key(fee_token_account, &fee_pubkey)?;
+            }
+            reward_pool.record_claim_fee(*self.reward_mint.key, fee)?;
+
+            (
+                reward_amount,
+                fee,
+                vault_kind,
+                reward_pool.pool.rewards_root,
+                reward_pool.pool.liquidity_mint,
+                reward_pool.pool.bump,
+            )
+        };
 
         let reward_pool_seeds = &[
             b"reward_pool".as_ref(),
-            &reward_pool.rewards_root.to_bytes()[..32],
-            &reward_pool.liquidity_mint.to_bytes()[..32],
-            &[reward_pool.bump],
+            rewards_root.as_ref(),
+            liquidity_mint.as_ref(),
+            &[bump],
         ];
+        let user_amount = reward_amount
+            .checked_sub(fee)
+            .ok_or(EverlendError::MathOverflow)?;
+        self.pay_reward(
+            program_id,
+            vault_kind,
+            self.user_reward_token_account,
+            user_amount,
+            reward_pool_seeds,
+        )?;
 
-        {
-            assert_account_key(self.user, &mining.owner)?;
-            assert_account_key(self.reward_pool, &mining.reward_pool)?;
-            assert_account_key(
-                self.reward_pool,
-                &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+        if let Some(fee_token_account) = self.fee_token_account.filter(|_| fee > 0) {
+            self.pay_reward(
+                program_id,
+                vault_kind,
+                fee_token_account,
+                fee,
+                reward_pool_seeds,
             )?;
+            // treasury takes lamports fee as wrapped SOL
+            if vault_kind == VaultKind::Native {
+                everlend_utils::cpi::spl_token::sync_native(fee_token_account.clone())?;
+            }
+        }
 
-            let bump = reward_pool
-                .vaults
-                .iter()
-                .find(|v| &v.reward_mint == self.reward_mint.key)
-                .ok_or(ProgramError::InvalidArgument)?
-                .vault_token_account_bump;
-
-            let vault_seeds = &[
-                b"vault".as_ref(),
-                &self.reward_pool.key.to_bytes()[..32],
-                &self.reward_mint.key.to_bytes()[..32],
-                &[bump],
-            ];
-
-            assert_account_key(
-                self.vault,
-                &Pubkey::create_program_address(vault_seeds, program_id)?,
-            )?;
+        Ok(())
+    }
+
+    /// Checks the user owns the position or holds its NFT
+    fn assert_controller(&self, program_id: &Pubkey, mining: &Mining) -> ProgramResult {
+        if !bool::from(mining.is_tokenized) {
+            return assert_account_key(self.user, &mining.owner);
         }
 
-        mining.refresh_rewards(reward_pool.vaults.iter(), timestamp as u64)?;
-        let reward_amount = mining.flush_rewards(*self.reward_mint.key)?;
-        reward_pool.update_vault_totals(*self.reward_mint.key, reward_amount)?;
+        let holder_token_account = self
+            .holder_token_account
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        mining.assert_holder(
+            program_id,
+            self.mining.key,
+            self.user.key,
+            &Account::unpack(&holder_token_account.data.borrow())?,
+        )
+    }
+
+    /// pay reward from the vault depending on its kind
+    fn pay_reward(
+        &self,
+        program_id: &Pubkey,
+        vault_kind: VaultKind,
+        destination: &'a AccountInfo<'b>,
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        match vault_kind {
+            VaultKind::Transfer => self.spl_transfer_reward(destination, amount, seeds),
+            VaultKind::Mint => self.spl_mint_reward(destination, amount, seeds),
+            VaultKind::Native => self.native_transfer_reward(program_id, destination, amount),
+        }
+    }
 
-        self.spl_transfer_reward(reward_amount, reward_pool_seeds)?;
+    /// transfer lamports reward to user
+    pub fn native_transfer_reward(
+        &self,
+        program_id: &Pubkey,
+        destination: &'a AccountInfo<'b>,
+        amount: u64,
+    ) -> ProgramResult {
+        assert_owned_by(self.vault, program_id)?;
+        if amount == 0 {
+            return Ok(());
+        }
 
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
-        Mining::pack(mining, *self.mining.data.borrow_mut())?;
+        everlend_utils::cpi::system::withdraw_lamports(
+            self.vault,
+            destination,
+            amount,
+            &Rent::get()?,
+        )
+    }
+
+    /// mint reward to user
+    pub fn spl_mint_reward(
+        &self,
+        destination: &'a AccountInfo<'b>,
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        assert_owned_by(destination, &spl_token::id())?;
+        if amount == 0 {
+            return Ok(());
+        }
+
+        everlend_utils::cpi::spl_token::mint_to(
+            self.reward_mint.clone(),
+            destination.clone(),
+            self.reward_pool.clone(),
+            amount,
+            &[seeds],
+        )?;
 
         Ok(())
     }
 
     /// create reward token account for user and transfer reward
-    pub fn spl_transfer_reward(&self, amount: u64, seeds: &[&[u8]]) -> ProgramResult {
+    pub fn spl_transfer_reward(
+        &self,
+        destination: &'a AccountInfo<'b>,
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        assert_owned_by(self.vault, &spl_token::id())?;
+        assert_owned_by(destination, &spl_token::id())?;
         if amount == 0 {
             return Ok(());
         }
 
         everlend_utils::cpi::spl_token::transfer(
             self.vault.clone(),
-            self.user_reward_token_account.clone(),
+            destination.clone(),
             self.reward_pool.clone(),
             amount,
             &[seeds],
@@ -0,0 +1,269 @@
+use crate::events::RewardsEvent;
+use crate::state::{RewardPool, RewardVault, RewardsRoot, VaultKind, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_option::COption;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::{clock, Sysvar};
+use spl_token::instruction::AuthorityType;
+use spl_token::state::{Account, Mint};
+
+/// Instruction context
+pub struct ClosePoolContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    reward_pool_spl: &'a AccountInfo<'b>,
+    reward_pool_authority: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    authority_token_account: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    vaults: Vec<VaultAccounts<'a, 'b>>,
+}
+
+/// Vault account, its reward mint and the account its leftover goes to
+struct VaultAccounts<'a, 'b> {
+    vault: &'a AccountInfo<'b>,
+    reward_mint: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ClosePoolContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<ClosePoolContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool_spl = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let reward_pool_authority = AccountLoader::next_uninitialized(account_info_iter)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let authority_token_account =
+            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        let vaults_len = RewardPool::load(&reward_pool.data.borrow())?.vaults_len as usize;
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter) > vaults_len * 3 {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+
+        // every vault of the pool in order
+        let mut vaults = Vec::with_capacity(vaults_len);
+        for _ in 0..vaults_len {
+            vaults.push(VaultAccounts {
+                vault: AccountLoader::next_unchecked(account_info_iter)?,
+                reward_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                destination: AccountLoader::next_unchecked(account_info_iter)?,
+            });
+        }
+
+        Ok(ClosePoolContext {
+            rewards_root,
+            reward_pool,
+            reward_pool_spl,
+            reward_pool_authority,
+            authority,
+            authority_token_account,
+            clock,
+            stakers_index,
+            vaults,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        let (pool, vaults) = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load_account(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
+
+            reward_pool.pool.assert_closed(timestamp)?;
+
+            // every mining has to be withdrawn or force unstaked first,
+            // empty ones included as they can still hold unclaimed rewards
+            if reward_pool.pool.total_amount > 0
+                || reward_pool.pool.total_stakers > 0
+                || reward_pool.pool.total_unbonding_amount > 0
+                || reward_pool.pool.total_minings > 0
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            {
+                let spl_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"spl".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        reward_pool.pool.liquidity_mint.as_ref(),
+                        &[reward_pool.pool.spl_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+
+                let (authority_token_account, _) = find_associated_token_account(
+                    self.authority.key,
+                    &reward_pool.pool.liquidity_mint,
+                );
+                assert_account_key(self.authority_token_account, &authority_token_account)?;
+            }
+
+            if bool::from(reward_pool.pool.is_competitive) {
+                let stakers_index = self
+                    .stakers_index
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let stakers_index_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"stakers".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        &[reward_pool.pool.stakers_index_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(stakers_index, &stakers_index_pubkey)?;
+            }
+
+            (*reward_pool.pool, reward_pool.vaults().to_vec())
+        };
+
+        let reward_pool_seeds = &[
+            b"reward_pool".as_ref(),
+            pool.rewards_root.as_ref(),
+            pool.liquidity_mint.as_ref(),
+            &[pool.bump],
+        ];
+        assert_account_key(
+            self.reward_pool,
+            &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+        )?;
+
+        // tokens sent to the pool outside of deposits are swept to the authority
+        let authority_seeds = &[self.reward_pool.key.as_ref(), &[pool.authority_bump]];
+        assert_account_key(
+            self.reward_pool_authority,
+            &Pubkey::create_program_address(authority_seeds, program_id)?,
+        )?;
+        self.sweep_and_close(
+            self.reward_pool_spl,
+            self.authority_token_account,
+            self.reward_pool_authority,
+            authority_seeds,
+        )?;
+
+        for (vault, accounts) in vaults.iter().zip(self.vaults.iter()) {
+            self.close_vault(program_id, vault, accounts, reward_pool_seeds)?;
+        }
+
+        if let Some(stakers_index) = self
+            .stakers_index
+            .filter(|_| bool::from(pool.is_competitive))
+        {
+            everlend_utils::cpi::system::close_account(stakers_index, self.authority)?;
+        }
+        everlend_utils::cpi::system::close_account(self.reward_pool, self.authority)?;
+
+        RewardsEvent::PoolClosed {
+            reward_pool: *self.reward_pool.key,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Sweeps undistributed rewards of the vault, returns its minting and closes it
+    fn close_vault(
+        &self,
+        program_id: &Pubkey,
+        vault: &RewardVault,
+        accounts: &VaultAccounts<'a, 'b>,
+        reward_pool_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let vault_seeds = &[
+            b"vault".as_ref(),
+            self.reward_pool.key.as_ref(),
+            vault.reward_mint.as_ref(),
+            &[vault.vault_token_account_bump],
+        ];
+        assert_account_key(
+            accounts.vault,
+            &Pubkey::create_program_address(vault_seeds, program_id)?,
+        )?;
+        assert_account_key(accounts.reward_mint, &vault.reward_mint)?;
+
+        // lamports vaults are closed right to the authority
+        if vault.vault_kind() == VaultKind::Native {
+            assert_account_key(accounts.destination, self.authority.key)?;
+            return everlend_utils::cpi::system::close_account(accounts.vault, self.authority);
+        }
+
+        let (destination, _) =
+            find_associated_token_account(self.authority.key, &vault.reward_mint);
+        assert_account_key(accounts.destination, &destination)?;
+
+        // minting goes back to the root authority, the vault kind could be changed since
+        let mint = Mint::unpack(&accounts.reward_mint.data.borrow())?;
+        if mint.mint_authority == COption::Some(*self.reward_pool.key) {
+            everlend_utils::cpi::spl_token::set_authority(
+                accounts.reward_mint.clone(),
+                self.reward_pool.clone(),
+                Some(self.authority.key),
+                AuthorityType::MintTokens,
+                &[reward_pool_seeds],
+            )?;
+        }
+
+        self.sweep_and_close(
+            accounts.vault,
+            accounts.destination,
+            self.reward_pool,
+            reward_pool_seeds,
+        )
+    }
+
+    /// Transfers the whole token account balance and closes it with rent to the authority
+    fn sweep_and_close(
+        &self,
+        account: &'a AccountInfo<'b>,
+        destination: &'a AccountInfo<'b>,
+        owner: &'a AccountInfo<'b>,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let amount = Account::unpack(&account.data.borrow())?.amount;
+        if amount > 0 {
+            everlend_utils::cpi::spl_token::transfer(
+                account.clone(),
+                destination.clone(),
+                owner.clone(),
+                amount,
+                &[seeds],
+            )?;
+        }
+
+        everlend_utils::cpi::spl_token::close_account(
+            self.authority.clone(),
+            account.clone(),
+            owner.clone(),
+            &[seeds],
+        )
+    }
+}
@@ -1,11 +1,15 @@
-use crate::state::{Mining, RewardPool};
-use crate::{find_mining_program_address, find_reward_pool_spl_token_account};
-use everlend_utils::{assert_account_key, AccountLoader};
+use crate::events::RewardsEvent;
+use crate::state::{Denylist, Mining, Referrer, RewardPool, StakersIndex, ZeroCopy};
+use crate::{find_mining_program_address, find_referrer_program_address};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{
+    assert_account_key, assert_owned_by, bps_floor, AccountLoader, EverlendError,
+};
 use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
-use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::rent::Rent;
 use solana_program::system_program;
@@ -19,8 +23,29 @@ pub struct DepositMiningContext<'a, 'b> {
     mining: &'a AccountInfo<'b>,
     user_token_account: &'a AccountInfo<'b>,
     user: &'a AccountInfo<'b>,
+    owner: Pubkey,
+    is_funded: bool,
     clock: &'a AccountInfo<'b>,
     rent: &'a AccountInfo<'b>,
+    denylist: Option<&'a AccountInfo<'b>>,
+    referrer: Option<&'a AccountInfo<'b>>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    displaced: Option<DisplacedAccounts<'a, 'b>>,
+    fee_token_account: Option<&'a AccountInfo<'b>>,
+    receipt: Option<ReceiptAccounts<'a, 'b>>,
+}
+
+/// Receipt mint and receipt associated token account of the owner
+struct ReceiptAccounts<'a, 'b> {
+    receipt_mint: &'a AccountInfo<'b>,
+    user_receipt_account: &'a AccountInfo<'b>,
+}
+
+/// Accounts of the smallest position displaced from a full competitive pool
+struct DisplacedAccounts<'a, 'b> {
+    reward_pool_authority: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    owner_token_account: &'a AccountInfo<'b>,
 }
 
 impl<'a, 'b> DepositMiningContext<'a, 'b> {
@@ -28,6 +53,8 @@ impl<'a, 'b> DepositMiningContext<'a, 'b> {
     pub fn new(
         program_id: &Pubkey,
         accounts: &'a [AccountInfo<'b>],
+        is_referred: bool,
+        beneficiary: Option<Pubkey>,
     ) -> Result<DepositMiningContext<'a, 'b>, ProgramError> {
         let account_info_iter = &mut accounts.iter().enumerate();
         let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
@@ -35,8 +62,7 @@ impl<'a, 'b> DepositMiningContext<'a, 'b> {
             AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
         let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
         let mining = AccountLoader::next_unchecked(account_info_iter)?; // unchecked so we can create on the fly
-        let user_token_account =
-            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let user_token_account = AccountLoader::next_unchecked(account_info_iter)?;
         let user = AccountLoader::next_signer(account_info_iter)?;
 
         let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
@@ -44,6 +70,75 @@ impl<'a, 'b> DepositMiningContext<'a, 'b> {
             AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
         let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
         let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+        // pools guarded by the denylist of the rewards root take it next
+        let has_denylist = {
+            let reward_pool_data = reward_pool.data.borrow();
+            bool::from(RewardPool::load(&reward_pool_data)?.has_denylist)
+        };
+        let denylist = if has_denylist {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                program_id,
+            )?)
+        } else {
+            None
+        };
+        // unchecked so we can create on the fly
+        let referrer = if is_referred {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+
+        // pools with deposit fee take the fee token account and receipt pools
+        // take the receipt accounts last
+        let (fee_accounts, receipt_accounts) = {
+            let reward_pool_data = reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            (
+                (reward_pool.deposit_fee_bps > 0) as usize,
+                reward_pool.has_receipt() as usize * 2,
+            )
+        };
+        let trailing_accounts = fee_accounts + receipt_accounts;
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter) > trailing_accounts {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+        let displaced = if AccountLoader::remaining_len(account_info_iter) > trailing_accounts {
+            Some(DisplacedAccounts {
+                reward_pool_authority: AccountLoader::next_uninitialized(account_info_iter)?,
+                mining: AccountLoader::next_with_owner(account_info_iter, program_id)?,
+                owner_token_account: AccountLoader::next_with_owner(
+                    account_info_iter,
+                    &spl_token::id(),
+                )?,
+            })
+        } else {
+            None
+        };
+        let fee_token_account = if fee_accounts > 0 {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+        let receipt = if receipt_accounts > 0 {
+            Some(ReceiptAccounts {
+                receipt_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                user_receipt_account: AccountLoader::next_with_owner(
+                    account_info_iter,
+                    &spl_token::id(),
+                )?,
+            })
+        } else {
+            None
+        };
 
         Ok(DepositMiningContext {
             reward_pool,
@@ -52,102 +147,498 @@ impl<'a, 'b> DepositMiningContext<'a, 'b> {
             mining,
             user_token_account,
             user,
+            // the user funds the position of the beneficiary
+            owner: beneficiary.unwrap_or(*user.key),
+            is_funded: beneficiary.is_some(),
             clock,
             rent,
+            denylist,
+            referrer,
+            stakers_index,
+            displaced,
+            fee_token_account,
+            receipt,
         })
     }
 
     /// Process instruction
-    pub fn process(&self, program_id: &Pubkey, amount: u64) -> ProgramResult {
-        let (mut mining, is_first_deposit) = self.check_and_init_mining(program_id)?;
+    pub fn process(
+        &self,
+        program_id: &Pubkey,
+        amount: u64,
+        referrer: Option<Pubkey>,
+        min_lock_sec: Option<u64>,
+    ) -> ProgramResult {
+        self.assert_not_denied(program_id)?;
+        self.check_and_init_mining(program_id)?;
         {
-            let mining_pubkey = Pubkey::create_program_address(
+            let mining_data = self.mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+            assert_account_key(self.mining, &mining.address(program_id)?)?;
+            assert_account_key(self.reward_pool, &mining.reward_pool)?;
+            if mining.owner != self.owner {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            // funders can't extend the lock of stake the owner already has
+            if self.is_funded && (mining.amount > 0 || mining.unbonding_amount > 0) {
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.liquidity_mint, &reward_pool.liquidity_mint)?;
+
+            // enforced locks are capped by the pool admin
+            if min_lock_sec
+                .is_some_and(|min_lock_sec| min_lock_sec > reward_pool.max_funder_lock_sec)
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            let spl_pubkey = Pubkey::create_program_address(
                 &[
-                    b"mining".as_ref(),
-                    self.user.key.as_ref(),
+                    b"spl".as_ref(),
                     self.reward_pool.key.as_ref(),
-                    &[mining.bump],
+                    self.liquidity_mint.key.as_ref(),
+                    &[reward_pool.spl_bump],
                 ],
                 program_id,
             )?;
-            assert_account_key(self.mining, &mining_pubkey)?;
-            assert_account_key(self.reward_pool, &mining.reward_pool)?;
-            assert_account_key(self.user, &mining.owner)?;
+            assert_account_key(self.reward_pool_spl_token_account, &spl_pubkey)?;
+        }
+
+        let rent = Rent::from_account_info(self.rent)?;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.user, &rent, mining_len)?;
+        }
+
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
+
+        if let Some(referrer) = referrer {
+            self.record_referrer(program_id, &referrer, timestamp as u64)?;
         }
 
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
+        let fee = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            bps_floor(amount, RewardPool::load(&reward_pool_data)?.deposit_fee_bps)?
+        };
+        let net_amount = amount.checked_sub(fee).ok_or(EverlendError::MathOverflow)?;
+
+        let stakers_index = self.stakers_index(program_id)?;
+        if let Some(stakers_index) = stakers_index {
+            self.displace_smallest(
+                program_id,
+                stakers_index,
+                net_amount,
+                &rent,
+                timestamp as u64,
+            )?;
+        }
+
+        if !self.is_native_deposit() {
+            assert_owned_by(self.user_token_account, &spl_token::id())?;
+        }
+        self.transfer_from_user(self.reward_pool_spl_token_account, net_amount)?;
+        if fee > 0 {
+            let fee_token_account = self
+                .fee_token_account
+                .ok_or(ProgramError::NotEnoughAccountKeys)?;
+            {
+                let reward_pool_data = self.reward_pool.data.borrow();
+                let fee_pubkey = RewardPool::load(&reward_pool_data)?
+                    .liquidity_fee_token_account(program_id, self.reward_pool.key);
+                assert_account_key(fee_token_account, &fee_pubkey)?;
+            }
+            self.transfer_from_user(fee_token_account, fee)?;
+        }
+
+        {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            reward_pool.record_deposit_fee(fee)?;
+            reward_pool.deposit(&mut mining, net_amount, timestamp as u64)?;
+            if let Some(min_lock_sec) = min_lock_sec {
+                mining
+                    .mining
+                    .enforce_lock((timestamp as u64).saturating_add(min_lock_sec));
+            }
+
+            if let Some(stakers_index) = stakers_index {
+                StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                    .upsert(*self.mining.key, mining.mining.amount)?;
+            }
+
+            if self.is_funded {
+                RewardsEvent::PositionFunded {
+                    reward_pool: *self.reward_pool.key,
+                    mining: *self.mining.key,
+                    funder: *self.user.key,
+                    owner: self.owner,
+                    amount: net_amount,
+                    lock_ends_at: mining.mining.lock_ends_at(reward_pool.pool.lock_time_sec),
+                }
+                .emit();
+            }
+        }
+
+        self.mint_receipt(net_amount)
+    }
+
+    /// Checks neither the user nor the owner is on the denylist of the rewards root
+    fn assert_not_denied(&self, program_id: &Pubkey) -> ProgramResult {
+        let denylist = match self.denylist {
+            Some(denylist) => denylist,
+            None => return Ok(()),
+        };
+
         {
-            let (spl_pubkey, _) = find_reward_pool_spl_token_account(
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            let denylist_pubkey = Pubkey::create_program_address(
+                &[
+                    b"denylist".as_ref(),
+                    reward_pool.rewards_root.as_ref(),
+                    &[reward_pool.denylist_bump],
+                ],
                 program_id,
-                self.reward_pool.key,
-                self.liquidity_mint.key,
-            );
+            )?;
+            assert_account_key(denylist, &denylist_pubkey)?;
+        }
 
-            assert_account_key(self.reward_pool_spl_token_account, &spl_pubkey)?;
+        let denylist_data = denylist.data.borrow();
+        let denylist = Denylist::load_account(&denylist_data)?;
+
+        if denylist.contains(self.user.key) || denylist.contains(&self.owner) {
+            return Err(EverlendError::WalletIsDenied.into());
         }
 
-        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
+        Ok(())
+    }
 
-        // Transfer token from source to token account
-        everlend_utils::cpi::spl_token::transfer(
-            self.user_token_account.clone(),
-            self.reward_pool_spl_token_account.clone(),
-            self.user.clone(),
+    /// Mints receipts of the staked amount to the owner in a receipt pool
+    fn mint_receipt(&self, amount: u64) -> ProgramResult {
+        let receipt = match self.receipt.as_ref() {
+            Some(receipt) => receipt,
+            None => return Ok(()),
+        };
+
+        let (rewards_root, liquidity_mint, bump) = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(receipt.receipt_mint, &reward_pool.receipt_mint)?;
+
+            let (user_receipt_account, _) =
+                find_associated_token_account(&self.owner, &reward_pool.receipt_mint);
+            assert_account_key(receipt.user_receipt_account, &user_receipt_account)?;
+
+            (
+                reward_pool.rewards_root,
+                reward_pool.liquidity_mint,
+                reward_pool.bump,
+            )
+        };
+
+        let reward_pool_seeds = &[
+            b"reward_pool".as_ref(),
+            rewards_root.as_ref(),
+            liquidity_mint.as_ref(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::spl_token::mint_to(
+            receipt.receipt_mint.clone(),
+            receipt.user_receipt_account.clone(),
+            self.reward_pool.clone(),
             amount,
-            &[],
+            &[reward_pool_seeds],
+        )
+    }
+
+    /// Transfers user tokens, or wraps user lamports in a native pool
+    fn transfer_from_user(&self, destination: &'a AccountInfo<'b>, amount: u64) -> ProgramResult {
+        if self.is_native_deposit() {
+            // wrap lamports straight into the destination account
+            everlend_utils::cpi::system::transfer(
+                self.user.clone(),
+                destination.clone(),
+                amount,
+                &[],
+            )?;
+            everlend_utils::cpi::spl_token::sync_native(destination.clone())
+        } else {
+            // Transfer token from source to token account
+            everlend_utils::cpi::spl_token::transfer(
+                self.user_token_account.clone(),
+                destination.clone(),
+                self.user.clone(),
+                amount,
+                &[],
+            )
+        }
+    }
+
+    /// Checks the user deposits lamports into a native pool,
+    /// the user wallet itself is passed as the user token account then
+    fn is_native_deposit(&self) -> bool {
+        self.user_token_account.key == self.user.key
+            && *self.liquidity_mint.key == spl_token::native_mint::id()
+    }
+
+    /// Returns stakers index of a competitive pool
+    fn stakers_index(
+        &self,
+        program_id: &Pubkey,
+    ) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+        let reward_pool_data = self.reward_pool.data.borrow();
+        let reward_pool = RewardPool::load(&reward_pool_data)?;
+        if !bool::from(reward_pool.is_competitive) {
+            return Ok(None);
+        }
+
+        let stakers_index = self
+            .stakers_index
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let stakers_index_pubkey = Pubkey::create_program_address(
+            &[
+                b"stakers".as_ref(),
+                self.reward_pool.key.as_ref(),
+                &[reward_pool.stakers_index_bump],
+            ],
+            program_id,
         )?;
+        assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+        Ok(Some(stakers_index))
+    }
+
+    /// Force unstakes the smallest position when a new staker outbids it in a full pool,
+    /// its rewards are settled first and stay claimable, frozen ones aren't indexed
+    fn displace_smallest(
+        &self,
+        program_id: &Pubkey,
+        stakers_index: &'a AccountInfo<'b>,
+        amount: u64,
+        rent: &Rent,
+        timestamp: u64,
+    ) -> ProgramResult {
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            let mining_data = self.mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+
+            let is_new_staker = mining.amount == 0 && amount > 0;
+            if !is_new_staker
+                || reward_pool.max_stakers == 0
+                || reward_pool.total_stakers < reward_pool.max_stakers
+            {
+                return Ok(());
+            }
+        }
+
+        let displaced = self.displaced.as_ref().ok_or(EverlendError::PoolIsFull)?;
+
+        {
+            let stakers_index_data = stakers_index.data.borrow();
+            let smallest = StakersIndex::load_account(&stakers_index_data)?
+                .min()
+                .copied()
+                .ok_or(EverlendError::PoolIsFull)?;
+            assert_account_key(displaced.mining, &smallest.mining)?;
+
+            if amount <= smallest.amount {
+                return Err(EverlendError::PoolIsFull.into());
+            }
+        }
 
-        reward_pool.deposit(&mut mining, amount, is_first_deposit, timestamp as u64)?;
+        // grow displaced mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = displaced.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if displaced.mining.data_len() < mining_len {
+            realloc_with_rent(displaced.mining, self.user, rent, mining_len)?;
+        }
 
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
-        Mining::pack(mining, *self.mining.data.borrow_mut())?;
+        let (displaced_amount, owner, authority_bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            let mut mining_data = displaced.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            let (owner_token_account, _) = find_associated_token_account(
+                &mining.mining.owner,
+                &reward_pool.pool.liquidity_mint,
+            );
+            assert_account_key(displaced.owner_token_account, &owner_token_account)?;
+
+            let displaced_amount = reward_pool.displace(&mut mining, timestamp)?;
+            StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                .remove(displaced.mining.key);
+
+            (
+                displaced_amount,
+                mining.mining.owner,
+                reward_pool.pool.authority_bump,
+            )
+        };
+
+        let signers_seeds = &[self.reward_pool.key.as_ref(), &[authority_bump]];
+        assert_account_key(
+            displaced.reward_pool_authority,
+            &Pubkey::create_program_address(signers_seeds, program_id)?,
+        )?;
+
+        everlend_utils::cpi::spl_token::transfer(
+            self.reward_pool_spl_token_account.clone(),
+            displaced.owner_token_account.clone(),
+            displaced.reward_pool_authority.clone(),
+            displaced_amount,
+            &[signers_seeds],
+        )?;
+
+        RewardsEvent::MiningDisplaced {
+            reward_pool: *self.reward_pool.key,
+            mining: *displaced.mining.key,
+            owner,
+            amount: displaced_amount,
+            displaced_by: *self.mining.key,
+        }
+        .emit();
 
         Ok(())
     }
 
-    /// Process instruction
-    pub fn check_and_init_mining(
+    /// Records the referrer of a new mining, later deposits keep the first referrer
+    fn record_referrer(
         &self,
         program_id: &Pubkey,
-    ) -> Result<(Mining, bool), ProgramError> {
+        referrer: &Pubkey,
+        timestamp: u64,
+    ) -> ProgramResult {
+        let referrer_account = self.referrer.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (referrer_pubkey, bump) =
+            find_referrer_program_address(program_id, self.reward_pool.key, referrer);
+        assert_account_key(referrer_account, &referrer_pubkey)?;
+
+        {
+            let mining_data = self.mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+            if mining.rewards_calculated_at != 0 {
+                return Ok(());
+            }
+        }
+
+        if *referrer == self.owner {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if referrer_account.owner.eq(&Pubkey::default()) {
+            let signers_seeds = &[
+                "referrer".as_bytes(),
+                &self.reward_pool.key.to_bytes(),
+                &referrer.to_bytes(),
+                &[bump],
+            ];
+
+            everlend_utils::cpi::system::create_account_with_len(
+                program_id,
+                self.user.clone(),
+                referrer_account.clone(),
+                &[signers_seeds],
+                &Rent::from_account_info(self.rent)?,
+                Referrer::LEN,
+            )?;
+
+            *Referrer::load_unchecked_mut(&mut referrer_account.data.borrow_mut())? =
+                Referrer::initialize(*self.reward_pool.key, *referrer, bump);
+        }
+
+        Referrer::load_mut(&mut referrer_account.data.borrow_mut())?.record(timestamp)?;
+
+        let referral_share_bps = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            RewardPool::load(&reward_pool_data)?.referral_share_bps
+        };
+        let mut mining_data = self.mining.data.borrow_mut();
+        let mining = Mining::load_mut(&mut mining_data)?;
+        mining.referrer = *referrer;
+        mining.referral_share_bps = referral_share_bps;
+
+        RewardsEvent::MiningReferred {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            referrer: *referrer,
+            referral_share_bps,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Process instruction
+    pub fn check_and_init_mining(&self, program_id: &Pubkey) -> ProgramResult {
         if self.mining.owner.eq(&Pubkey::default()) {
             // create account
             let bump = self.create_mining_acc(program_id)?;
-            return Ok((
-                Mining::initialize(*self.reward_pool.key, bump, *self.user.key),
-                true,
-            ));
+            let mut mining_data = self.mining.data.borrow_mut();
+            *Mining::load_unchecked_mut(&mut mining_data)? =
+                Mining::initialize(*self.reward_pool.key, bump, self.owner);
+            RewardPool::load_mut(&mut self.reward_pool.data.borrow_mut())?.open_mining()?;
+
+            return Ok(());
         }
 
         if self.mining.owner.eq(program_id) {
-            return Ok((Mining::unpack(&self.mining.data.borrow())?, false));
+            return Ok(());
         }
 
         Err(ProgramError::InvalidAccountOwner)
     }
 
-    /// create a mining account for user
+    /// create a mining account for owner
     pub fn create_mining_acc(&self, program_id: &Pubkey) -> Result<u8, ProgramError> {
         let bump = {
             let (pubkey, bump) =
-                find_mining_program_address(program_id, self.user.key, self.reward_pool.key);
+                find_mining_program_address(program_id, &self.owner, self.reward_pool.key);
             assert_account_key(self.mining, &pubkey)?;
             bump
         };
 
+        let vaults_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            RewardPool::load(&reward_pool_data)?.vaults_len
+        };
+
         let signers_seeds = &[
             "mining".as_bytes(),
-            &self.user.key.to_bytes(),
+            &self.owner.to_bytes(),
             &self.reward_pool.key.to_bytes(),
             &[bump],
         ];
 
-        everlend_utils::cpi::system::create_account::<Mining>(
+        everlend_utils::cpi::system::create_account_with_len(
             program_id,
             self.user.clone(),
             self.mining.clone(),
             &[signers_seeds],
             &Rent::from_account_info(self.rent)?,
+            Mining::account_len(vaults_len as usize),
         )?;
 
         Ok(bump)
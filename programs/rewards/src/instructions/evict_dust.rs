@@ -0,0 +1,212 @@
+use crate::events::RewardsEvent;
+use crate::state::{Mining, RewardPool, StakersIndex, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::Account;
+
+/// Instruction context
+pub struct EvictDustContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    reward_pool_spl: &'a AccountInfo<'b>,
+    reward_pool_authority: &'a AccountInfo<'b>,
+    liquidity_mint: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    owner_token_account: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    holder_token_account: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b> EvictDustContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<EvictDustContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool_spl = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let reward_pool_authority = AccountLoader::next_uninitialized(account_info_iter)?;
+        let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let owner_token_account =
+            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let payer = AccountLoader::next_signer(account_info_iter)?;
+
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        // tokenized positions take the holder token account last
+        let holder_accounts =
+            bool::from(Mining::load(&mining.data.borrow())?.is_tokenized) as usize;
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter) > holder_accounts {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+        let holder_token_account = if holder_accounts > 0 {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(EvictDustContext {
+            reward_pool,
+            reward_pool_spl,
+            reward_pool_authority,
+            liquidity_mint,
+            mining,
+            owner_token_account,
+            payer,
+            clock,
+            stakers_index,
+            holder_token_account,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.payer, &Rent::get()?, mining_len)?;
+        }
+
+        let (amount, recipient, authority_bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            {
+                let reward_pool_seeds = &[
+                    b"reward_pool".as_ref(),
+                    reward_pool.pool.rewards_root.as_ref(),
+                    reward_pool.pool.liquidity_mint.as_ref(),
+                    &[reward_pool.pool.bump],
+                ];
+
+                assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+                assert_account_key(
+                    self.reward_pool,
+                    &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+                )?;
+            }
+
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+
+            {
+                let spl_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"spl".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        reward_pool.pool.liquidity_mint.as_ref(),
+                        &[reward_pool.pool.spl_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(self.liquidity_mint, &reward_pool.pool.liquidity_mint)?;
+                assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+            }
+
+            // tokens of a tokenized position go to the holder of its NFT,
+            // who keeps claiming the rewards of the position
+            let recipient = match self.holder_token_account {
+                Some(holder_token_account) => {
+                    let token_account = Account::unpack(&holder_token_account.data.borrow())?;
+                    mining.mining.assert_holder(
+                        program_id,
+                        self.mining.key,
+                        &token_account.owner,
+                        &token_account,
+                    )?;
+                    token_account.owner
+                }
+                None => mining.mining.owner,
+            };
+
+            // evicted tokens only go back to the recipient's associated token account
+            {
+                let (owner_token_account, _) =
+                    find_associated_token_account(&recipient, &reward_pool.pool.liquidity_mint);
+                assert_account_key(self.owner_token_account, &owner_token_account)?;
+            }
+
+            // receipts of the position can only be burned by the owner
+            if reward_pool.pool.has_receipt() {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            let amount = reward_pool.evict(&mut mining, timestamp as u64)?;
+
+            if bool::from(reward_pool.pool.is_competitive) {
+                let stakers_index = self
+                    .stakers_index
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let stakers_index_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"stakers".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        &[reward_pool.pool.stakers_index_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+                StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                    .remove(self.mining.key);
+            }
+
+            (amount, recipient, reward_pool.pool.authority_bump)
+        };
+
+        let signers_seeds = &[self.reward_pool.key.as_ref(), &[authority_bump]];
+        assert_account_key(
+            self.reward_pool_authority,
+            &Pubkey::create_program_address(signers_seeds, program_id)?,
+        )?;
+
+        everlend_utils::cpi::spl_token::transfer(
+            self.reward_pool_spl.clone(),
+            self.owner_token_account.clone(),
+            self.reward_pool_authority.clone(),
+            amount,
+            &[signers_seeds],
+        )?;
+
+        RewardsEvent::MiningEvicted {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            owner: recipient,
+            amount,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
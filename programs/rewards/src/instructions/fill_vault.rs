@@ -1,10 +1,16 @@
-use crate::state::RewardPool;
-use everlend_utils::{assert_account_key, AccountLoader};
+use crate::events::RewardsEvent;
+use crate::find_funder_contribution_program_address;
+use crate::state::{FunderContribution, RewardPool, RewardsRoot, VaultKind, ZeroCopy};
+use everlend_utils::{assert_account_key, assert_owned_by, AccountLoader, EverlendError};
 use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
 
 /// Instruction context
 pub struct FillVaultContext<'a, 'b> {
@@ -13,6 +19,9 @@ pub struct FillVaultContext<'a, 'b> {
     vault_token_account: &'a AccountInfo<'b>,
     source_token_account: &'a AccountInfo<'b>,
     authority: &'a AccountInfo<'b>,
+    funder_contribution: &'a AccountInfo<'b>,
+    rewards_root: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
 }
 
 impl<'a, 'b> FillVaultContext<'a, 'b> {
@@ -25,12 +34,16 @@ impl<'a, 'b> FillVaultContext<'a, 'b> {
 
         let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
         let reward_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
-        let vault_token_account =
-            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
-        let source_token_account =
-            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        // token accounts or lamports accounts depending on the vault kind
+        let vault_token_account = AccountLoader::next_unchecked(account_info_iter)?;
+        let source_token_account = AccountLoader::next_unchecked(account_info_iter)?;
         let authority = AccountLoader::next_signer(account_info_iter)?;
         let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let funder_contribution = AccountLoader::next_optional(account_info_iter, program_id)?;
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
 
         Ok(FillVaultContext {
             reward_pool,
@@ -38,20 +51,30 @@ impl<'a, 'b> FillVaultContext<'a, 'b> {
             vault_token_account,
             source_token_account,
             authority,
+            funder_contribution,
+            rewards_root,
+            clock,
         })
     }
 
     /// Process instruction
     pub fn process(&self, program_id: &Pubkey, amount: u64) -> ProgramResult {
-        let reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
 
-        {
+        let (total_funded, vault_kind) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
+
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
             let vault = reward_pool
-                .vaults
-                .iter()
-                .find(|v| &v.reward_mint == self.reward_mint.key)
+                .find_vault_mut(self.reward_mint.key)
                 .ok_or(ProgramError::InvalidArgument)?;
 
+            if !vault.can_fill(self.authority.key, &rewards_root.authority) {
+                return Err(EverlendError::FillNotPermitted.into());
+            }
+
             let vault_seeds = &[
                 b"vault".as_ref(),
                 &self.reward_pool.key.to_bytes()[..32],
@@ -62,17 +85,92 @@ impl<'a, 'b> FillVaultContext<'a, 'b> {
             assert_account_key(
                 self.vault_token_account,
                 &Pubkey::create_program_address(vault_seeds, program_id)?,
-            )?
+            )?;
+
+            vault.total_funded = vault
+                .total_funded
+                .checked_add(amount)
+                .ok_or(EverlendError::MathOverflow)?;
+            (vault.total_funded, vault.vault_kind())
+        };
+
+        self.check_and_init_funder_contribution(program_id)?;
+        FunderContribution::load_mut(&mut self.funder_contribution.data.borrow_mut())?
+            .record(amount, timestamp)?;
+
+        if vault_kind == VaultKind::Native {
+            assert_owned_by(self.vault_token_account, program_id)?;
+
+            everlend_utils::cpi::system::transfer(
+                self.authority.clone(),
+                self.vault_token_account.clone(),
+                amount,
+                &[],
+            )?;
+        } else {
+            assert_owned_by(self.vault_token_account, &spl_token::id())?;
+            assert_owned_by(self.source_token_account, &spl_token::id())?;
+
+            everlend_utils::cpi::spl_token::transfer(
+                self.source_token_account.clone(),
+                self.vault_token_account.clone(),
+                self.authority.clone(),
+                amount,
+                &[],
+            )?;
         }
 
-        everlend_utils::cpi::spl_token::transfer(
-            self.source_token_account.clone(),
-            self.vault_token_account.clone(),
-            self.authority.clone(),
+        RewardsEvent::VaultFilled {
+            reward_pool: *self.reward_pool.key,
+            reward_mint: *self.reward_mint.key,
+            funder: *self.authority.key,
             amount,
-            &[],
+            total_funded,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Creates a funder contribution account on the first fill
+    pub fn check_and_init_funder_contribution(&self, program_id: &Pubkey) -> ProgramResult {
+        let (funder_contribution_pubkey, bump) = find_funder_contribution_program_address(
+            program_id,
+            self.reward_pool.key,
+            self.reward_mint.key,
+            self.authority.key,
+        );
+        assert_account_key(self.funder_contribution, &funder_contribution_pubkey)?;
+
+        if self.funder_contribution.owner.eq(program_id) {
+            return Ok(());
+        }
+
+        let signers_seeds = &[
+            "funder".as_bytes(),
+            &self.reward_pool.key.to_bytes(),
+            &self.reward_mint.key.to_bytes(),
+            &self.authority.key.to_bytes(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.authority.clone(),
+            self.funder_contribution.clone(),
+            &[signers_seeds],
+            &Rent::get()?,
+            FunderContribution::LEN,
         )?;
 
+        *FunderContribution::load_unchecked_mut(&mut self.funder_contribution.data.borrow_mut())? =
+            FunderContribution::initialize(
+                *self.reward_pool.key,
+                *self.reward_mint.key,
+                *self.authority.key,
+                bump,
+            );
+
         Ok(())
     }
 }
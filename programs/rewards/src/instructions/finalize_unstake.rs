@@ -0,0 +1,138 @@
+use crate::state::{Mining, RewardPool, ZeroCopy};
+use everlend_utils::{assert_account_key, bps_floor, AccountLoader, EverlendError};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::{clock, Sysvar};
+
+/// Instruction context
+pub struct FinalizeUnstakeContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    reward_pool_spl: &'a AccountInfo<'b>,
+    reward_pool_authority: &'a AccountInfo<'b>,
+    liquidity_mint: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    user_token_account: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    fee_token_account: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b> FinalizeUnstakeContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<FinalizeUnstakeContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool_spl = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let reward_pool_authority = AccountLoader::next_uninitialized(account_info_iter)?;
+        let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let user_token_account =
+            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        // pools with withdraw fee only
+        let fee_token_account = if AccountLoader::has_more(account_info_iter) {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+
+        Ok(FinalizeUnstakeContext {
+            reward_pool,
+            reward_pool_spl,
+            reward_pool_authority,
+            liquidity_mint,
+            mining,
+            user_token_account,
+            user,
+            clock,
+            fee_token_account,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        let (amount, fee, authority_bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mining = Mining::load_mut(&mut mining_data)?;
+
+            {
+                assert_account_key(self.mining, &mining.address(program_id)?)?;
+                assert_account_key(self.user, &mining.owner)?;
+                assert_account_key(self.reward_pool, &mining.reward_pool)?;
+            }
+
+            {
+                let spl_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"spl".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        reward_pool.liquidity_mint.as_ref(),
+                        &[reward_pool.spl_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(self.liquidity_mint, &reward_pool.liquidity_mint)?;
+                assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+            }
+
+            let amount = reward_pool.finalize_unstake(mining, timestamp)?;
+            let fee = bps_floor(amount, reward_pool.withdraw_fee_bps)?;
+            if fee > 0 {
+                let fee_token_account = self
+                    .fee_token_account
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                assert_account_key(
+                    fee_token_account,
+                    &reward_pool.liquidity_fee_token_account(program_id, self.reward_pool.key),
+                )?;
+            }
+
+            (amount, fee, reward_pool.authority_bump)
+        };
+
+        RewardPool::load_account_mut(&mut self.reward_pool.data.borrow_mut())?
+            .record_withdraw_fee(fee)?;
+
+        let signers_seeds = &[self.reward_pool.key.as_ref(), &[authority_bump]];
+        assert_account_key(
+            self.reward_pool_authority,
+            &Pubkey::create_program_address(signers_seeds, program_id)?,
+        )?;
+
+        everlend_utils::cpi::spl_token::transfer(
+            self.reward_pool_spl.clone(),
+            self.user_token_account.clone(),
+            self.reward_pool_authority.clone(),
+            amount.checked_sub(fee).ok_or(EverlendError::MathOverflow)?,
+            &[signers_seeds],
+        )?;
+        if let Some(fee_token_account) = self.fee_token_account.filter(|_| fee > 0) {
+            everlend_utils::cpi::spl_token::transfer(
+                self.reward_pool_spl.clone(),
+                fee_token_account.clone(),
+                self.reward_pool_authority.clone(),
+                fee,
+                &[signers_seeds],
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+use crate::events::RewardsEvent;
+use crate::instructions::ReferrerAccounts;
+use crate::state::{Mining, RewardPool, RewardVault, StakersIndex, VaultKind, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, assert_owned_by, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::Account;
+use std::cmp;
+
+/// Instruction context
+pub struct ForceUnstakeContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    reward_pool_spl: &'a AccountInfo<'b>,
+    reward_pool_authority: &'a AccountInfo<'b>,
+    liquidity_mint: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    owner: &'a AccountInfo<'b>,
+    owner_token_account: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    vaults: Vec<VaultAccounts<'a, 'b>>,
+    holder_token_account: Option<&'a AccountInfo<'b>>,
+    referrer: Option<ReferrerAccounts<'a, 'b>>,
+}
+
+/// Vault account, its reward mint and the account rewards go to
+struct VaultAccounts<'a, 'b> {
+    vault: &'a AccountInfo<'b>,
+    reward_mint: &'a AccountInfo<'b>,
+    destination: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ForceUnstakeContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<ForceUnstakeContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool_spl = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let reward_pool_authority = AccountLoader::next_uninitialized(account_info_iter)?;
+        let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        // mining owner wallet getting back the rent
+        let owner = AccountLoader::next_unchecked(account_info_iter)?;
+        let owner_token_account =
+            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let payer = AccountLoader::next_signer(account_info_iter)?;
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        let vaults_len = RewardPool::load(&reward_pool.data.borrow())?.vaults_len as usize;
+        // tokenized positions take the holder token account and referred positions
+        // take the referrer accounts last
+        let (holder_accounts, referrer_accounts) = {
+            let mining_data = mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+            (
+                bool::from(mining.is_tokenized) as usize,
+                mining.is_referred() as usize * 2,
+            )
+        };
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter)
+            > vaults_len * 3 + holder_accounts + referrer_accounts
+        {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+
+        // every vault of the pool in order
+        let mut vaults = Vec::with_capacity(vaults_len);
+        for _ in 0..vaults_len {
+            vaults.push(VaultAccounts {
+                vault: AccountLoader::next_unchecked(account_info_iter)?,
+                reward_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                destination: AccountLoader::next_unchecked(account_info_iter)?,
+            });
+        }
+
+        let holder_token_account = if holder_accounts > 0 {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+        let referrer = if referrer_accounts > 0 {
+            Some(ReferrerAccounts::new(program_id, account_info_iter)?)
+        } else {
+            None
+        };
+
+        Ok(ForceUnstakeContext {
+            reward_pool,
+            reward_pool_spl,
+            reward_pool_authority,
+            liquidity_mint,
+            mining,
+            owner,
+            owner_token_account,
+            payer,
+            clock,
+            stakers_index,
+            vaults,
+            holder_token_account,
+            referrer,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.payer, &Rent::get()?, mining_len)?;
+        }
+
+        // rewards accrued for the referrer are credited before the mining is closed
+        if let Some(referrer) = self.referrer.as_ref() {
+            referrer.settle(
+                program_id,
+                self.reward_pool,
+                self.mining,
+                self.payer,
+                timestamp,
+            )?;
+        }
+
+        let (amount, recipient, rewards, rewards_root, liquidity_mint, bump, authority_bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+            {
+                let reward_pool_seeds = &[
+                    b"reward_pool".as_ref(),
+                    reward_pool.pool.rewards_root.as_ref(),
+                    reward_pool.pool.liquidity_mint.as_ref(),
+                    &[reward_pool.pool.bump],
+                ];
+
+                assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+                assert_account_key(
+                    self.reward_pool,
+                    &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+                )?;
+            }
+
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+            assert_account_key(self.owner, &mining.mining.owner)?;
+
+            {
+                let spl_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"spl".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        reward_pool.pool.liquidity_mint.as_ref(),
+                        &[reward_pool.pool.spl_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(self.liquidity_mint, &reward_pool.pool.liquidity_mint)?;
+                assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+            }
+
+            // tokens of a tokenized position go to the holder of its NFT
+            let recipient = match self.holder_token_account {
+                Some(holder_token_account) => {
+                    let token_account = Account::unpack(&holder_token_account.data.borrow())?;
+                    mining.mining.assert_holder(
+                        program_id,
+                        self.mining.key,
+                        &token_account.owner,
+                        &token_account,
+                    )?;
+                    token_account.owner
+                }
+                None => mining.mining.owner,
+            };
+
+            // tokens only go back to the associated token account of the recipient
+            {
+                let (owner_token_account, _) =
+                    find_associated_token_account(&recipient, &reward_pool.pool.liquidity_mint);
+                assert_account_key(self.owner_token_account, &owner_token_account)?;
+            }
+
+            let amount = reward_pool.force_unstake(&mut mining, timestamp)?;
+            reward_pool.pool.close_mining()?;
+            // nothing accrued for the referrer is lost with the mining
+            mining.to_ref().assert_referral_settled(timestamp)?;
+
+            // accrued rewards are paid out with the stake as the mining is closed, vaults pay
+            // out what they can cover and the rest is forfeited so the crank can't get stuck
+            let vaults = reward_pool.to_ref().vaults().to_vec();
+            let mut rewards = Vec::with_capacity(vaults.len());
+            for (vault, accounts) in vaults.into_iter().zip(self.vaults.iter()) {
+                let max_amount = cmp::min(
+                    vault.claim_allowance(timestamp),
+                    self.payable_amount(&vault, accounts)?,
+                );
+                let reward_amount = mining.flush_rewards(vault.reward_mint, max_amount)?;
+                reward_pool.update_vault_totals(vault.reward_mint, reward_amount, timestamp)?;
+                rewards.push((vault, reward_amount));
+            }
+
+            if bool::from(reward_pool.pool.is_competitive) {
+                let stakers_index = self
+                    .stakers_index
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let stakers_index_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"stakers".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        &[reward_pool.pool.stakers_index_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+                StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                    .remove(self.mining.key);
+            }
+
+            (
+                amount,
+                recipient,
+                rewards,
+                reward_pool.pool.rewards_root,
+                reward_pool.pool.liquidity_mint,
+                reward_pool.pool.bump,
+                reward_pool.pool.authority_bump,
+            )
+        };
+
+        let signers_seeds = &[self.reward_pool.key.as_ref(), &[authority_bump]];
+        assert_account_key(
+            self.reward_pool_authority,
+            &Pubkey::create_program_address(signers_seeds, program_id)?,
+        )?;
+
+        // empty positions only get their rewards
+        if amount > 0 {
+            everlend_utils::cpi::spl_token::transfer(
+                self.reward_pool_spl.clone(),
+                self.owner_token_account.clone(),
+                self.reward_pool_authority.clone(),
+                amount,
+                &[signers_seeds],
+            )?;
+        }
+
+        let reward_pool_seeds = &[
+            b"reward_pool".as_ref(),
+            rewards_root.as_ref(),
+            liquidity_mint.as_ref(),
+            &[bump],
+        ];
+        for ((vault, reward_amount), accounts) in rewards.iter().zip(self.vaults.iter()) {
+            self.pay_reward(
+                program_id,
+                vault,
+                accounts,
+                &recipient,
+                *reward_amount,
+                reward_pool_seeds,
+            )?;
+        }
+
+        // close mining account
+        everlend_utils::cpi::system::close_account(self.mining, self.owner)?;
+
+        RewardsEvent::PositionForceUnstaked {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            owner: recipient,
+            amount,
+            rewards: rewards
+                .iter()
+                .map(|(vault, reward_amount)| (vault.reward_mint, *reward_amount))
+                .collect(),
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Amount the vault can pay out, rewards without a reward token account are skipped
+    fn payable_amount(
+        &self,
+        vault: &RewardVault,
+        accounts: &VaultAccounts<'a, 'b>,
+    ) -> Result<u64, ProgramError> {
+        match vault.vault_kind() {
+            VaultKind::Native => Ok(accounts
+                .vault
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(accounts.vault.data_len()))),
+            _ if *accounts.destination.owner != spl_token::id() => Ok(0),
+            VaultKind::Transfer => Ok(Account::unpack(&accounts.vault.data.borrow())?.amount),
+            VaultKind::Mint => Ok(u64::MAX),
+        }
+    }
+
+    /// Pays reward from the vault to the recipient depending on the vault kind
+    fn pay_reward(
+        &self,
+        program_id: &Pubkey,
+        vault: &RewardVault,
+        accounts: &VaultAccounts<'a, 'b>,
+        recipient: &Pubkey,
+        amount: u64,
+        seeds: &[&[u8]],
+    ) -> ProgramResult {
+        let vault_seeds = &[
+            b"vault".as_ref(),
+            self.reward_pool.key.as_ref(),
+            vault.reward_mint.as_ref(),
+            &[vault.vault_token_account_bump],
+        ];
+        assert_account_key(
+            accounts.vault,
+            &Pubkey::create_program_address(vault_seeds, program_id)?,
+        )?;
+        assert_account_key(accounts.reward_mint, &vault.reward_mint)?;
+
+        // lamports rewards go right to the recipient wallet
+        let vault_kind = vault.vault_kind();
+        if vault_kind == VaultKind::Native {
+            assert_account_key(accounts.destination, recipient)?;
+        } else {
+            let (destination, _) = find_associated_token_account(recipient, &vault.reward_mint);
+            assert_account_key(accounts.destination, &destination)?;
+        }
+
+        if amount == 0 {
+            return Ok(());
+        }
+
+        match vault_kind {
+            VaultKind::Transfer => everlend_utils::cpi::spl_token::transfer(
+                accounts.vault.clone(),
+                accounts.destination.clone(),
+                self.reward_pool.clone(),
+                amount,
+                &[seeds],
+            ),
+            VaultKind::Mint => everlend_utils::cpi::spl_token::mint_to(
+                accounts.reward_mint.clone(),
+                accounts.destination.clone(),
+                self.reward_pool.clone(),
+                amount,
+                &[seeds],
+            ),
+            VaultKind::Native => {
+                assert_owned_by(accounts.vault, program_id)?;
+                everlend_utils::cpi::system::withdraw_lamports(
+                    accounts.vault,
+                    accounts.destination,
+                    amount,
+                    &Rent::get()?,
+                )
+            }
+        }
+    }
+}
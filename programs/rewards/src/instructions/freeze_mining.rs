@@ -0,0 +1,140 @@
+use crate::events::RewardsEvent;
+use crate::state::{Mining, RewardPool, RewardsRoot, StakersIndex};
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+
+/// Instruction context
+pub struct FreezeMiningContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b> FreezeMiningContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<FreezeMiningContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+        // competitive pools only
+        let stakers_index = if AccountLoader::has_more(account_info_iter) {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+
+        Ok(FreezeMiningContext {
+            rewards_root,
+            reward_pool,
+            mining,
+            authority,
+            clock,
+            stakers_index,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey, reason: u8, pause_accrual: bool) -> ProgramResult {
+        // zero reason code means the position isn't frozen
+        if reason == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.authority, &Rent::get()?, mining_len)?;
+        }
+
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        let reward_pool_data = self.reward_pool.data.borrow();
+        let reward_pool = RewardPool::load_account(&reward_pool_data)?;
+        let mut mining_data = self.mining.data.borrow_mut();
+        let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+        assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
+        assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+        assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+
+        // rewards accrued before the freeze stay with the position
+        mining.refresh_rewards(reward_pool.iter_vaults(), timestamp)?;
+
+        mining.mining.freeze_reason = reason;
+        mining.mining.is_accrual_paused = pause_accrual.into();
+
+        // frozen positions leave the stakers index so they can't keep new stakers out
+        if let Some(stakers_index) = self.stakers_index(program_id, reward_pool.pool)? {
+            StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                .remove(self.mining.key);
+        }
+
+        RewardsEvent::MiningFrozen {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            owner: mining.mining.owner,
+            reason,
+            is_accrual_paused: pause_accrual,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Stakers index of a competitive pool
+    fn stakers_index(
+        &self,
+        program_id: &Pubkey,
+        reward_pool: &RewardPool,
+    ) -> Result<Option<&'a AccountInfo<'b>>, ProgramError> {
+        if !bool::from(reward_pool.is_competitive) {
+            return Ok(None);
+        }
+
+        let stakers_index = self
+            .stakers_index
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let stakers_index_pubkey = Pubkey::create_program_address(
+            &[
+                b"stakers".as_ref(),
+                self.reward_pool.key.as_ref(),
+                &[reward_pool.stakers_index_bump],
+            ],
+            program_id,
+        )?;
+        assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+        Ok(Some(stakers_index))
+    }
+}
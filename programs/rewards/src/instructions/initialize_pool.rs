@@ -1,4 +1,4 @@
-use crate::state::{InitRewardPoolParams, RewardPool, RewardsRoot};
+use crate::state::{InitRewardPoolParams, RewardPool, RewardsRoot, ZeroCopy};
 use crate::{find_reward_pool_program_address, find_reward_pool_spl_token_account};
 use everlend_utils::{assert_account_key, find_program_address, AccountLoader};
 use solana_program::account_info::AccountInfo;
@@ -58,21 +58,35 @@ impl<'a, 'b> InitializePoolContext<'a, 'b> {
         program_id: &Pubkey,
         lock_time_sec: u64,
         max_stakers: u64,
+        vaults_capacity: u8,
+        tiers_capacity: u8,
     ) -> ProgramResult {
+        if vaults_capacity == 0 || tiers_capacity == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
         {
             let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
             assert_account_key(self.payer, &rewards_root.authority)?;
         }
 
-        self.create_spl_acc(program_id)?;
-        self.create_rewards_pool_acc(program_id, lock_time_sec, max_stakers)?;
+        let (spl_bump, authority_bump) = self.create_spl_acc(program_id)?;
+        self.create_rewards_pool_acc(
+            program_id,
+            spl_bump,
+            authority_bump,
+            lock_time_sec,
+            max_stakers,
+            vaults_capacity,
+            tiers_capacity,
+        )?;
 
         Ok(())
     }
 
     /// create pool token account
-    pub fn create_spl_acc(&self, program_id: &Pubkey) -> ProgramResult {
-        {
+    pub fn create_spl_acc(&self, program_id: &Pubkey) -> Result<(u8, u8), ProgramError> {
+        let spl_bump = {
             let bump = {
                 let (spl_pubkey, bump) = find_reward_pool_spl_token_account(
                     program_id,
@@ -98,9 +112,12 @@ impl<'a, 'b> InitializePoolContext<'a, 'b> {
                 &[signers_seeds],
                 &Rent::from_account_info(self.rent)?,
             )?;
-        }
 
-        let (reward_pool_authority, _) = find_program_address(program_id, self.reward_pool.key);
+            bump
+        };
+
+        let (reward_pool_authority, authority_bump) =
+            find_program_address(program_id, self.reward_pool.key);
         assert_account_key(self.reward_pool_authority, &reward_pool_authority)?;
 
         everlend_utils::cpi::spl_token::initialize_account(
@@ -110,15 +127,20 @@ impl<'a, 'b> InitializePoolContext<'a, 'b> {
             self.rent.clone(),
         )?;
 
-        Ok(())
+        Ok((spl_bump, authority_bump))
     }
 
     /// create pool account
+    #[allow(clippy::too_many_arguments)]
     pub fn create_rewards_pool_acc(
         &self,
         program_id: &Pubkey,
+        spl_bump: u8,
+        authority_bump: u8,
         lock_time_sec: u64,
         max_stakers: u64,
+        vaults_capacity: u8,
+        tiers_capacity: u8,
     ) -> ProgramResult {
         let bump = {
             let (reward_pool_pubkey, bump) = find_reward_pool_program_address(
@@ -137,23 +159,28 @@ impl<'a, 'b> InitializePoolContext<'a, 'b> {
             &[bump],
         ];
 
-        everlend_utils::cpi::system::create_account::<RewardPool>(
+        everlend_utils::cpi::system::create_account_with_len(
             program_id,
             self.payer.clone(),
             self.reward_pool.clone(),
             &[reward_pool_seeds],
             &Rent::from_account_info(self.rent)?,
+            RewardPool::account_len(vaults_capacity, tiers_capacity),
         )?;
 
-        let reward_pool = RewardPool::init(InitRewardPoolParams {
-            rewards_root: *self.rewards_root.key,
-            bump,
-            lock_time_sec,
-            liquidity_mint: *self.liquidity_mint.key,
-            max_stakers,
-        });
-
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        *RewardPool::load_unchecked_mut(&mut reward_pool_data)? =
+            RewardPool::init(InitRewardPoolParams {
+                rewards_root: *self.rewards_root.key,
+                bump,
+                authority_bump,
+                spl_bump,
+                lock_time_sec,
+                liquidity_mint: *self.liquidity_mint.key,
+                max_stakers,
+                vaults_capacity,
+                tiers_capacity,
+            });
 
         Ok(())
     }
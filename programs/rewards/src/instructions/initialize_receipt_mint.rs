@@ -0,0 +1,104 @@
+use crate::find_receipt_mint_program_address;
+use crate::state::{RewardPool, RewardsRoot, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+use spl_token::state::Mint;
+
+/// Instruction context
+pub struct InitializeReceiptMintContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    liquidity_mint: &'a AccountInfo<'b>,
+    receipt_mint: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> InitializeReceiptMintContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<InitializeReceiptMintContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let receipt_mint = AccountLoader::next_uninitialized(account_info_iter)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        Ok(InitializeReceiptMintContext {
+            rewards_root,
+            reward_pool,
+            liquidity_mint,
+            receipt_mint,
+            authority,
+            rent,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+            assert_account_key(self.liquidity_mint, &reward_pool.liquidity_mint)?;
+
+            // every staked amount has to be backed by receipts, and receipts can't be
+            // force unstaked from a competitive pool
+            if reward_pool.has_receipt()
+                || reward_pool.total_amount > 0
+                || bool::from(reward_pool.is_competitive)
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        let (receipt_mint_pubkey, bump) =
+            find_receipt_mint_program_address(program_id, self.reward_pool.key);
+        assert_account_key(self.receipt_mint, &receipt_mint_pubkey)?;
+
+        let signers_seeds = &[b"receipt".as_ref(), self.reward_pool.key.as_ref(), &[bump]];
+
+        everlend_utils::cpi::system::create_account::<Mint>(
+            &spl_token::id(),
+            self.authority.clone(),
+            self.receipt_mint.clone(),
+            &[signers_seeds],
+            &Rent::from_account_info(self.rent)?,
+        )?;
+
+        let decimals = Mint::unpack(&self.liquidity_mint.data.borrow())?.decimals;
+        everlend_utils::cpi::spl_token::initialize_mint(
+            self.receipt_mint.clone(),
+            self.reward_pool.clone(),
+            self.rent.clone(),
+            decimals,
+        )?;
+
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        reward_pool.receipt_mint = *self.receipt_mint.key;
+        reward_pool.receipt_bump = bump;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::find_stakers_index_program_address;
+use crate::state::{RewardPool, RewardsRoot, StakersIndex, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+
+/// Instruction context
+pub struct InitializeStakersIndexContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    stakers_index: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> InitializeStakersIndexContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<InitializeStakersIndexContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let stakers_index = AccountLoader::next_uninitialized(account_info_iter)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        Ok(InitializeStakersIndexContext {
+            rewards_root,
+            reward_pool,
+            stakers_index,
+            authority,
+            rent,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        let max_stakers = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+            // every position has to be indexed, so only an empty capped pool can switch,
+            // and receipts can't be force unstaked
+            if reward_pool.max_stakers == 0
+                || reward_pool.total_stakers > 0
+                || reward_pool.has_receipt()
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.max_stakers
+        };
+
+        let (stakers_index_pubkey, bump) =
+            find_stakers_index_program_address(program_id, self.reward_pool.key);
+        assert_account_key(self.stakers_index, &stakers_index_pubkey)?;
+
+        let signers_seeds = &[b"stakers".as_ref(), self.reward_pool.key.as_ref(), &[bump]];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.authority.clone(),
+            self.stakers_index.clone(),
+            &[signers_seeds],
+            &Rent::from_account_info(self.rent)?,
+            StakersIndex::account_len(max_stakers),
+        )?;
+
+        *StakersIndex::load_unchecked_mut(&mut self.stakers_index.data.borrow_mut())? =
+            StakersIndex::initialize(*self.reward_pool.key, bump, max_stakers);
+
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        reward_pool.is_competitive = true.into();
+        reward_pool.stakers_index_bump = bump;
+
+        Ok(())
+    }
+}
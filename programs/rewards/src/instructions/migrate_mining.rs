@@ -9,7 +9,7 @@ use solana_program::rent::Rent;
 use solana_program::system_program;
 use solana_program::sysvar::{Sysvar, SysvarId};
 
-use crate::state::{RewardPool, Mining, DeprecatedMining};
+use crate::state::{DeprecatedMining, Mining, RewardPool, ZeroCopy};
 
 /// Instruction context
 pub struct MigrateMiningContext<'a, 'b> {
@@ -53,9 +53,10 @@ impl<'a, 'b> MigrateMiningContext<'a, 'b> {
         let rent = Rent::from_account_info(self.rent)?;
 
         let deprecated_mining = DeprecatedMining::unpack(&self.mining.data.borrow())?;
-        let mining = Mining::migrate(&deprecated_mining);
+        let mut mining = Mining::migrate(&deprecated_mining);
 
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
+        let reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
+        mining.lock_time_sec = reward_pool.lock_time_sec;
 
         let reward_pool_seeds = &[
             b"reward_pool".as_ref(),
@@ -86,9 +87,18 @@ impl<'a, 'b> MigrateMiningContext<'a, 'b> {
             assert_account_key(self.mining, &mining_pubkey)?;
         }
 
-        realloc_with_rent(self.mining, self.payer, &rent, Mining::LEN)?;
+        realloc_with_rent(
+            self.mining,
+            self.payer,
+            &rent,
+            Mining::account_len(deprecated_mining.indexes.len()),
+        )?;
 
-        Mining::pack(mining, *self.mining.data.borrow_mut())?;
+        let mut mining_data = self.mining.data.borrow_mut();
+        mining_data.fill(0);
+        *Mining::load_unchecked_mut(&mut mining_data)? = mining;
+        Mining::load_account_mut(&mut mining_data)?.migrate_indexes(&deprecated_mining)?;
+        RewardPool::load_mut(&mut self.reward_pool.data.borrow_mut())?.open_mining()?;
 
         Ok(())
     }
@@ -1,5 +1,5 @@
 use everlend_utils::cpi::system::realloc_with_rent;
-use everlend_utils::{assert_account_key, AccountLoader};
+use everlend_utils::{assert_account_key, find_program_address, AccountLoader};
 use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint_deprecated::ProgramResult;
 use solana_program::program_error::ProgramError;
@@ -9,8 +9,8 @@ use solana_program::rent::Rent;
 use solana_program::system_program;
 use solana_program::sysvar::{Sysvar, SysvarId};
 
-use crate::find_reward_pool_program_address;
-use crate::state::{RewardPool, DeprecatedRewardPool, RewardsRoot};
+use crate::state::{DeprecatedRewardPool, RewardPool, RewardsRoot, ZeroCopy};
+use crate::{find_reward_pool_program_address, find_reward_pool_spl_token_account};
 
 /// Instruction context
 pub struct MigratePoolContext<'a, 'b> {
@@ -47,11 +47,17 @@ impl<'a, 'b> MigratePoolContext<'a, 'b> {
     }
 
     /// Process instruction
-    pub fn process(&self, program_id: &Pubkey, max_stakers: u64, total_stakers: u64) -> ProgramResult {
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
         let rent = Rent::from_account_info(self.rent)?;
 
         let deprecated_pool = DeprecatedRewardPool::unpack(&self.reward_pool.data.borrow())?;
-        let reward_pool = RewardPool::migrate(&deprecated_pool, max_stakers, total_stakers);
+        let (_, authority_bump) = find_program_address(program_id, self.reward_pool.key);
+        let (_, spl_bump) = find_reward_pool_spl_token_account(
+            program_id,
+            self.reward_pool.key,
+            &deprecated_pool.liquidity_mint,
+        );
+        let reward_pool = RewardPool::migrate(&deprecated_pool, authority_bump, spl_bump);
 
         let (reward_pool_pubkey, _) = find_reward_pool_program_address(
             program_id,
@@ -67,9 +73,17 @@ impl<'a, 'b> MigratePoolContext<'a, 'b> {
             assert_account_key(self.liquidity_mint, &deprecated_pool.liquidity_mint)?;
         }
 
-        realloc_with_rent(self.reward_pool, self.payer, &rent, RewardPool::LEN)?;
+        realloc_with_rent(
+            self.reward_pool,
+            self.payer,
+            &rent,
+            RewardPool::account_len(reward_pool.vaults_capacity, reward_pool.tiers_capacity),
+        )?;
 
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        reward_pool_data.fill(0);
+        *RewardPool::load_unchecked_mut(&mut reward_pool_data)? = reward_pool;
+        RewardPool::load_account_mut(&mut reward_pool_data)?.migrate_vaults(&deprecated_pool)?;
 
         Ok(())
     }
@@ -11,6 +11,32 @@ mod upgrade_mining;
 mod withdraw_mining;
 mod migrate_pool;
 mod migrate_mining;
+mod resize_pool;
+mod update_pool;
+mod evict_dust;
+mod initialize_stakers_index;
+mod request_unstake;
+mod finalize_unstake;
+mod cancel_unstake;
+mod set_pool_fees;
+mod settle_referral_rewards;
+mod add_boost_source;
+mod update_boost_source;
+mod refresh_boost;
+mod set_tier_prices;
+mod buy_tier;
+mod initialize_receipt_mint;
+mod sync_receipt;
+mod open_position;
+mod move_position;
+mod tokenize_position;
+mod start_pool_closing;
+mod force_unstake;
+mod close_pool;
+mod force_withdraw;
+mod freeze_mining;
+mod unfreeze_mining;
+mod update_denylist;
 
 pub use add_vault::*;
 pub use claim::*;
@@ -23,3 +49,29 @@ pub use upgrade_mining::*;
 pub use withdraw_mining::*;
 pub use migrate_pool::*;
 pub use migrate_mining::*;
+pub use resize_pool::*;
+pub use update_pool::*;
+pub use evict_dust::*;
+pub use initialize_stakers_index::*;
+pub use request_unstake::*;
+pub use finalize_unstake::*;
+pub use cancel_unstake::*;
+pub use set_pool_fees::*;
+pub use settle_referral_rewards::*;
+pub use add_boost_source::*;
+pub use update_boost_source::*;
+pub use refresh_boost::*;
+pub use set_tier_prices::*;
+pub use buy_tier::*;
+pub use initialize_receipt_mint::*;
+pub use sync_receipt::*;
+pub use open_position::*;
+pub use move_position::*;
+pub use tokenize_position::*;
+pub use start_pool_closing::*;
+pub use force_unstake::*;
+pub use close_pool::*;
+pub use force_withdraw::*;
+pub use freeze_mining::*;
+pub use unfreeze_mining::*;
+pub use update_denylist::*;
@@ -0,0 +1,89 @@
+use crate::state::{Mining, RewardPool};
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+
+/// Instruction context of split and merge of user positions
+pub struct MovePositionContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    source_mining: &'a AccountInfo<'b>,
+    destination_mining: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> MovePositionContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<MovePositionContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let source_mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let destination_mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        Ok(MovePositionContext {
+            reward_pool,
+            source_mining,
+            destination_mining,
+            user,
+            clock,
+        })
+    }
+
+    /// Process instruction, moves the amount or the whole source position
+    pub fn process(&self, program_id: &Pubkey, amount: Option<u64>) -> ProgramResult {
+        if self.source_mining.key == self.destination_mining.key {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        // grow mining accounts if the pool has new vaults
+        for mining in [self.source_mining, self.destination_mining].iter() {
+            let mining_len = {
+                let reward_pool_data = self.reward_pool.data.borrow();
+                let mining_data = mining.data.borrow();
+                Mining::load_account(&mining_data)?
+                    .required_len(&RewardPool::load_account(&reward_pool_data)?)
+            };
+            if mining.data_len() < mining_len {
+                realloc_with_rent(mining, self.user, &Rent::get()?, mining_len)?;
+            }
+        }
+
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+        let mut source_data = self.source_mining.data.borrow_mut();
+        let mut source = Mining::load_account_mut(&mut source_data)?;
+        let mut destination_data = self.destination_mining.data.borrow_mut();
+        let mut destination = Mining::load_account_mut(&mut destination_data)?;
+
+        for (mining_info, mining) in [
+            (self.source_mining, *source.mining),
+            (self.destination_mining, *destination.mining),
+        ]
+        .iter()
+        {
+            assert_account_key(mining_info, &mining.address(program_id)?)?;
+            assert_account_key(self.user, &mining.owner)?;
+            assert_account_key(self.reward_pool, &mining.reward_pool)?;
+        }
+
+        let amount = amount.unwrap_or(source.mining.amount);
+        reward_pool.move_stake(&mut source, &mut destination, amount, timestamp)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::find_mining_position_address;
+use crate::state::{Mining, RewardPool, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+
+/// Instruction context
+pub struct OpenPositionContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> OpenPositionContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<OpenPositionContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_uninitialized(account_info_iter)?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        Ok(OpenPositionContext {
+            reward_pool,
+            mining,
+            user,
+            rent,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey, index: u16) -> ProgramResult {
+        let vaults_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+
+            // receipts follow the default position only
+            if index > 0 && reward_pool.has_receipt() {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.vaults_len
+        };
+
+        let (mining_pubkey, bump) =
+            find_mining_position_address(program_id, self.user.key, self.reward_pool.key, index);
+        assert_account_key(self.mining, &mining_pubkey)?;
+
+        let position_index = index.to_le_bytes();
+        let mut signers_seeds = vec![
+            "mining".as_bytes(),
+            self.user.key.as_ref(),
+            self.reward_pool.key.as_ref(),
+        ];
+        if index > 0 {
+            signers_seeds.push(&position_index);
+        }
+        let bump_seed = [bump];
+        signers_seeds.push(&bump_seed);
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.user.clone(),
+            self.mining.clone(),
+            &[&signers_seeds],
+            &Rent::from_account_info(self.rent)?,
+            Mining::account_len(vaults_len as usize),
+        )?;
+
+        let mut mining = Mining::initialize(*self.reward_pool.key, bump, *self.user.key);
+        mining.position_index = index;
+        *Mining::load_unchecked_mut(&mut self.mining.data.borrow_mut())? = mining;
+        RewardPool::load_mut(&mut self.reward_pool.data.borrow_mut())?.open_mining()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::events::RewardsEvent;
+use crate::state::{BoostKind, BoostSource, Mining, RewardPool, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::Account;
+
+/// Instruction context
+pub struct RefreshBoostContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+    boost_sources: Vec<(&'a AccountInfo<'b>, &'a AccountInfo<'b>)>,
+}
+
+impl<'a, 'b> RefreshBoostContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<RefreshBoostContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let payer = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        // every boost source of the pool followed by the owner associated token account
+        // for token sources or the owner token account holding a mint of the list
+        let mut boost_sources = Vec::new();
+        while AccountLoader::has_more(account_info_iter) {
+            boost_sources.push((
+                AccountLoader::next_with_owner(account_info_iter, program_id)?,
+                AccountLoader::next_unchecked(account_info_iter)?,
+            ));
+        }
+
+        Ok(RefreshBoostContext {
+            reward_pool,
+            mining,
+            payer,
+            clock,
+            boost_sources,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.payer, &Rent::get()?, mining_len)?;
+        }
+
+        let reward_pool_data = self.reward_pool.data.borrow();
+        let reward_pool = RewardPool::load_account(&reward_pool_data)?;
+        let mut mining_data = self.mining.data.borrow_mut();
+        let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+        {
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+            assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+        }
+
+        // all sources are required so the boost can't be dropped by leaving one out
+        if self.boost_sources.len() != reward_pool.pool.boost_sources_len as usize {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+
+        let owner = mining.mining.owner;
+        let is_owner = *self.payer.key == owner;
+
+        let mut boost_bps: u16 = 0;
+        let mut held_boost_sources = 0;
+        for (index, (boost_source_info, token_account_info)) in
+            self.boost_sources.iter().enumerate()
+        {
+            let boost_source_data = boost_source_info.data.borrow();
+            let boost_source = BoostSource::load(&boost_source_data)?;
+            let boost_source_pubkey = Pubkey::create_program_address(
+                &[
+                    b"boost".as_ref(),
+                    self.reward_pool.key.as_ref(),
+                    &[index as u8],
+                    &[boost_source.bump],
+                ],
+                program_id,
+            )?;
+            assert_account_key(boost_source_info, &boost_source_pubkey)?;
+
+            let source_bit = 1u64 << index;
+            let is_held = match boost_source.boost_kind() {
+                BoostKind::Token => {
+                    // only the balance of the owner associated token account counts
+                    let (token_account, _) =
+                        find_associated_token_account(&owner, &boost_source.mints()[0]);
+                    assert_account_key(token_account_info, &token_account)?;
+                    Self::is_holder(boost_source, &owner, token_account_info)?
+                }
+                // only the owner can prove it holds none of the mints
+                BoostKind::MintList => {
+                    Self::is_holder(boost_source, &owner, token_account_info)?
+                        || (!is_owner && mining.mining.held_boost_sources & source_bit != 0)
+                }
+            };
+            if !is_held {
+                continue;
+            }
+
+            held_boost_sources |= source_bit;
+            boost_bps = boost_bps
+                .checked_add(boost_source.multiplier_bps)
+                .ok_or(EverlendError::MathOverflow)?;
+        }
+
+        // settle rewards at the old multiplier
+        mining.refresh_rewards(reward_pool.iter_vaults(), timestamp)?;
+        mining.mining.boost_bps = boost_bps;
+        mining.mining.held_boost_sources = held_boost_sources;
+
+        RewardsEvent::BoostRefreshed {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            boost_bps,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Checks the token account of the owner qualifies for the boost source,
+    /// missing accounts don't
+    fn is_holder(
+        boost_source: &BoostSource,
+        owner: &Pubkey,
+        token_account_info: &AccountInfo,
+    ) -> Result<bool, ProgramError> {
+        if *token_account_info.owner != spl_token::id()
+            || token_account_info.data_len() != Account::LEN
+        {
+            return Ok(false);
+        }
+
+        let token_account = Account::unpack(&token_account_info.data.borrow())?;
+        Ok(boost_source.holder_multiplier_bps(owner, &token_account) > 0)
+    }
+}
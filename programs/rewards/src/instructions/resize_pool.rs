@@ -0,0 +1,85 @@
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+
+use crate::state::{RewardPool, RewardsRoot, ZeroCopy};
+
+/// Instruction context
+pub struct ResizePoolContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> ResizePoolContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<ResizePoolContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        Ok(ResizePoolContext {
+            rewards_root,
+            reward_pool,
+            authority,
+            rent,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(
+        &self,
+        _program_id: &Pubkey,
+        vaults_capacity: u8,
+        tiers_capacity: u8,
+    ) -> ProgramResult {
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+            // the pool can only grow
+            if vaults_capacity < reward_pool.vaults_capacity
+                || tiers_capacity < reward_pool.tiers_capacity
+                || (vaults_capacity == reward_pool.vaults_capacity
+                    && tiers_capacity == reward_pool.tiers_capacity)
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+        }
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        realloc_with_rent(
+            self.reward_pool,
+            self.authority,
+            &Rent::from_account_info(self.rent)?,
+            RewardPool::account_len(vaults_capacity, tiers_capacity),
+        )?;
+
+        RewardPool::resize(
+            &mut self.reward_pool.data.borrow_mut(),
+            vaults_capacity,
+            tiers_capacity,
+        )
+    }
+}
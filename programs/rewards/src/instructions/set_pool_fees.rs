@@ -0,0 +1,100 @@
+use crate::events::RewardsEvent;
+use crate::state::{RewardPool, RewardsRoot, VaultKind};
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError, BPS_SCALER};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+/// Instruction context
+pub struct SetPoolFeesContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SetPoolFeesContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<SetPoolFeesContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+
+        Ok(SetPoolFeesContext {
+            rewards_root,
+            reward_pool,
+            authority,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(
+        &self,
+        _program_id: &Pubkey,
+        treasury: Pubkey,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        claim_fee_bps: u16,
+        is_fee_to_vault: bool,
+    ) -> ProgramResult {
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        if [deposit_fee_bps, withdraw_fee_bps, claim_fee_bps]
+            .iter()
+            .any(|fee_bps| *fee_bps as u128 > BPS_SCALER)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // claim fees are paid in reward mints, so they always go to the treasury
+        let has_treasury = treasury != Pubkey::default();
+        if claim_fee_bps > 0 && !has_treasury {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        if is_fee_to_vault {
+            // fees are liquidity tokens, so only a vault of the liquidity mint can take them
+            let vault = reward_pool
+                .to_ref()
+                .find_vault(&reward_pool.pool.liquidity_mint)
+                .copied()
+                .ok_or(EverlendError::InvalidRewardVault)?;
+            if vault.vault_kind() != VaultKind::Transfer {
+                return Err(EverlendError::InvalidRewardVault.into());
+            }
+        } else if (deposit_fee_bps > 0 || withdraw_fee_bps > 0) && !has_treasury {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        reward_pool.pool.treasury = treasury;
+        reward_pool.pool.deposit_fee_bps = deposit_fee_bps;
+        reward_pool.pool.withdraw_fee_bps = withdraw_fee_bps;
+        reward_pool.pool.claim_fee_bps = claim_fee_bps;
+        reward_pool.pool.is_fee_to_vault = is_fee_to_vault.into();
+
+        RewardsEvent::PoolFeesUpdated {
+            reward_pool: *self.reward_pool.key,
+            treasury,
+            deposit_fee_bps,
+            withdraw_fee_bps,
+            claim_fee_bps,
+            is_fee_to_vault,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use crate::state::{RewardPool, RewardsRoot, ZeroCopy, MAX_TIER_PRICES};
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+/// Instruction context
+pub struct SetTierPricesContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    burn_mint: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SetTierPricesContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<SetTierPricesContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let burn_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+
+        Ok(SetTierPricesContext {
+            rewards_root,
+            reward_pool,
+            burn_mint,
+            authority,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(
+        &self,
+        _program_id: &Pubkey,
+        prices: Vec<u64>,
+        tier_duration_sec: u64,
+    ) -> ProgramResult {
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        // the base tier is never bought
+        if prices.len() > MAX_TIER_PRICES
+            || prices.len() > reward_pool.tiers_capacity as usize
+            || prices.first().copied().unwrap_or_default() != 0
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // bought tiers keep their expiry
+        reward_pool.burn_mint = *self.burn_mint.key;
+        reward_pool.tier_duration_sec = tier_duration_sec;
+        reward_pool.tier_prices = [0; MAX_TIER_PRICES];
+        reward_pool.tier_prices[..prices.len()].copy_from_slice(&prices);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::events::RewardsEvent;
+use crate::find_mining_program_address;
+use crate::state::{Mining, RewardPool, ZeroCopy};
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use std::iter::Enumerate;
+
+/// Instruction context
+pub struct SettleReferralRewardsContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    referrer: ReferrerAccounts<'a, 'b>,
+    payer: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+}
+
+/// Mining of the referrer credited with the referral rewards and the referrer wallet
+pub struct ReferrerAccounts<'a, 'b> {
+    referrer_mining: &'a AccountInfo<'b>,
+    referrer: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SettleReferralRewardsContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<SettleReferralRewardsContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let referrer = ReferrerAccounts::new(program_id, account_info_iter)?;
+        let payer = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        Ok(SettleReferralRewardsContext {
+            reward_pool,
+            mining,
+            referrer,
+            payer,
+            clock,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        self.referrer.settle(
+            program_id,
+            self.reward_pool,
+            self.mining,
+            self.payer,
+            timestamp,
+        )
+    }
+}
+
+impl<'a, 'b> ReferrerAccounts<'a, 'b> {
+    /// Loads the referrer mining and the referrer wallet
+    pub fn new<I: Iterator<Item = &'a AccountInfo<'b>>>(
+        program_id: &Pubkey,
+        account_info_iter: &mut Enumerate<I>,
+    ) -> Result<ReferrerAccounts<'a, 'b>, ProgramError> {
+        // referrers without a position get a mining on the fly
+        let referrer_mining = AccountLoader::next_optional(account_info_iter, program_id)?;
+        let referrer = AccountLoader::next_unchecked(account_info_iter)?;
+
+        Ok(ReferrerAccounts {
+            referrer_mining,
+            referrer,
+        })
+    }
+
+    /// Credits rewards accrued for the referrer up to the timestamp to the referrer mining
+    pub fn settle(
+        &self,
+        program_id: &Pubkey,
+        reward_pool: &'a AccountInfo<'b>,
+        mining: &'a AccountInfo<'b>,
+        payer: &'a AccountInfo<'b>,
+        timestamp: u64,
+    ) -> ProgramResult {
+        {
+            let mining_data = mining.data.borrow();
+            let mining_account = Mining::load(&mining_data)?;
+            assert_account_key(mining, &mining_account.address(program_id)?)?;
+            assert_account_key(reward_pool, &mining_account.reward_pool)?;
+
+            if !mining_account.is_referred() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            assert_account_key(self.referrer, &mining_account.referrer)?;
+        }
+
+        self.check_and_init_referrer_mining(program_id, reward_pool, payer)?;
+
+        // grow mining accounts if the pool has new vaults
+        let rent = Rent::get()?;
+        for mining_info in [mining, self.referrer_mining] {
+            let mining_len = {
+                let reward_pool_data = reward_pool.data.borrow();
+                let mining_data = mining_info.data.borrow();
+                Mining::load_account(&mining_data)?
+                    .required_len(&RewardPool::load_account(&reward_pool_data)?)
+            };
+            if mining_info.data_len() < mining_len {
+                realloc_with_rent(mining_info, payer, &rent, mining_len)?;
+            }
+        }
+
+        let reward_pool_data = reward_pool.data.borrow();
+        let reward_pool_account = RewardPool::load_account(&reward_pool_data)?;
+
+        let mut mining_data = mining.data.borrow_mut();
+        let mut mining_account = Mining::load_account_mut(&mut mining_data)?;
+        mining_account.refresh_rewards(reward_pool_account.iter_vaults(), timestamp)?;
+
+        let mut referrer_mining_data = self.referrer_mining.data.borrow_mut();
+        let mut referrer_mining = Mining::load_account_mut(&mut referrer_mining_data)?;
+
+        for (reward_mint, amount) in mining_account.take_referral_rewards() {
+            referrer_mining.credit_rewards(reward_mint, amount)?;
+
+            RewardsEvent::ReferralRewardsSettled {
+                reward_pool: *reward_pool.key,
+                mining: *mining.key,
+                referrer: *self.referrer.key,
+                reward_mint,
+                amount,
+            }
+            .emit();
+        }
+
+        Ok(())
+    }
+
+    /// Creates a mining account for the referrer without a position in the pool
+    fn check_and_init_referrer_mining(
+        &self,
+        program_id: &Pubkey,
+        reward_pool: &'a AccountInfo<'b>,
+        payer: &'a AccountInfo<'b>,
+    ) -> ProgramResult {
+        let (referrer_mining_pubkey, bump) =
+            find_mining_program_address(program_id, self.referrer.key, reward_pool.key);
+        assert_account_key(self.referrer_mining, &referrer_mining_pubkey)?;
+
+        if self.referrer_mining.owner.eq(program_id) {
+            return Ok(());
+        }
+
+        let vaults_len = {
+            let reward_pool_data = reward_pool.data.borrow();
+            RewardPool::load(&reward_pool_data)?.vaults_len
+        };
+
+        let signers_seeds = &[
+            "mining".as_bytes(),
+            &self.referrer.key.to_bytes(),
+            &reward_pool.key.to_bytes(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            payer.clone(),
+            self.referrer_mining.clone(),
+            &[signers_seeds],
+            &Rent::get()?,
+            Mining::account_len(vaults_len as usize),
+        )?;
+
+        *Mining::load_unchecked_mut(&mut self.referrer_mining.data.borrow_mut())? =
+            Mining::initialize(*reward_pool.key, bump, *self.referrer.key);
+        RewardPool::load_mut(&mut reward_pool.data.borrow_mut())?.open_mining()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use crate::events::RewardsEvent;
+use crate::state::{PoolStatus, RewardPool, RewardsRoot, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::{clock, Sysvar};
+
+/// Instruction context
+pub struct StartPoolClosingContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> StartPoolClosingContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<StartPoolClosingContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        Ok(StartPoolClosingContext {
+            rewards_root,
+            reward_pool,
+            authority,
+            clock,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, _program_id: &Pubkey, grace_period_sec: u64) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        if reward_pool.pool_status() == PoolStatus::Closing {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // stakers get at least the pool lock to exit before being force unstaked
+        if grace_period_sec == 0 || grace_period_sec < reward_pool.lock_time_sec {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        reward_pool.status = PoolStatus::Closing as u8;
+        reward_pool.closes_at = timestamp
+            .checked_add(grace_period_sec)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        RewardsEvent::PoolClosingStarted {
+            reward_pool: *self.reward_pool.key,
+            closes_at: reward_pool.closes_at,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use crate::events::RewardsEvent;
+use crate::find_mining_program_address;
+use crate::state::{Mining, PoolStatus, RewardPool, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::clock::Clock;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::{Account, Mint};
+
+/// Instruction context
+pub struct SyncReceiptContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    receipt_mint: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    owner_receipt_account: &'a AccountInfo<'b>,
+    owner: &'a AccountInfo<'b>,
+    payer: &'a AccountInfo<'b>,
+    clock: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> SyncReceiptContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<SyncReceiptContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let receipt_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        // unchecked so we can create on the fly
+        let mining = AccountLoader::next_unchecked(account_info_iter)?;
+        // unchecked as the owner may have closed the account
+        let owner_receipt_account = AccountLoader::next_unchecked(account_info_iter)?;
+        let owner = AccountLoader::next_unchecked(account_info_iter)?;
+        let payer = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+
+        Ok(SyncReceiptContext {
+            reward_pool,
+            receipt_mint,
+            mining,
+            owner_receipt_account,
+            owner,
+            payer,
+            clock,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            if !reward_pool.has_receipt() {
+                return Err(ProgramError::InvalidArgument);
+            }
+            // positions of a closing pool are force unstaked regardless of receipts
+            if reward_pool.pool_status() == PoolStatus::Closing {
+                return Err(ProgramError::InvalidArgument);
+            }
+            assert_account_key(self.receipt_mint, &reward_pool.receipt_mint)?;
+        }
+
+        // positions follow receipts held in the owner's associated token account only
+        let (owner_receipt_account, _) =
+            find_associated_token_account(self.owner.key, self.receipt_mint.key);
+        assert_account_key(self.owner_receipt_account, &owner_receipt_account)?;
+        let balance = if self.owner_receipt_account.owner == &spl_token::id() {
+            Account::unpack(&self.owner_receipt_account.data.borrow())?.amount
+        } else {
+            0
+        };
+
+        if self.mining.owner.eq(&Pubkey::default()) {
+            if balance == 0 {
+                return Ok(());
+            }
+            self.create_mining_acc(program_id)?;
+        }
+        if self.mining.owner != program_id {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.payer, &Rent::get()?, mining_len)?;
+        }
+
+        let receipt_supply = Mint::unpack(&self.receipt_mint.data.borrow())?.supply;
+
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+        let mut mining_data = self.mining.data.borrow_mut();
+        let mut mining = Mining::load_account_mut(&mut mining_data)?;
+
+        {
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+            assert_account_key(self.owner, &mining.mining.owner)?;
+            assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+        }
+
+        reward_pool.sync_receipt(&mut mining, balance, receipt_supply, timestamp)?;
+
+        RewardsEvent::ReceiptSynced {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            owner: *self.owner.key,
+            amount: mining.mining.amount,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Creates a mining account for the receipt holder
+    fn create_mining_acc(&self, program_id: &Pubkey) -> ProgramResult {
+        let (mining_pubkey, bump) =
+            find_mining_program_address(program_id, self.owner.key, self.reward_pool.key);
+        assert_account_key(self.mining, &mining_pubkey)?;
+
+        let vaults_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            RewardPool::load(&reward_pool_data)?.vaults_len
+        };
+
+        let signers_seeds = &[
+            "mining".as_bytes(),
+            &self.owner.key.to_bytes(),
+            &self.reward_pool.key.to_bytes(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account_with_len(
+            program_id,
+            self.payer.clone(),
+            self.mining.clone(),
+            &[signers_seeds],
+            &Rent::get()?,
+            Mining::account_len(vaults_len as usize),
+        )?;
+
+        *Mining::load_unchecked_mut(&mut self.mining.data.borrow_mut())? =
+            Mining::initialize(*self.reward_pool.key, bump, *self.owner.key);
+        RewardPool::load_mut(&mut self.reward_pool.data.borrow_mut())?.open_mining()?;
+
+        Ok(())
+    }
+}
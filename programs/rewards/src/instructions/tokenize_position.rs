@@ -0,0 +1,159 @@
+use crate::events::RewardsEvent;
+use crate::find_position_mint_program_address;
+use crate::state::{Mining, RewardPool, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+use spl_token::instruction::AuthorityType;
+use spl_token::state::{Account, Mint};
+
+/// Instruction context
+pub struct TokenizePositionContext<'a, 'b> {
+    reward_pool: &'a AccountInfo<'b>,
+    mining: &'a AccountInfo<'b>,
+    position_mint: &'a AccountInfo<'b>,
+    user_position_account: &'a AccountInfo<'b>,
+    user: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> TokenizePositionContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<TokenizePositionContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let position_mint = AccountLoader::next_uninitialized(account_info_iter)?;
+        let user_position_account = AccountLoader::next_uninitialized(account_info_iter)?;
+        let user = AccountLoader::next_signer(account_info_iter)?;
+        let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        Ok(TokenizePositionContext {
+            reward_pool,
+            mining,
+            position_mint,
+            user_position_account,
+            user,
+            rent,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
+        let (rewards_root, liquidity_mint, pool_bump) = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+
+            // receipts, stakers index and unstake requests are bound to the owner
+            if reward_pool.has_receipt()
+                || bool::from(reward_pool.is_competitive)
+                || reward_pool.unbonding_period_sec > 0
+            {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            (
+                reward_pool.rewards_root,
+                reward_pool.liquidity_mint,
+                reward_pool.bump,
+            )
+        };
+
+        let amount = {
+            let mining_data = self.mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+
+            assert_account_key(self.mining, &mining.address(program_id)?)?;
+            assert_account_key(self.user, &mining.owner)?;
+            assert_account_key(self.reward_pool, &mining.reward_pool)?;
+            mining.assert_owner_controlled()?;
+            mining.assert_not_frozen()?;
+
+            mining.amount
+        };
+
+        let (position_mint_pubkey, bump) =
+            find_position_mint_program_address(program_id, self.mining.key);
+        assert_account_key(self.position_mint, &position_mint_pubkey)?;
+
+        let rent = Rent::from_account_info(self.rent)?;
+        everlend_utils::cpi::system::create_account::<Mint>(
+            &spl_token::id(),
+            self.user.clone(),
+            self.position_mint.clone(),
+            &[&[b"position".as_ref(), self.mining.key.as_ref(), &[bump]]],
+            &rent,
+        )?;
+        everlend_utils::cpi::spl_token::initialize_mint(
+            self.position_mint.clone(),
+            self.reward_pool.clone(),
+            self.rent.clone(),
+            0,
+        )?;
+
+        everlend_utils::cpi::system::create_account::<Account>(
+            &spl_token::id(),
+            self.user.clone(),
+            self.user_position_account.clone(),
+            &[],
+            &rent,
+        )?;
+        everlend_utils::cpi::spl_token::initialize_account(
+            self.user_position_account.clone(),
+            self.position_mint.clone(),
+            self.user.clone(),
+            self.rent.clone(),
+        )?;
+
+        // a single token is minted and the supply is fixed for good
+        let reward_pool_seeds = &[
+            b"reward_pool".as_ref(),
+            rewards_root.as_ref(),
+            liquidity_mint.as_ref(),
+            &[pool_bump],
+        ];
+        everlend_utils::cpi::spl_token::mint_to(
+            self.position_mint.clone(),
+            self.user_position_account.clone(),
+            self.reward_pool.clone(),
+            1,
+            &[reward_pool_seeds],
+        )?;
+        everlend_utils::cpi::spl_token::set_authority(
+            self.position_mint.clone(),
+            self.reward_pool.clone(),
+            None,
+            AuthorityType::MintTokens,
+            &[reward_pool_seeds],
+        )?;
+
+        {
+            let mut mining_data = self.mining.data.borrow_mut();
+            let mining = Mining::load_mut(&mut mining_data)?;
+            mining.is_tokenized = true.into();
+            mining.position_mint_bump = bump;
+        }
+
+        RewardsEvent::PositionTokenized {
+            reward_pool: *self.reward_pool.key,
+            mining: *self.mining.key,
+            position_mint: *self.position_mint.key,
+            amount,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use crate::state::{BoostKind, BoostSource, RewardPool, RewardsRoot, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader, BPS_SCALER};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+/// Instruction context
+pub struct UpdateBoostSourceContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    boost_source: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> UpdateBoostSourceContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<UpdateBoostSourceContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let boost_source = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+
+        Ok(UpdateBoostSourceContext {
+            rewards_root,
+            reward_pool,
+            boost_source,
+            authority,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(
+        &self,
+        _program_id: &Pubkey,
+        multiplier_bps: Option<u16>,
+        min_amount: Option<u64>,
+    ) -> ProgramResult {
+        {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        let mut boost_source_data = self.boost_source.data.borrow_mut();
+        let boost_source = BoostSource::load_mut(&mut boost_source_data)?;
+        assert_account_key(self.reward_pool, &boost_source.reward_pool)?;
+
+        // holders keep the old multiplier until their boost is refreshed
+        if let Some(multiplier_bps) = multiplier_bps {
+            if multiplier_bps as u128 > BPS_SCALER {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            boost_source.multiplier_bps = multiplier_bps;
+        }
+
+        if let Some(min_amount) = min_amount {
+            if boost_source.kind != BoostKind::Token as u8 {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            boost_source.min_amount = min_amount;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use crate::events::RewardsEvent;
+use crate::find_denylist_program_address;
+use crate::state::{Denylist, RewardPool, RewardsRoot, ZeroCopy};
+use everlend_utils::cpi::system::realloc_with_rent;
+use everlend_utils::{assert_account_key, AccountLoader};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
+use solana_program::sysvar::{Sysvar, SysvarId};
+
+/// Instruction context
+pub struct UpdateDenylistContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    denylist: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+    rent: &'a AccountInfo<'b>,
+    reward_pools: Vec<&'a AccountInfo<'b>>,
+}
+
+impl<'a, 'b> UpdateDenylistContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<UpdateDenylistContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        // created on the first denied wallet
+        let denylist = AccountLoader::next_optional(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+        let _system_program =
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        let rent = AccountLoader::next_with_key(account_info_iter, &Rent::id())?;
+
+        // pools whose deposits are checked against the denylist from now on
+        let mut reward_pools = Vec::new();
+        while AccountLoader::has_more(account_info_iter) {
+            reward_pools.push(AccountLoader::next_with_owner(
+                account_info_iter,
+                program_id,
+            )?);
+        }
+
+        Ok(UpdateDenylistContext {
+            rewards_root,
+            denylist,
+            authority,
+            rent,
+            reward_pools,
+        })
+    }
+
+    /// Process instruction
+    pub fn process(&self, program_id: &Pubkey, wallet: Pubkey, is_denied: bool) -> ProgramResult {
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        let (denylist_pubkey, bump) =
+            find_denylist_program_address(program_id, self.rewards_root.key);
+        assert_account_key(self.denylist, &denylist_pubkey)?;
+
+        let rent = Rent::from_account_info(self.rent)?;
+        if self.denylist.data_is_empty() {
+            let signers_seeds = &[
+                b"denylist".as_ref(),
+                self.rewards_root.key.as_ref(),
+                &[bump],
+            ];
+
+            everlend_utils::cpi::system::create_account_with_len(
+                program_id,
+                self.authority.clone(),
+                self.denylist.clone(),
+                &[signers_seeds],
+                &rent,
+                Denylist::account_len(0),
+            )?;
+
+            *Denylist::load_unchecked_mut(&mut self.denylist.data.borrow_mut())? =
+                Denylist::initialize(*self.rewards_root.key, bump);
+        }
+
+        for reward_pool in self.reward_pools.iter() {
+            let mut reward_pool_data = reward_pool.data.borrow_mut();
+            let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+            assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+            reward_pool.has_denylist = true.into();
+            reward_pool.denylist_bump = bump;
+        }
+
+        let (is_listed, len) = {
+            let denylist_data = self.denylist.data.borrow();
+            let denylist = Denylist::load_account(&denylist_data)?;
+            (denylist.contains(&wallet), denylist.denylist.len)
+        };
+
+        // listing a wallet twice or removing an unlisted one changes nothing
+        if is_denied && !is_listed {
+            realloc_with_rent(
+                self.denylist,
+                self.authority,
+                &rent,
+                Denylist::account_len(len + 1),
+            )?;
+            Denylist::load_account_mut(&mut self.denylist.data.borrow_mut())?.push(wallet)?;
+        } else if !is_denied && is_listed {
+            Denylist::load_account_mut(&mut self.denylist.data.borrow_mut())?.remove(&wallet);
+            self.shrink(&rent, Denylist::account_len(len - 1))?;
+        }
+
+        RewardsEvent::DenylistUpdated {
+            rewards_root: *self.rewards_root.key,
+            wallet,
+            is_denied,
+        }
+        .emit();
+
+        Ok(())
+    }
+
+    /// Shrinks the denylist and returns the freed rent to the authority
+    fn shrink(&self, rent: &Rent, new_len: usize) -> ProgramResult {
+        self.denylist.realloc(new_len, false)?;
+
+        let excess = self
+            .denylist
+            .lamports()
+            .saturating_sub(rent.minimum_balance(new_len));
+        **self.denylist.lamports.borrow_mut() -= excess;
+        **self.authority.lamports.borrow_mut() += excess;
+
+        Ok(())
+    }
+}
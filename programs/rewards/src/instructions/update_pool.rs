@@ -0,0 +1,175 @@
+use crate::events::RewardsEvent;
+use crate::state::{PoolStatus, RewardPool, RewardsRoot, ZeroCopy};
+use everlend_utils::{assert_account_key, AccountLoader, BPS_SCALER};
+use solana_program::account_info::AccountInfo;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::program_pack::Pack;
+use solana_program::pubkey::Pubkey;
+
+/// Instruction context
+pub struct UpdatePoolContext<'a, 'b> {
+    rewards_root: &'a AccountInfo<'b>,
+    reward_pool: &'a AccountInfo<'b>,
+    authority: &'a AccountInfo<'b>,
+}
+
+impl<'a, 'b> UpdatePoolContext<'a, 'b> {
+    /// New instruction context
+    pub fn new(
+        program_id: &Pubkey,
+        accounts: &'a [AccountInfo<'b>],
+    ) -> Result<UpdatePoolContext<'a, 'b>, ProgramError> {
+        let account_info_iter = &mut accounts.iter().enumerate();
+
+        let rewards_root = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let reward_pool = AccountLoader::next_with_owner(account_info_iter, program_id)?;
+        let authority = AccountLoader::next_signer(account_info_iter)?;
+
+        Ok(UpdatePoolContext {
+            rewards_root,
+            reward_pool,
+            authority,
+        })
+    }
+
+    /// Process instruction
+    #[allow(clippy::too_many_arguments)]
+    pub fn process(
+        &self,
+        _program_id: &Pubkey,
+        lock_time_sec: Option<u64>,
+        max_stakers: Option<u64>,
+        min_deposit_amount: Option<u64>,
+        max_deposit_amount: Option<u64>,
+        min_stake_amount: Option<u64>,
+        max_stake_amount: Option<u64>,
+        unbonding_period_sec: Option<u64>,
+        is_linear_unbonding: Option<bool>,
+        status: Option<PoolStatus>,
+        referral_share_bps: Option<u16>,
+        warmup_period_sec: Option<u64>,
+        warmup_rate_bps: Option<u16>,
+        max_funder_lock_sec: Option<u64>,
+    ) -> ProgramResult {
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+
+        {
+            let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
+            assert_account_key(self.authority, &rewards_root.authority)?;
+        }
+
+        // a shorter lock applies to existing positions right away,
+        // a longer one only to deposits made after the update
+        if let Some(lock_time_sec) = lock_time_sec {
+            reward_pool.lock_time_sec = lock_time_sec;
+        }
+
+        // lowering the cap below the current stakers keeps existing positions,
+        // new stakers are rejected until the pool drops under the cap
+        if let Some(max_stakers) = max_stakers {
+            // the stakers index of a competitive pool is sized for max stakers
+            if bool::from(reward_pool.is_competitive) {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.max_stakers = max_stakers;
+        }
+
+        if let Some(min_deposit_amount) = min_deposit_amount {
+            reward_pool.min_deposit_amount = min_deposit_amount;
+        }
+
+        if let Some(max_deposit_amount) = max_deposit_amount {
+            reward_pool.max_deposit_amount = max_deposit_amount;
+        }
+
+        if reward_pool.max_deposit_amount > 0
+            && reward_pool.min_deposit_amount > reward_pool.max_deposit_amount
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // positions below a raised min stake amount can be evicted
+        if let Some(min_stake_amount) = min_stake_amount {
+            reward_pool.min_stake_amount = min_stake_amount;
+        }
+
+        if let Some(max_stake_amount) = max_stake_amount {
+            reward_pool.max_stake_amount = max_stake_amount;
+        }
+
+        if reward_pool.max_stake_amount > 0
+            && reward_pool.min_stake_amount > reward_pool.max_stake_amount
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // pending unstake requests keep their unlock time
+        if let Some(unbonding_period_sec) = unbonding_period_sec {
+            reward_pool.unbonding_period_sec = unbonding_period_sec;
+        }
+
+        if let Some(is_linear_unbonding) = is_linear_unbonding {
+            reward_pool.is_linear_unbonding = is_linear_unbonding.into();
+        }
+
+        // closing is started with its grace period and can't be undone
+        if let Some(status) = status {
+            if status == PoolStatus::Closing || reward_pool.pool_status() == PoolStatus::Closing {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.status = status as u8;
+        }
+
+        // referred minings keep the share they were referred with
+        if let Some(referral_share_bps) = referral_share_bps {
+            if referral_share_bps as u128 > BPS_SCALER {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.referral_share_bps = referral_share_bps;
+        }
+
+        // warming amounts keep their warm-up end and rate
+        if let Some(warmup_period_sec) = warmup_period_sec {
+            reward_pool.warmup_period_sec = warmup_period_sec;
+        }
+
+        if let Some(warmup_rate_bps) = warmup_rate_bps {
+            if warmup_rate_bps as u128 > BPS_SCALER {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            reward_pool.warmup_rate_bps = warmup_rate_bps;
+        }
+
+        // locks funders already enforced are kept
+        if let Some(max_funder_lock_sec) = max_funder_lock_sec {
+            reward_pool.max_funder_lock_sec = max_funder_lock_sec;
+        }
+
+        RewardsEvent::PoolUpdated {
+            reward_pool: *self.reward_pool.key,
+            lock_time_sec: reward_pool.lock_time_sec,
+            max_stakers: reward_pool.max_stakers,
+            min_deposit_amount: reward_pool.min_deposit_amount,
+            max_deposit_amount: reward_pool.max_deposit_amount,
+            min_stake_amount: reward_pool.min_stake_amount,
+            max_stake_amount: reward_pool.max_stake_amount,
+            unbonding_period_sec: reward_pool.unbonding_period_sec,
+            is_linear_unbonding: reward_pool.is_linear_unbonding.into(),
+            status: reward_pool.pool_status(),
+            referral_share_bps: reward_pool.referral_share_bps,
+            warmup_period_sec: reward_pool.warmup_period_sec,
+            warmup_rate_bps: reward_pool.warmup_rate_bps,
+            max_funder_lock_sec: reward_pool.max_funder_lock_sec,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
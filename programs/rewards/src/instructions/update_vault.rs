@@ -1,13 +1,15 @@
-use everlend_utils::{assert_account_key, AccountLoader};
+use everlend_utils::{assert_account_key, AccountLoader, EverlendError};
 use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
+use solana_program::program_option::COption;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
 use solana_program::sysvar::Sysvar;
 use solana_program::{account_info::AccountInfo, sysvar::clock};
+use spl_token::state::Mint;
 
-use crate::state::{RewardPool, RewardTier, RewardsRoot};
+use crate::state::{RewardPool, RewardTier, RewardsRoot, VaultKind};
 
 /// Instruction context
 pub struct UpdateVaultContext<'a, 'b> {
@@ -42,25 +44,43 @@ impl<'a, 'b> UpdateVaultContext<'a, 'b> {
     }
 
     /// Process instruction
+    #[allow(clippy::too_many_arguments)]
     pub fn process(
         &self,
         _program_id: &Pubkey,
         reward_period_sec: Option<u32>,
         is_enabled: Option<bool>,
         reward_tiers: Option<Vec<RewardTier>>,
+        manager: Option<Pubkey>,
+        is_fill_permissioned: Option<bool>,
+        kind: Option<VaultKind>,
+        max_mint_amount: Option<u64>,
+        max_mint_amount_per_period: Option<u64>,
+        is_epoch_aligned: Option<bool>,
+        epoch_anchor: Option<u64>,
+        is_partial_epoch_excluded: Option<bool>,
     ) -> ProgramResult {
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
-        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
+        let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+        let mut reward_pool = RewardPool::load_account_mut(&mut reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
 
         {
             let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
             assert_account_key(self.payer, &rewards_root.authority)?;
         }
 
+        // positions keep the old rates up to the change
+        if reward_period_sec.is_some() || reward_tiers.is_some() {
+            let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
+            reward_pool.checkpoint_rates(self.reward_mint.key, timestamp)?;
+        }
+
+        // fees are transferred into the vault of the liquidity mint
+        let is_fee_vault = bool::from(reward_pool.pool.is_fee_to_vault)
+            && *self.reward_mint.key == reward_pool.pool.liquidity_mint;
+
         let vault = reward_pool
-            .vaults
-            .iter_mut()
-            .find(|v| &v.reward_mint == self.reward_mint.key)
+            .find_vault_mut(self.reward_mint.key)
             .ok_or(ProgramError::InvalidArgument)?;
 
         if let Some(reward_period_sec) = reward_period_sec {
@@ -68,19 +88,66 @@ impl<'a, 'b> UpdateVaultContext<'a, 'b> {
         }
 
         if let Some(is_enabled) = is_enabled {
-            if !vault.is_enabled && is_enabled {
+            if !bool::from(vault.is_enabled) && is_enabled {
                 // enabling vault - update the time
                 vault.enabled_at = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
             }
 
-            vault.is_enabled = is_enabled;
+            vault.is_enabled = is_enabled.into();
         }
 
-        if let Some(reward_tiers) = reward_tiers {
-            vault.reward_tiers = reward_tiers;
+        if let Some(manager) = manager {
+            vault.manager = manager;
+        }
+
+        if let Some(is_fill_permissioned) = is_fill_permissioned {
+            vault.is_fill_permissioned = is_fill_permissioned.into();
+        }
+
+        if let Some(kind) = kind {
+            // native vaults hold lamports instead of a token account
+            if kind == VaultKind::Native || vault.vault_kind() == VaultKind::Native {
+                return Err(ProgramError::InvalidArgument);
+            }
+
+            if is_fee_vault && kind != VaultKind::Transfer {
+                return Err(EverlendError::InvalidRewardVault.into());
+            }
+
+            if kind == VaultKind::Mint {
+                let mint = Mint::unpack(&self.reward_mint.data.borrow())?;
+                if mint.mint_authority != COption::Some(*self.reward_pool.key) {
+                    return Err(EverlendError::InvalidMintAuthority.into());
+                }
+            }
+
+            vault.kind = kind as u8;
+        }
+
+        if let Some(max_mint_amount) = max_mint_amount {
+            vault.max_mint_amount = max_mint_amount;
+        }
+
+        if let Some(max_mint_amount_per_period) = max_mint_amount_per_period {
+            vault.max_mint_amount_per_period = max_mint_amount_per_period;
+        }
+
+        // running periods of existing positions end on the next epoch boundary
+        if let Some(is_epoch_aligned) = is_epoch_aligned {
+            vault.is_epoch_aligned = is_epoch_aligned.into();
         }
 
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
+        if let Some(epoch_anchor) = epoch_anchor {
+            vault.epoch_anchor = epoch_anchor;
+        }
+
+        if let Some(is_partial_epoch_excluded) = is_partial_epoch_excluded {
+            vault.is_partial_epoch_excluded = is_partial_epoch_excluded.into();
+        }
+
+        if let Some(reward_tiers) = reward_tiers {
+            reward_pool.set_vault_tiers(self.reward_mint.key, &reward_tiers)?;
+        }
 
         Ok(())
     }
@@ -1,4 +1,5 @@
-use crate::state::{Mining, RewardPool, RewardsRoot, MAX_TIERS};
+use crate::state::{Mining, RewardPool, RewardsRoot};
+use everlend_utils::cpi::system::realloc_with_rent;
 use everlend_utils::{assert_account_key, AccountLoader};
 use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
@@ -6,6 +7,8 @@ use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
+use solana_program::system_program;
 use solana_program::sysvar::{clock, Sysvar};
 
 /// Instruction context
@@ -32,6 +35,10 @@ impl<'a, 'b> UpgradeMiningContext<'a, 'b> {
         let user = AccountLoader::next_unchecked(account_info_iter)?;
         let authority = AccountLoader::next_signer(account_info_iter)?;
         let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
+        // only needed to grow the mining, it's passed after the accounts of older clients
+        if AccountLoader::has_more(account_info_iter) {
+            AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
+        }
 
         Ok(UpgradeMiningContext {
             rewards_root,
@@ -45,44 +52,48 @@ impl<'a, 'b> UpgradeMiningContext<'a, 'b> {
 
     /// Process instruction
     pub fn process(&self, program_id: &Pubkey, tier: u8) -> ProgramResult {
-        let reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
-        assert_account_key(self.rewards_root, &reward_pool.rewards_root)?;
-
         {
             let rewards_root = RewardsRoot::unpack(&self.rewards_root.data.borrow())?;
             assert_account_key(self.authority, &rewards_root.authority)?;
         }
 
-        let mut mining = Mining::unpack(&self.mining.data.borrow())?;
+        // grow mining account if the pool has new vaults
+        let mining_len = {
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let mining_data = self.mining.data.borrow();
+            Mining::load_account(&mining_data)?
+                .required_len(&RewardPool::load_account(&reward_pool_data)?)
+        };
+        if self.mining.data_len() < mining_len {
+            realloc_with_rent(self.mining, self.authority, &Rent::get()?, mining_len)?;
+        }
+
+        let reward_pool_data = self.reward_pool.data.borrow();
+        let reward_pool = RewardPool::load_account(&reward_pool_data)?;
+        assert_account_key(self.rewards_root, &reward_pool.pool.rewards_root)?;
+
+        let mut mining_data = self.mining.data.borrow_mut();
+        let mut mining = Mining::load_account_mut(&mut mining_data)?;
         {
-            let mining_pubkey = Pubkey::create_program_address(
-                &[
-                    b"mining".as_ref(),
-                    self.user.key.as_ref(),
-                    self.reward_pool.key.as_ref(),
-                    &[mining.bump],
-                ],
-                program_id,
-            )?;
-            assert_account_key(self.mining, &mining_pubkey)?;
-            assert_account_key(self.reward_pool, &mining.reward_pool)?;
-            assert_account_key(self.user, &mining.owner)?;
+            assert_account_key(self.mining, &mining.mining.address(program_id)?)?;
+            assert_account_key(self.reward_pool, &mining.mining.reward_pool)?;
+            assert_account_key(self.user, &mining.mining.owner)?;
         }
 
         let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp;
 
-        if tier > MAX_TIERS as u8 - 1 {
+        if tier >= reward_pool.pool.tiers_capacity {
             return Err(ProgramError::InvalidArgument);
         }
 
-        if tier == mining.reward_tier {
+        if tier == mining.mining.reward_tier {
             return Err(ProgramError::InvalidArgument);
         }
 
-        mining.refresh_rewards(reward_pool.vaults.iter(), timestamp as u64)?;
-        mining.reward_tier = tier;
-
-        Mining::pack(mining, *self.mining.data.borrow_mut())?;
+        mining.refresh_rewards(reward_pool.iter_vaults(), timestamp as u64)?;
+        mining.mining.reward_tier = tier;
+        mining.mining.base_tier = tier;
+        mining.mining.tier_expires_at = 0;
 
         Ok(())
     }
@@ -1,14 +1,20 @@
-use crate::find_reward_pool_spl_token_account;
-use crate::state::{Mining, RewardPool};
-use everlend_utils::{assert_account_key, find_program_address, AccountLoader, EverlendError};
+use crate::find_unwrap_program_address;
+use crate::instructions::ReferrerAccounts;
+use crate::state::{Mining, PoolStatus, RewardPool, StakersIndex, ZeroCopy};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
+use everlend_utils::{
+    assert_account_key, assert_owned_by, bps_floor, AccountLoader, EverlendError,
+};
 use solana_program::account_info::AccountInfo;
 use solana_program::clock::Clock;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::program_error::ProgramError;
 use solana_program::program_pack::Pack;
 use solana_program::pubkey::Pubkey;
+use solana_program::rent::Rent;
 use solana_program::system_program;
 use solana_program::sysvar::{clock, Sysvar};
+use spl_token::state::Account;
 
 /// Instruction context
 pub struct WithdrawMiningContext<'a, 'b> {
@@ -20,6 +26,23 @@ pub struct WithdrawMiningContext<'a, 'b> {
     user_token_account: &'a AccountInfo<'b>,
     user: &'a AccountInfo<'b>,
     clock: &'a AccountInfo<'b>,
+    stakers_index: Option<&'a AccountInfo<'b>>,
+    fee_token_account: Option<&'a AccountInfo<'b>>,
+    receipt: Option<ReceiptAccounts<'a, 'b>>,
+    position: Option<PositionAccounts<'a, 'b>>,
+    referrer: Option<ReferrerAccounts<'a, 'b>>,
+}
+
+/// Receipt mint and receipt associated token account of the user
+struct ReceiptAccounts<'a, 'b> {
+    receipt_mint: &'a AccountInfo<'b>,
+    user_receipt_account: &'a AccountInfo<'b>,
+}
+
+/// Position NFT mint and the token account of the holder
+struct PositionAccounts<'a, 'b> {
+    position_mint: &'a AccountInfo<'b>,
+    holder_token_account: &'a AccountInfo<'b>,
 }
 
 impl<'a, 'b> WithdrawMiningContext<'a, 'b> {
@@ -34,8 +57,8 @@ impl<'a, 'b> WithdrawMiningContext<'a, 'b> {
         let reward_pool_authority = AccountLoader::next_uninitialized(account_info_iter)?;
         let liquidity_mint = AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
         let mining = AccountLoader::next_with_owner(account_info_iter, program_id)?;
-        let user_token_account =
-            AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?;
+        // temporary unwrap account when withdrawing lamports from a native pool
+        let user_token_account = AccountLoader::next_unchecked(account_info_iter)?;
         let user = AccountLoader::next_signer(account_info_iter)?;
 
         let _token_program = AccountLoader::next_with_key(account_info_iter, &spl_token::id())?;
@@ -43,6 +66,70 @@ impl<'a, 'b> WithdrawMiningContext<'a, 'b> {
             AccountLoader::next_with_key(account_info_iter, &system_program::id())?;
         let clock = AccountLoader::next_with_key(account_info_iter, &clock::id())?;
 
+        // pools with withdraw fee take the fee token account, receipt pools take the
+        // receipt accounts, tokenized positions take the position accounts and
+        // referred positions take the referrer accounts last
+        let (fee_accounts, receipt_accounts) = {
+            let reward_pool_data = reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            (
+                (reward_pool.withdraw_fee_bps > 0) as usize,
+                reward_pool.has_receipt() as usize * 2,
+            )
+        };
+        let (position_accounts, referrer_accounts) = {
+            let mining_data = mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+            (
+                bool::from(mining.is_tokenized) as usize * 2,
+                mining.is_referred() as usize * 2,
+            )
+        };
+
+        // competitive pools only
+        let stakers_index = if AccountLoader::remaining_len(account_info_iter)
+            > fee_accounts + receipt_accounts + position_accounts + referrer_accounts
+        {
+            Some(AccountLoader::next_optional(account_info_iter, program_id)?)
+        } else {
+            None
+        };
+        let fee_token_account = if fee_accounts > 0 {
+            Some(AccountLoader::next_with_owner(
+                account_info_iter,
+                &spl_token::id(),
+            )?)
+        } else {
+            None
+        };
+        let receipt = if receipt_accounts > 0 {
+            Some(ReceiptAccounts {
+                receipt_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                user_receipt_account: AccountLoader::next_with_owner(
+                    account_info_iter,
+                    &spl_token::id(),
+                )?,
+            })
+        } else {
+            None
+        };
+        let position = if position_accounts > 0 {
+            Some(PositionAccounts {
+                position_mint: AccountLoader::next_with_owner(account_info_iter, &spl_token::id())?,
+                holder_token_account: AccountLoader::next_with_owner(
+                    account_info_iter,
+                    &spl_token::id(),
+                )?,
+            })
+        } else {
+            None
+        };
+        let referrer = if referrer_accounts > 0 {
+            Some(ReferrerAccounts::new(program_id, account_info_iter)?)
+        } else {
+            None
+        };
+
         Ok(WithdrawMiningContext {
             reward_pool,
             reward_pool_spl,
@@ -52,85 +139,295 @@ impl<'a, 'b> WithdrawMiningContext<'a, 'b> {
             user_token_account,
             user,
             clock,
+            stakers_index,
+            fee_token_account,
+            receipt,
+            position,
+            referrer,
         })
     }
 
     /// Process instruction
     pub fn process(&self, program_id: &Pubkey) -> ProgramResult {
-        let mut reward_pool = RewardPool::unpack(&self.reward_pool.data.borrow())?;
-        let mining = Mining::unpack(&self.mining.data.borrow())?;
-
-        let reward_pool_seeds = &[
-            b"reward_pool".as_ref(),
-            &reward_pool.rewards_root.to_bytes()[..32],
-            &reward_pool.liquidity_mint.to_bytes()[..32],
-            &[reward_pool.bump],
-        ];
+        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
 
-        {
-            assert_account_key(self.user, &mining.owner)?;
-            assert_account_key(self.reward_pool, &mining.reward_pool)?;
-            assert_account_key(
+        // rewards accrued for the referrer are credited before the mining is closed
+        if let Some(referrer) = self.referrer.as_ref() {
+            referrer.settle(
+                program_id,
                 self.reward_pool,
-                &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+                self.mining,
+                self.user,
+                timestamp,
             )?;
         }
 
-        {
-            let mining_pubkey = Pubkey::create_program_address(
-                &[
-                    b"mining".as_ref(),
-                    self.user.key.as_ref(),
-                    self.reward_pool.key.as_ref(),
-                    &[mining.bump],
-                ],
-                program_id,
-            )?;
-            assert_account_key(self.mining, &mining_pubkey)?;
+        let (amount, fee, authority_bump) = {
+            let mut reward_pool_data = self.reward_pool.data.borrow_mut();
+            let reward_pool = RewardPool::load_mut(&mut reward_pool_data)?;
+            let mining_data = self.mining.data.borrow();
+            let mining = Mining::load(&mining_data)?;
+
+            let reward_pool_seeds = &[
+                b"reward_pool".as_ref(),
+                reward_pool.rewards_root.as_ref(),
+                reward_pool.liquidity_mint.as_ref(),
+                &[reward_pool.bump],
+            ];
+
+            {
+                self.assert_controller(program_id, mining)?;
+                mining.assert_not_frozen()?;
+                assert_account_key(self.reward_pool, &mining.reward_pool)?;
+                assert_account_key(
+                    self.reward_pool,
+                    &Pubkey::create_program_address(reward_pool_seeds, program_id)?,
+                )?;
+            }
+
+            assert_account_key(self.mining, &mining.address(program_id)?)?;
+
+            {
+                let spl_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"spl".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        reward_pool.liquidity_mint.as_ref(),
+                        &[reward_pool.spl_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(self.liquidity_mint, &reward_pool.liquidity_mint)?;
+                assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+            }
+
+            // check if it's allowed to withdraw, stakers of a closing pool exit freely
+            let is_closing = reward_pool.pool_status() == PoolStatus::Closing;
+            if !is_closing && timestamp < mining.lock_ends_at(reward_pool.lock_time_sec) {
+                return Err(EverlendError::LockTimeStillActive.into());
+            }
+
+            // unbonding pools release tokens via unstake requests only, positions tokenized
+            // before the pool enabled unbonding can't request unstake and withdraw right away
+            if mining.unbonding_amount > 0 {
+                return Err(EverlendError::UnbondingStillActive.into());
+            }
+            if !is_closing
+                && mining.amount > 0
+                && reward_pool.unbonding_period_sec > 0
+                && !bool::from(mining.is_tokenized)
+            {
+                return Err(EverlendError::UnbondingRequired.into());
+            }
+
+            // nothing accrued for the referrer is lost with the mining
+            Mining::load_account(&mining_data)?.assert_referral_settled(timestamp)?;
+
+            reward_pool.withdraw(mining.amount)?;
+            reward_pool.close_mining()?;
+
+            if bool::from(reward_pool.is_competitive) {
+                let stakers_index = self
+                    .stakers_index
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                let stakers_index_pubkey = Pubkey::create_program_address(
+                    &[
+                        b"stakers".as_ref(),
+                        self.reward_pool.key.as_ref(),
+                        &[reward_pool.stakers_index_bump],
+                    ],
+                    program_id,
+                )?;
+                assert_account_key(stakers_index, &stakers_index_pubkey)?;
+
+                StakersIndex::load_account_mut(&mut stakers_index.data.borrow_mut())?
+                    .remove(self.mining.key);
+            }
+
+            let fee = bps_floor(mining.amount, reward_pool.withdraw_fee_bps)?;
+            if fee > 0 {
+                let fee_token_account = self
+                    .fee_token_account
+                    .ok_or(ProgramError::NotEnoughAccountKeys)?;
+                assert_account_key(
+                    fee_token_account,
+                    &reward_pool.liquidity_fee_token_account(program_id, self.reward_pool.key),
+                )?;
+            }
+
+            (mining.amount, fee, reward_pool.authority_bump)
+        };
+
+        RewardPool::load_account_mut(&mut self.reward_pool.data.borrow_mut())?
+            .record_withdraw_fee(fee)?;
+
+        self.burn_receipt(amount)?;
+        self.burn_position()?;
+        self.spl_transfer_and_close(program_id, amount, fee, authority_bump)?;
+
+        Ok(())
+    }
+
+    /// Checks the user owns the position or holds its NFT
+    fn assert_controller(&self, program_id: &Pubkey, mining: &Mining) -> ProgramResult {
+        if !bool::from(mining.is_tokenized) {
+            return assert_account_key(self.user, &mining.owner);
         }
 
+        let position = self
+            .position
+            .as_ref()
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        assert_account_key(
+            position.position_mint,
+            &mining.position_mint(program_id, self.mining.key)?,
+        )?;
+        mining.assert_holder(
+            program_id,
+            self.mining.key,
+            self.user.key,
+            &Account::unpack(&position.holder_token_account.data.borrow())?,
+        )
+    }
+
+    /// Burns the position NFT of a tokenized position
+    fn burn_position(&self) -> ProgramResult {
+        let position = match self.position.as_ref() {
+            Some(position) => position,
+            None => return Ok(()),
+        };
+
+        everlend_utils::cpi::spl_token::burn(
+            position.position_mint.clone(),
+            position.holder_token_account.clone(),
+            self.user.clone(),
+            1,
+            &[],
+        )
+    }
+
+    /// Burns receipts of the withdrawn amount from the user in a receipt pool
+    fn burn_receipt(&self, amount: u64) -> ProgramResult {
+        let receipt = match self.receipt.as_ref() {
+            Some(receipt) => receipt,
+            None => return Ok(()),
+        };
+
         {
-            let (spl_pubkey, _) = find_reward_pool_spl_token_account(
-                program_id,
-                self.reward_pool.key,
-                self.liquidity_mint.key,
-            );
+            let reward_pool_data = self.reward_pool.data.borrow();
+            let reward_pool = RewardPool::load(&reward_pool_data)?;
+            assert_account_key(receipt.receipt_mint, &reward_pool.receipt_mint)?;
 
-            assert_account_key(self.reward_pool_spl, &spl_pubkey)?;
+            let (user_receipt_account, _) =
+                find_associated_token_account(self.user.key, &reward_pool.receipt_mint);
+            assert_account_key(receipt.user_receipt_account, &user_receipt_account)?;
         }
 
-        // check if it's allowed to withdraw
-        let timestamp = Clock::from_account_info(self.clock)?.unix_timestamp as u64;
-        if timestamp.saturating_sub(mining.last_deposit_time) < reward_pool.lock_time_sec {
-            return Err(EverlendError::LockTimeStillActive.into());
+        if amount == 0 {
+            return Ok(());
         }
 
-        reward_pool.withdraw(mining.amount)?;
-        RewardPool::pack(reward_pool, *self.reward_pool.data.borrow_mut())?;
-
-        self.spl_transfer_and_close(program_id, mining.amount)?;
-
-        Ok(())
+        everlend_utils::cpi::spl_token::burn(
+            receipt.receipt_mint.clone(),
+            receipt.user_receipt_account.clone(),
+            self.user.clone(),
+            amount,
+            &[],
+        )
     }
 
-    fn spl_transfer_and_close(&self, program_id: &Pubkey, amount: u64) -> ProgramResult {
-        let (reward_pool_authority, bump_seed) =
-            find_program_address(program_id, self.reward_pool.key);
-        assert_account_key(self.reward_pool_authority, &reward_pool_authority)?;
-        let signers_seeds = &[self.reward_pool.key.as_ref(), &[bump_seed]];
+    fn spl_transfer_and_close(
+        &self,
+        program_id: &Pubkey,
+        amount: u64,
+        fee: u64,
+        authority_bump: u8,
+    ) -> ProgramResult {
+        let signers_seeds = &[self.reward_pool.key.as_ref(), &[authority_bump]];
+        assert_account_key(
+            self.reward_pool_authority,
+            &Pubkey::create_program_address(signers_seeds, program_id)?,
+        )?;
+
+        let unwrap_bump = self.unwrap_bump(program_id);
+        if let Some(unwrap_bump) = unwrap_bump {
+            self.create_unwrap_acc(unwrap_bump)?;
+        } else {
+            assert_owned_by(self.user_token_account, &spl_token::id())?;
+        }
 
         // Transfer token from source to token account
         everlend_utils::cpi::spl_token::transfer(
             self.reward_pool_spl.clone(),
             self.user_token_account.clone(),
             self.reward_pool_authority.clone(),
-            amount,
+            amount.checked_sub(fee).ok_or(EverlendError::MathOverflow)?,
             &[signers_seeds],
         )?;
+        if let Some(fee_token_account) = self.fee_token_account.filter(|_| fee > 0) {
+            everlend_utils::cpi::spl_token::transfer(
+                self.reward_pool_spl.clone(),
+                fee_token_account.clone(),
+                self.reward_pool_authority.clone(),
+                fee,
+                &[signers_seeds],
+            )?;
+        }
+
+        // unwrap lamports with the rent of the temporary account back to the user
+        if unwrap_bump.is_some() {
+            everlend_utils::cpi::spl_token::close_account(
+                self.user.clone(),
+                self.user_token_account.clone(),
+                self.reward_pool_authority.clone(),
+                &[signers_seeds],
+            )?;
+        }
 
         // close mining account
         everlend_utils::cpi::system::close_account(self.mining, self.user)?;
 
         Ok(())
     }
+
+    /// Returns bump of the temporary unwrap account when withdrawing from a native pool
+    fn unwrap_bump(&self, program_id: &Pubkey) -> Option<u8> {
+        if *self.liquidity_mint.key != spl_token::native_mint::id()
+            || *self.user_token_account.owner != system_program::id()
+        {
+            return None;
+        }
+
+        let (unwrap_pubkey, bump) =
+            find_unwrap_program_address(program_id, self.reward_pool.key, self.user.key);
+        if *self.user_token_account.key != unwrap_pubkey {
+            return None;
+        }
+
+        Some(bump)
+    }
+
+    /// creates temporary wrapped SOL account owned by the pool authority
+    fn create_unwrap_acc(&self, bump: u8) -> ProgramResult {
+        let signers_seeds = &[
+            b"unwrap".as_ref(),
+            self.reward_pool.key.as_ref(),
+            self.user.key.as_ref(),
+            &[bump],
+        ];
+
+        everlend_utils::cpi::system::create_account::<Account>(
+            &spl_token::id(),
+            self.user.clone(),
+            self.user_token_account.clone(),
+            &[signers_seeds],
+            &Rent::get()?,
+        )?;
+
+        everlend_utils::cpi::spl_token::initialize_account3(
+            self.user_token_account.clone(),
+            self.liquidity_mint.clone(),
+            self.reward_pool_authority.key,
+        )
+    }
 }
@@ -2,6 +2,7 @@
 
 //! Rewards contract
 
+pub mod events;
 pub mod instruction;
 pub mod instructions;
 pub mod processor;
@@ -78,3 +79,113 @@ pub fn find_reward_pool_spl_token_account(
         program_id,
     )
 }
+
+/// Generates stakers index address
+pub fn find_stakers_index_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&["stakers".as_bytes(), &reward_pool.to_bytes()], program_id)
+}
+
+/// Generates funder contribution address
+pub fn find_funder_contribution_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    reward_mint: &Pubkey,
+    funder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            "funder".as_bytes(),
+            &reward_pool.to_bytes(),
+            &reward_mint.to_bytes(),
+            &funder.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates address of the temporary account unwrapping native pool withdrawals
+pub fn find_unwrap_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    user: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            "unwrap".as_bytes(),
+            &reward_pool.to_bytes(),
+            &user.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates referrer address
+pub fn find_referrer_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    referrer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            "referrer".as_bytes(),
+            &reward_pool.to_bytes(),
+            &referrer.to_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates boost source address
+pub fn find_boost_source_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+    index: u8,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &["boost".as_bytes(), &reward_pool.to_bytes(), &[index]],
+        program_id,
+    )
+}
+
+/// Generates receipt mint address
+pub fn find_receipt_mint_program_address(
+    program_id: &Pubkey,
+    reward_pool: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&["receipt".as_bytes(), &reward_pool.to_bytes()], program_id)
+}
+
+/// Generates position NFT mint address
+pub fn find_position_mint_program_address(program_id: &Pubkey, mining: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&["position".as_bytes(), &mining.to_bytes()], program_id)
+}
+
+/// Generates mining address of the user position, the default position has index zero
+pub fn find_mining_position_address(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    reward_pool: &Pubkey,
+    index: u16,
+) -> (Pubkey, u8) {
+    if index == 0 {
+        return find_mining_program_address(program_id, user, reward_pool);
+    }
+
+    Pubkey::find_program_address(
+        &[
+            "mining".as_bytes(),
+            &user.to_bytes(),
+            &reward_pool.to_bytes(),
+            &index.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Generates denylist address
+pub fn find_denylist_program_address(program_id: &Pubkey, rewards_root: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&["denylist".as_bytes(), &rewards_root.to_bytes()], program_id)
+}
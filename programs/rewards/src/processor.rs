@@ -7,7 +7,6 @@ use solana_program::account_info::AccountInfo;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::msg;
 use solana_program::pubkey::Pubkey;
-use everlend_utils::EverlendError;
 
 ///
 pub fn process_instruction(
@@ -21,18 +20,23 @@ pub fn process_instruction(
         RewardsInstruction::InitializePool {
             lock_time_sec,
             max_stakers,
+            vaults_capacity,
+            tiers_capacity,
         } => {
             msg!("RewardsInstruction: InitializePool");
             InitializePoolContext::new(program_id, accounts)?.process(
                 program_id,
                 lock_time_sec,
                 max_stakers,
+                vaults_capacity,
+                tiers_capacity,
             )
         }
         RewardsInstruction::AddVault {
             reward_period_sec,
             is_enabled,
             tiers,
+            kind,
         } => {
             msg!("RewardsInstruction: AddVault");
             AddVaultContext::new(program_id, accounts)?.process(
@@ -40,12 +44,21 @@ pub fn process_instruction(
                 reward_period_sec,
                 is_enabled,
                 tiers,
+                kind,
             )
         }
         RewardsInstruction::UpdateVault {
             reward_period_sec,
             is_enabled,
             tiers,
+            manager,
+            is_fill_permissioned,
+            kind,
+            max_mint_amount,
+            max_mint_amount_per_period,
+            is_epoch_aligned,
+            epoch_anchor,
+            is_partial_epoch_excluded,
         } => {
             msg!("RewardsInstruction: AddVault");
             UpdateVaultContext::new(program_id, accounts)?.process(
@@ -53,6 +66,14 @@ pub fn process_instruction(
                 reward_period_sec,
                 is_enabled,
                 tiers,
+                manager,
+                is_fill_permissioned,
+                kind,
+                max_mint_amount,
+                max_mint_amount_per_period,
+                is_epoch_aligned,
+                epoch_anchor,
+                is_partial_epoch_excluded,
             )
         }
 
@@ -62,7 +83,8 @@ pub fn process_instruction(
         }
         RewardsInstruction::DepositMining { amount } => {
             msg!("RewardsInstruction: DepositMining");
-            DepositMiningContext::new(program_id, accounts)?.process(program_id, amount)
+            DepositMiningContext::new(program_id, accounts, false, None)?
+                .process(program_id, amount, None, None)
         }
         RewardsInstruction::WithdrawMining => {
             msg!("RewardsInstruction: WithdrawMining");
@@ -80,15 +102,225 @@ pub fn process_instruction(
             msg!("RewardsInstruction: InitializeRoot");
             InitializeRootContext::new(program_id, accounts)?.process(program_id)
         }
-        RewardsInstruction::MigratePool { max_stakers, total_stakers } => {
+        RewardsInstruction::MigratePool => {
             msg!("RewardsInstruction: MigratePool");
-            Err(EverlendError::NotImplemented.into())
-            // MigratePoolContext::new(program_id, accounts)?.process(program_id, max_stakers, total_stakers)
+            MigratePoolContext::new(program_id, accounts)?.process(program_id)
         }
         RewardsInstruction::MigrateMining => {
             msg!("RewardsInstruction: MigrateMining");
-            Err(EverlendError::NotImplemented.into())
-            // MigrateMiningContext::new(program_id, accounts)?.process(program_id)
+            MigrateMiningContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::ResizePool {
+            vaults_capacity,
+            tiers_capacity,
+        } => {
+            msg!("RewardsInstruction: ResizePool");
+            ResizePoolContext::new(program_id, accounts)?.process(
+                program_id,
+                vaults_capacity,
+                tiers_capacity,
+            )
+        }
+        RewardsInstruction::UpdatePool {
+            lock_time_sec,
+            max_stakers,
+            min_deposit_amount,
+            max_deposit_amount,
+            min_stake_amount,
+            max_stake_amount,
+            unbonding_period_sec,
+            is_linear_unbonding,
+            status,
+            referral_share_bps,
+            warmup_period_sec,
+            warmup_rate_bps,
+            max_funder_lock_sec,
+        } => {
+            msg!("RewardsInstruction: UpdatePool");
+            UpdatePoolContext::new(program_id, accounts)?.process(
+                program_id,
+                lock_time_sec,
+                max_stakers,
+                min_deposit_amount,
+                max_deposit_amount,
+                min_stake_amount,
+                max_stake_amount,
+                unbonding_period_sec,
+                is_linear_unbonding,
+                status,
+                referral_share_bps,
+                warmup_period_sec,
+                warmup_rate_bps,
+                max_funder_lock_sec,
+            )
+        }
+        RewardsInstruction::EvictDust => {
+            msg!("RewardsInstruction: EvictDust");
+            EvictDustContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::InitializeStakersIndex => {
+            msg!("RewardsInstruction: InitializeStakersIndex");
+            InitializeStakersIndexContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::RequestUnstake { amount } => {
+            msg!("RewardsInstruction: RequestUnstake");
+            RequestUnstakeContext::new(program_id, accounts)?.process(program_id, amount)
+        }
+        RewardsInstruction::FinalizeUnstake => {
+            msg!("RewardsInstruction: FinalizeUnstake");
+            FinalizeUnstakeContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::CancelUnstake => {
+            msg!("RewardsInstruction: CancelUnstake");
+            CancelUnstakeContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::SetPoolFees {
+            treasury,
+            deposit_fee_bps,
+            withdraw_fee_bps,
+            claim_fee_bps,
+            is_fee_to_vault,
+        } => {
+            msg!("RewardsInstruction: SetPoolFees");
+            SetPoolFeesContext::new(program_id, accounts)?.process(
+                program_id,
+                treasury,
+                deposit_fee_bps,
+                withdraw_fee_bps,
+                claim_fee_bps,
+                is_fee_to_vault,
+            )
+        }
+        RewardsInstruction::SettleReferralRewards => {
+            msg!("RewardsInstruction: SettleReferralRewards");
+            SettleReferralRewardsContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::AddBoostSource {
+            kind,
+            multiplier_bps,
+            min_amount,
+            mints,
+        } => {
+            msg!("RewardsInstruction: AddBoostSource");
+            AddBoostSourceContext::new(program_id, accounts)?.process(
+                program_id,
+                kind,
+                multiplier_bps,
+                min_amount,
+                mints,
+            )
+        }
+        RewardsInstruction::UpdateBoostSource {
+            multiplier_bps,
+            min_amount,
+        } => {
+            msg!("RewardsInstruction: UpdateBoostSource");
+            UpdateBoostSourceContext::new(program_id, accounts)?.process(
+                program_id,
+                multiplier_bps,
+                min_amount,
+            )
+        }
+        RewardsInstruction::RefreshBoost => {
+            msg!("RewardsInstruction: RefreshBoost");
+            RefreshBoostContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::SetTierPrices {
+            prices,
+            tier_duration_sec,
+        } => {
+            msg!("RewardsInstruction: SetTierPrices");
+            SetTierPricesContext::new(program_id, accounts)?.process(
+                program_id,
+                prices,
+                tier_duration_sec,
+            )
+        }
+        RewardsInstruction::BuyTier { tier } => {
+            msg!("RewardsInstruction: BuyTier");
+            BuyTierContext::new(program_id, accounts)?.process(program_id, tier)
+        }
+        RewardsInstruction::InitializeReceiptMint => {
+            msg!("RewardsInstruction: InitializeReceiptMint");
+            InitializeReceiptMintContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::SyncReceipt => {
+            msg!("RewardsInstruction: SyncReceipt");
+            SyncReceiptContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::OpenPosition { index } => {
+            msg!("RewardsInstruction: OpenPosition");
+            OpenPositionContext::new(program_id, accounts)?.process(program_id, index)
+        }
+        RewardsInstruction::SplitPosition { amount } => {
+            msg!("RewardsInstruction: SplitPosition");
+            MovePositionContext::new(program_id, accounts)?.process(program_id, Some(amount))
+        }
+        RewardsInstruction::MergePosition => {
+            msg!("RewardsInstruction: MergePosition");
+            MovePositionContext::new(program_id, accounts)?.process(program_id, None)
+        }
+        RewardsInstruction::TokenizePosition => {
+            msg!("RewardsInstruction: TokenizePosition");
+            TokenizePositionContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::StartPoolClosing { grace_period_sec } => {
+            msg!("RewardsInstruction: StartPoolClosing");
+            StartPoolClosingContext::new(program_id, accounts)?
+                .process(program_id, grace_period_sec)
+        }
+        RewardsInstruction::ForceUnstake => {
+            msg!("RewardsInstruction: ForceUnstake");
+            ForceUnstakeContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::ClosePool => {
+            msg!("RewardsInstruction: ClosePool");
+            ClosePoolContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::ForceWithdraw => {
+            msg!("RewardsInstruction: ForceWithdraw");
+            ForceWithdrawContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::FreezeMining {
+            reason,
+            pause_accrual,
+        } => {
+            msg!("RewardsInstruction: FreezeMining");
+            FreezeMiningContext::new(program_id, accounts)?.process(
+                program_id,
+                reason,
+                pause_accrual,
+            )
+        }
+        RewardsInstruction::UnfreezeMining => {
+            msg!("RewardsInstruction: UnfreezeMining");
+            UnfreezeMiningContext::new(program_id, accounts)?.process(program_id)
+        }
+        RewardsInstruction::UpdateDenylist { wallet, is_denied } => {
+            msg!("RewardsInstruction: UpdateDenylist");
+            UpdateDenylistContext::new(program_id, accounts)?.process(program_id, wallet, is_denied)
+        }
+        RewardsInstruction::DepositFor {
+            beneficiary,
+            amount,
+            min_lock_sec,
+        } => {
+            msg!("RewardsInstruction: DepositFor");
+            DepositMiningContext::new(program_id, accounts, false, Some(beneficiary))?.process(
+                program_id,
+                amount,
+                None,
+                min_lock_sec,
+            )
+        }
+        RewardsInstruction::DepositMiningWithReferrer { amount, referrer } => {
+            msg!("RewardsInstruction: DepositMiningWithReferrer");
+            DepositMiningContext::new(program_id, accounts, true, None)?.process(
+                program_id,
+                amount,
+                Some(referrer),
+                None,
+            )
         }
     }
 }
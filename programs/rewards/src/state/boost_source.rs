@@ -0,0 +1,117 @@
+use super::AccountType;
+use crate::state::ZeroCopy;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+use spl_token::state::Account;
+
+/// Max number of mints in a mint list boost source
+pub const MAX_BOOST_MINTS: usize = 10;
+
+/// Max number of boost sources per reward pool, sources held by the owner are tracked as bits
+pub const MAX_BOOST_SOURCES: u8 = 64;
+
+/// Source of the reward multiplier for holders of a partner token or NFT
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct BoostSource {
+    /// Account type - BoostSource
+    pub account_type: u8,
+    /// Saved bump for boost source account
+    pub bump: u8,
+    /// Boost kind
+    pub kind: u8,
+    /// Index of the source in the reward pool
+    pub index: u8,
+    /// Multiplier added to the reward tier ratio in basis points, zero disables the source
+    pub multiplier_bps: u16,
+    /// Number of initialized mints
+    pub mints_len: u8,
+    /// Padding
+    pub _padding: [u8; 1],
+    /// Reward pool address
+    pub reward_pool: Pubkey,
+    /// Min balance of the holder token account
+    pub min_amount: u64,
+    /// Token mint or NFT mints qualifying for the boost
+    pub mints: [Pubkey; MAX_BOOST_MINTS],
+}
+
+impl ZeroCopy for BoostSource {
+    const ACCOUNT_TYPE: AccountType = AccountType::BoostSource;
+}
+
+impl BoostSource {
+    /// Initialize a Boost Source
+    pub fn initialize(
+        reward_pool: Pubkey,
+        bump: u8,
+        index: u8,
+        kind: BoostKind,
+        multiplier_bps: u16,
+        min_amount: u64,
+        mints: &[Pubkey],
+    ) -> Result<BoostSource, ProgramError> {
+        if mints.is_empty()
+            || mints.len() > MAX_BOOST_MINTS
+            || (kind == BoostKind::Token && mints.len() != 1)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut boost_source = BoostSource {
+            account_type: AccountType::BoostSource as u8,
+            bump,
+            kind: kind as u8,
+            index,
+            multiplier_bps,
+            mints_len: mints.len() as u8,
+            reward_pool,
+            // a single NFT is enough to hold
+            min_amount: match kind {
+                BoostKind::Token => min_amount,
+                BoostKind::MintList => 1,
+            },
+            ..Zeroable::zeroed()
+        };
+        boost_source.mints[..mints.len()].copy_from_slice(mints);
+
+        Ok(boost_source)
+    }
+
+    /// Initialized mints
+    pub fn mints(&self) -> &[Pubkey] {
+        &self.mints[..self.mints_len as usize]
+    }
+
+    /// Boost kind
+    pub fn boost_kind(&self) -> BoostKind {
+        match self.kind {
+            0 => BoostKind::Token,
+            _ => BoostKind::MintList,
+        }
+    }
+
+    /// Multiplier the token account holder gets from the source
+    pub fn holder_multiplier_bps(&self, holder: &Pubkey, token_account: &Account) -> u16 {
+        if token_account.owner != *holder
+            || token_account.amount < self.min_amount
+            || !self.mints().contains(&token_account.mint)
+        {
+            return 0;
+        }
+
+        self.multiplier_bps
+    }
+}
+
+/// Boost source kind
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum BoostKind {
+    /// Holders of a min balance of the token mint
+    Token,
+    /// Holders of any mint of the list
+    MintList,
+}
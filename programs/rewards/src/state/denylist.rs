@@ -0,0 +1,127 @@
+use super::{load_records, load_records_mut, AccountType};
+use crate::state::ZeroCopy;
+use bytemuck::{Pod, Zeroable};
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Wallets denied from depositing into pools of a rewards root
+///
+/// The account data holds this header followed by `len` wallet addresses,
+/// the account grows and shrinks with the list.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Denylist {
+    /// Account type - Denylist
+    pub account_type: u8,
+    /// Saved bump for denylist account
+    pub bump: u8,
+    /// Padding
+    pub _padding: [u8; 6],
+    /// Rewards root address
+    pub rewards_root: Pubkey,
+    /// Number of denied wallets
+    pub len: u64,
+}
+
+impl ZeroCopy for Denylist {
+    const ACCOUNT_TYPE: AccountType = AccountType::Denylist;
+}
+
+impl Denylist {
+    /// Initialize a Denylist
+    pub fn initialize(rewards_root: Pubkey, bump: u8) -> Denylist {
+        Denylist {
+            account_type: AccountType::Denylist as u8,
+            bump,
+            rewards_root,
+            ..Zeroable::zeroed()
+        }
+    }
+
+    /// Account data length for the given number of wallets
+    pub fn account_len(len: u64) -> usize {
+        Self::LEN + len as usize * std::mem::size_of::<Pubkey>()
+    }
+
+    /// Borrows denylist with its wallets in place
+    pub fn load_account(data: &[u8]) -> Result<DenylistRef<'_>, ProgramError> {
+        let denylist = Self::load(data)?;
+
+        Ok(DenylistRef {
+            denylist,
+            wallets: load_records(&data[Self::LEN..], denylist.len as usize)?,
+        })
+    }
+
+    /// Mutably borrows denylist with every wallet slot the account data fits
+    pub fn load_account_mut(data: &mut [u8]) -> Result<DenylistMut<'_>, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        let (denylist, records) = data.split_at_mut(Self::LEN);
+        let denylist = Self::load_mut(denylist)?;
+        let capacity = records.len() / std::mem::size_of::<Pubkey>();
+        let wallets = load_records_mut(records, capacity)?;
+
+        Ok(DenylistMut { denylist, wallets })
+    }
+}
+
+/// Denylist with its wallets
+#[derive(Clone, Copy)]
+pub struct DenylistRef<'a> {
+    /// Denylist header
+    pub denylist: &'a Denylist,
+    wallets: &'a [Pubkey],
+}
+
+impl<'a> DenylistRef<'a> {
+    /// Denied wallets
+    pub fn wallets(&self) -> &'a [Pubkey] {
+        self.wallets
+    }
+
+    /// Checks the wallet is denied
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        self.wallets.contains(wallet)
+    }
+}
+
+/// Mutable denylist with its wallets
+pub struct DenylistMut<'a> {
+    /// Denylist header
+    pub denylist: &'a mut Denylist,
+    wallets: &'a mut [Pubkey],
+}
+
+impl DenylistMut<'_> {
+    /// Writes the wallet into the slot after the denied ones,
+    /// the account has to be grown to the new length first
+    pub fn push(&mut self, wallet: Pubkey) -> Result<(), ProgramError> {
+        let len = self.denylist.len as usize;
+        *self
+            .wallets
+            .get_mut(len)
+            .ok_or(ProgramError::AccountDataTooSmall)? = wallet;
+        self.denylist.len += 1;
+
+        Ok(())
+    }
+
+    /// Moves the last wallet into the slot of the removed one,
+    /// returns false if the wallet isn't denied
+    pub fn remove(&mut self, wallet: &Pubkey) -> bool {
+        let len = self.denylist.len as usize;
+        let i = match self.wallets[..len].iter().position(|w| w == wallet) {
+            Some(i) => i,
+            None => return false,
+        };
+
+        self.wallets.swap(i, len - 1);
+        self.wallets[len - 1] = Pubkey::default();
+        self.denylist.len -= 1;
+
+        true
+    }
+}
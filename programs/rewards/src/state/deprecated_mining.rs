@@ -1,4 +1,4 @@
-use crate::state::{AccountType, MAX_REWARDS};
+use crate::state::{AccountType, DEPRECATED_MAX_REWARDS};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_program::msg;
 use solana_program::program_error::ProgramError;
@@ -31,7 +31,7 @@ pub struct DeprecatedMining {
 
 impl Sealed for DeprecatedMining {}
 impl Pack for DeprecatedMining {
-    const LEN: usize = 1 + (32 + 1 + 8 + 8 + 32 + 8 + 1 + (4 + DeprecatedRewardIndex::LEN * MAX_REWARDS));
+    const LEN: usize = 1 + (32 + 1 + 8 + 8 + 32 + 8 + 1 + (4 + DeprecatedRewardIndex::LEN * DEPRECATED_MAX_REWARDS));
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let mut slice = dst;
@@ -61,9 +61,11 @@ pub struct DeprecatedRewardIndex {
     pub reward_mint: Pubkey,
     /// Rewards amount
     pub rewards: u64,
+    /// claimed_total_rewards
+    pub claimed_total_rewards: u64,
 }
 
 impl DeprecatedRewardIndex {
     ///
-    pub const LEN: usize = 32 + 8;
-}
\ No newline at end of file
+    pub const LEN: usize = 32 + 8 + 8;
+}
@@ -1,10 +1,15 @@
-use crate::state::{AccountType, InitRewardPoolParams, RewardTier, MAX_REWARDS, MAX_TIERS};
+use crate::state::{AccountType, RewardTier};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use solana_program::msg;
 use solana_program::program_error::ProgramError;
 use solana_program::program_pack::{IsInitialized, Pack, Sealed};
 use solana_program::pubkey::Pubkey;
 
+/// Max reward vaults of the deprecated layout
+pub const DEPRECATED_MAX_REWARDS: usize = 3;
+/// Max reward tiers of the deprecated layout
+pub const DEPRECATED_MAX_TIERS: usize = 5;
+
 /// Deprecated Reward pool
 #[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct DeprecatedRewardPool {
@@ -16,6 +21,10 @@ pub struct DeprecatedRewardPool {
     pub bump: u8,
     /// Liquidity mint
     pub liquidity_mint: Pubkey,
+    /// max stakers
+    pub max_stakers: u64,
+    /// total stakers
+    pub total_stakers: u64,
     /// Total staked amount
     pub total_amount: u64,
     /// staking lock time
@@ -26,7 +35,7 @@ pub struct DeprecatedRewardPool {
 
 impl Sealed for DeprecatedRewardPool {}
 impl Pack for DeprecatedRewardPool {
-    const LEN: usize = 8 + (32 + 1 + 32 + 8 + (4 + DeprecatedRewardVault::LEN * MAX_REWARDS) + 32);
+    const LEN: usize = 1 + (32 + 1 + 32 + 8 + 8 + 8 + 8 + (4 + DeprecatedRewardVault::LEN * DEPRECATED_MAX_REWARDS));
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let mut slice = dst;
@@ -45,27 +54,11 @@ impl Pack for DeprecatedRewardPool {
 
 impl IsInitialized for DeprecatedRewardPool {
     fn is_initialized(&self) -> bool {
-        self.rewards_root != Pubkey::default()
+        self.account_type == AccountType::RewardPool
     }
 }
 
-impl DeprecatedRewardPool {
-    /// Init deprecated reward pool
-    pub fn init(params: InitRewardPoolParams) -> DeprecatedRewardPool {
-        DeprecatedRewardPool {
-            account_type: AccountType::RewardPool,
-            rewards_root: params.rewards_root,
-            bump: params.bump,
-            liquidity_mint: params.liquidity_mint,
-            total_amount: 0,
-            lock_time_sec: params.lock_time_sec,
-            vaults: vec![],
-        }
-    }
-}
-
-
-/// Reward vault
+/// Deprecated Reward vault
 #[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema, Default, Clone)]
 pub struct DeprecatedRewardVault {
     /// Bump of vault account
@@ -78,11 +71,13 @@ pub struct DeprecatedRewardVault {
     pub is_enabled: bool,
     /// Timestamp since when distribution begins
     pub enabled_at: u64,
+    /// Total rewards
+    pub claimed_total_amount: u64,
     /// Reward tiers
     pub reward_tiers: Vec<RewardTier>,
 }
 
 impl DeprecatedRewardVault {
     /// LEN
-    pub const LEN: usize = 1 + 32 + 4 + 1 + 8 + (4 + RewardTier::LEN * MAX_TIERS);
+    pub const LEN: usize = 1 + 32 + 4 + 1 + 8 + 8 + (4 + RewardTier::LEN * DEPRECATED_MAX_TIERS);
 }
@@ -0,0 +1,76 @@
+use super::AccountType;
+use crate::state::ZeroCopy;
+use bytemuck::{Pod, Zeroable};
+use everlend_utils::EverlendError;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Rewards a funder has put into a single reward vault
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct FunderContribution {
+    /// Account type - FunderContribution
+    pub account_type: u8,
+    /// Saved bump for funder contribution account
+    pub bump: u8,
+    /// Padding
+    pub _padding: [u8; 6],
+    /// Reward pool address
+    pub reward_pool: Pubkey,
+    /// Reward mint address
+    pub reward_mint: Pubkey,
+    /// Funder address
+    pub funder: Pubkey,
+    /// Total amount filled by the funder
+    pub total_amount: u64,
+    /// Number of fills
+    pub fills_count: u64,
+    /// Timestamp of the first fill
+    pub first_funded_at: u64,
+    /// Timestamp of the last fill
+    pub last_funded_at: u64,
+    /// Amount of the last fill
+    pub last_amount: u64,
+}
+
+impl ZeroCopy for FunderContribution {
+    const ACCOUNT_TYPE: AccountType = AccountType::FunderContribution;
+}
+
+impl FunderContribution {
+    /// Initialize a Funder Contribution
+    pub fn initialize(
+        reward_pool: Pubkey,
+        reward_mint: Pubkey,
+        funder: Pubkey,
+        bump: u8,
+    ) -> FunderContribution {
+        FunderContribution {
+            account_type: AccountType::FunderContribution as u8,
+            bump,
+            reward_pool,
+            reward_mint,
+            funder,
+            ..Zeroable::zeroed()
+        }
+    }
+
+    /// Records a fill
+    pub fn record(&mut self, amount: u64, timestamp: u64) -> Result<(), ProgramError> {
+        self.total_amount = self
+            .total_amount
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        self.fills_count = self
+            .fills_count
+            .checked_add(1)
+            .ok_or(EverlendError::MathOverflow)?;
+        if self.first_funded_at == 0 {
+            self.first_funded_at = timestamp;
+        }
+        self.last_funded_at = timestamp;
+        self.last_amount = amount;
+
+        Ok(())
+    }
+}
@@ -1,102 +1,364 @@
-use super::AccountType;
-use crate::state::deprecated_mining::DeprecatedMining;
-use crate::state::{RewardVault, MAX_REWARDS};
-use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
-use everlend_utils::EverlendError;
+use super::{load_records, load_records_mut, AccountType};
+use crate::state::{
+    DeprecatedMining, PodBool, RewardPoolRef, RewardTier, RewardVault, VaultRates, ZeroCopy,
+};
+use bytemuck::{Pod, Zeroable};
+use everlend_utils::{bps_floor, EverlendError, BPS_SCALER};
 use solana_program::entrypoint::ProgramResult;
-use solana_program::msg;
 use solana_program::program_error::ProgramError;
-use solana_program::program_pack::{IsInitialized, Pack, Sealed};
 use solana_program::pubkey::Pubkey;
+use spl_token::state::Account;
 use std::cmp;
-use std::ops::Div;
-use std::slice::Iter;
+use std::convert::TryFrom;
 
 /// Mining
-#[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema)]
+///
+/// The account data holds this header followed by reward index records,
+/// the account grows when the user accrues from a new vault.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Mining {
     /// Account type - Mining
-    pub account_type: AccountType,
-    /// Reward pool address
-    pub reward_pool: Pubkey,
+    pub account_type: u8,
     /// Saved bump for mining account
     pub bump: u8,
+    /// reward tier
+    pub reward_tier: u8,
+    /// Number of initialized reward indexes
+    pub indexes_len: u8,
+    /// Share of the rewards accrued for the referrer in basis points
+    pub referral_share_bps: u16,
+    /// Multiplier added to the reward tier ratio by held boost sources in basis points
+    pub boost_bps: u16,
+    /// Reward pool address
+    pub reward_pool: Pubkey,
+    /// Mining owner
+    pub owner: Pubkey,
     /// Amount of staked
     pub amount: u64,
     /// Last rewards calculation
     pub rewards_calculated_at: u64,
-    /// Mining owner
-    pub owner: Pubkey,
     /// last deposit time
     pub last_deposit_time: u64,
-    /// reward tier
-    pub reward_tier: u8,
-    /// Reward indexes
-    pub indexes: Vec<RewardIndex>,
+    /// Pool lock time at the last deposit
+    pub lock_time_sec: u64,
+    /// End of the min lock set by the funder of the position, the pool lock can't shorten it
+    pub enforced_lock_ends_at: u64,
+    /// Pending unstake amount which doesn't accrue rewards
+    pub unbonding_amount: u64,
+    /// Start of the unbonding, moves forward on linear releases
+    pub unbonding_started_at: u64,
+    /// End of the unbonding
+    pub unlock_at: u64,
+    /// Referrer wallet, default if the mining wasn't referred
+    pub referrer: Pubkey,
+    /// End of the bought reward tier, zero means the tier doesn't expire
+    pub tier_expires_at: u64,
+    /// Part of the amount still warming up
+    pub warming_amount: u64,
+    /// End of the warm-up
+    pub warm_at: u64,
+    /// Reward rate of the warming amount in basis points
+    pub warmup_rate_bps: u16,
+    /// Index of the user position in the pool, zero is the default position
+    pub position_index: u16,
+    /// Position is held as an NFT, the holder claims and withdraws instead of the owner
+    pub is_tokenized: PodBool,
+    /// Position mint bump
+    pub position_mint_bump: u8,
+    /// Reason code of the admin freeze, zero means the position isn't frozen
+    pub freeze_reason: u8,
+    /// Frozen position doesn't accrue rewards
+    pub is_accrual_paused: PodBool,
+    /// Bits of the boost sources the owner held at the last boost refresh
+    pub held_boost_sources: u64,
+    /// Permanent reward tier a bought tier falls back to when it expires
+    pub base_tier: u8,
+    /// Padding
+    pub _padding: [u8; 7],
+}
+
+impl ZeroCopy for Mining {
+    const ACCOUNT_TYPE: AccountType = AccountType::Mining;
 }
 
 impl Mining {
     /// Initialize a Reward Pool
     pub fn initialize(reward_pool: Pubkey, bump: u8, owner: Pubkey) -> Mining {
         Mining {
-            account_type: AccountType::Mining,
+            account_type: AccountType::Mining as u8,
             reward_pool,
             bump,
-            amount: 0,
-            rewards_calculated_at: 0,
-            last_deposit_time: 0,
             owner,
-            reward_tier: 0,
-            indexes: vec![],
+            ..Zeroable::zeroed()
+        }
+    }
+
+    /// Account data length for the given number of reward indexes
+    pub fn account_len(indexes_capacity: usize) -> usize {
+        Self::LEN + indexes_capacity * RewardIndex::LEN
+    }
+
+    /// Borrows mining with its reward indexes in place
+    pub fn load_account(data: &[u8]) -> Result<MiningRef<'_>, ProgramError> {
+        let mining = Self::load(data)?;
+        let records = &data[Self::LEN..];
+
+        Ok(MiningRef {
+            mining,
+            indexes: load_records(records, records.len() / RewardIndex::LEN)?,
+        })
+    }
+
+    /// Mutably borrows mining with its reward indexes in place
+    pub fn load_account_mut(data: &mut [u8]) -> Result<MiningMut<'_>, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        let (mining, records) = data.split_at_mut(Self::LEN);
+        let indexes_capacity = records.len() / RewardIndex::LEN;
+
+        Ok(MiningMut {
+            mining: Self::load_mut(mining)?,
+            indexes: load_records_mut(records, indexes_capacity)?,
+        })
+    }
+
+    /// Mining address derived from the owner, the position index and the saved bump
+    pub fn address(&self, program_id: &Pubkey) -> Result<Pubkey, ProgramError> {
+        let position_index = self.position_index.to_le_bytes();
+        let bump = [self.bump];
+        let mut seeds = vec![
+            b"mining".as_ref(),
+            self.owner.as_ref(),
+            self.reward_pool.as_ref(),
+        ];
+        // the default position keeps the original address
+        if self.position_index > 0 {
+            seeds.push(&position_index);
+        }
+        seeds.push(&bump);
+
+        Ok(Pubkey::create_program_address(&seeds, program_id)?)
+    }
+
+    /// Position NFT mint derived from the mining address and the saved bump
+    pub fn position_mint(
+        &self,
+        program_id: &Pubkey,
+        mining: &Pubkey,
+    ) -> Result<Pubkey, ProgramError> {
+        Ok(Pubkey::create_program_address(
+            &[
+                b"position".as_ref(),
+                mining.as_ref(),
+                &[self.position_mint_bump],
+            ],
+            program_id,
+        )?)
+    }
+
+    /// Checks the token account of the user holds the position NFT
+    pub fn assert_holder(
+        &self,
+        program_id: &Pubkey,
+        mining: &Pubkey,
+        user: &Pubkey,
+        token_account: &Account,
+    ) -> ProgramResult {
+        if token_account.mint != self.position_mint(program_id, mining)?
+            || token_account.owner != *user
+            || token_account.amount != 1
+        {
+            return Err(EverlendError::InvalidPositionHolder.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks the position is controlled by its owner rather than an NFT holder
+    pub fn assert_owner_controlled(&self) -> ProgramResult {
+        if bool::from(self.is_tokenized) {
+            return Err(EverlendError::PositionIsTokenized.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks the position is frozen by the admin
+    pub fn is_frozen(&self) -> bool {
+        self.freeze_reason != 0
+    }
+
+    /// Checks the position isn't frozen by the admin
+    pub fn assert_not_frozen(&self) -> ProgramResult {
+        if self.is_frozen() {
+            return Err(EverlendError::MiningIsFrozen.into());
         }
+
+        Ok(())
+    }
+
+    /// End of the lock of the last deposit under the given pool lock
+    pub fn lock_ends_at(&self, pool_lock_time_sec: u64) -> u64 {
+        // a shorter pool lock applies right away and a longer one only to new deposits
+        let lock_time_sec = cmp::min(self.lock_time_sec, pool_lock_time_sec);
+        cmp::max(
+            self.last_deposit_time.saturating_add(lock_time_sec),
+            self.enforced_lock_ends_at,
+        )
+    }
+
+    /// Extends the enforced lock to at least the given end
+    pub fn enforce_lock(&mut self, ends_at: u64) {
+        self.enforced_lock_ends_at = cmp::max(self.enforced_lock_ends_at, ends_at);
+    }
+
+    /// Checks the mining was referred
+    pub fn is_referred(&self) -> bool {
+        self.referrer != Pubkey::default()
+    }
+
+    /// Amount accruing rewards, the warming amount earns at the warm-up rate
+    pub fn earning_amount(&self) -> Result<u64, ProgramError> {
+        let warming_earning = bps_floor(self.warming_amount, self.warmup_rate_bps)?;
+
+        self.amount
+            .checked_sub(self.warming_amount)
+            .and_then(|warm_amount| warm_amount.checked_add(warming_earning))
+            .ok_or_else(|| EverlendError::MathOverflow.into())
+    }
+
+    /// Earliest bought tier expiry or warm-up end up to the timestamp
+    fn next_change_at(&self, timestamp: u64) -> Option<u64> {
+        let warm_at = if self.warming_amount > 0 {
+            self.warm_at
+        } else {
+            0
+        };
+
+        [self.tier_expires_at, warm_at]
+            .iter()
+            .copied()
+            .filter(|changed_at| *changed_at != 0 && *changed_at <= timestamp)
+            .min()
     }
 
     /// Process migrate
     pub fn migrate(deprecated_mining: &DeprecatedMining) -> Mining {
-        Self {
-            account_type: deprecated_mining.account_type.clone(),
-            reward_pool: deprecated_mining.reward_pool,
-            bump: deprecated_mining.bump,
-            amount: deprecated_mining.amount,
-            rewards_calculated_at: deprecated_mining.rewards_calculated_at,
-            owner: deprecated_mining.owner,
-            last_deposit_time: deprecated_mining.last_deposit_time,
-            reward_tier: deprecated_mining.reward_tier,
-            indexes: deprecated_mining
-                .indexes
-                .iter()
-                .map(|i| RewardIndex {
-                    reward_mint: i.reward_mint,
-                    rewards: i.rewards,
-                    claimed_total_rewards: 0,
-                })
-                .collect(),
+        let mut mining = Mining::initialize(
+            deprecated_mining.reward_pool,
+            deprecated_mining.bump,
+            deprecated_mining.owner,
+        );
+        mining.amount = deprecated_mining.amount;
+        mining.rewards_calculated_at = deprecated_mining.rewards_calculated_at;
+        mining.last_deposit_time = deprecated_mining.last_deposit_time;
+        mining.reward_tier = deprecated_mining.reward_tier;
+        mining.base_tier = deprecated_mining.reward_tier;
+
+        mining
+    }
+}
+
+/// Mining with its reward indexes
+#[derive(Clone, Copy)]
+pub struct MiningRef<'a> {
+    /// Mining header
+    pub mining: &'a Mining,
+    indexes: &'a [RewardIndex],
+}
+
+impl<'a> MiningRef<'a> {
+    /// Initialized reward indexes
+    pub fn indexes(&self) -> &'a [RewardIndex] {
+        &self.indexes[..self.mining.indexes_len as usize]
+    }
+
+    /// Account data length needed to track every vault of the reward pool
+    pub fn required_len(&self, reward_pool: &RewardPoolRef) -> usize {
+        let missing = reward_pool
+            .vaults()
+            .iter()
+            .filter(|v| {
+                !self
+                    .indexes()
+                    .iter()
+                    .any(|i| i.reward_mint == v.reward_mint)
+            })
+            .count();
+
+        Mining::account_len(self.mining.indexes_len as usize + missing)
+    }
+
+    /// Checks rewards accrued for the referrer are settled up to the given time,
+    /// they're lost once the mining is closed
+    pub fn assert_referral_settled(&self, timestamp: u64) -> ProgramResult {
+        let is_accruing = self.mining.is_referred()
+            && self.mining.referral_share_bps > 0
+            && self.mining.amount > 0
+            && self.mining.rewards_calculated_at < timestamp;
+        if is_accruing || self.indexes().iter().any(|i| i.referral_rewards > 0) {
+            return Err(EverlendError::ReferralRewardsNotSettled.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Mutable mining with its reward indexes
+pub struct MiningMut<'a> {
+    /// Mining header
+    pub mining: &'a mut Mining,
+    indexes: &'a mut [RewardIndex],
+}
+
+impl MiningMut<'_> {
+    /// Reborrows as read only
+    pub fn to_ref(&self) -> MiningRef<'_> {
+        MiningRef {
+            mining: self.mining,
+            indexes: self.indexes,
         }
     }
 
     /// Returns reward index
-    pub fn reward_index_mut(&mut self, reward_mint: Pubkey) -> &mut RewardIndex {
-        match self
-            .indexes
+    pub fn reward_index_mut(
+        &mut self,
+        reward_mint: Pubkey,
+    ) -> Result<&mut RewardIndex, ProgramError> {
+        let len = self.mining.indexes_len as usize;
+        match self.indexes[..len]
             .iter()
             .position(|mi| mi.reward_mint == reward_mint)
         {
-            Some(i) => &mut self.indexes[i],
+            Some(i) => Ok(&mut self.indexes[i]),
             None => {
-                self.indexes.push(RewardIndex {
+                if len == self.indexes.len() {
+                    return Err(EverlendError::InvalidRewardVault.into());
+                }
+
+                self.indexes[len] = RewardIndex {
                     reward_mint,
                     ..Default::default()
-                });
-                self.indexes.last_mut().unwrap()
+                };
+                self.mining.indexes_len += 1;
+
+                Ok(&mut self.indexes[len])
             }
         }
     }
 
-    /// Flush rewards
-    pub fn flush_rewards(&mut self, reward_mint: Pubkey) -> Result<u64, ProgramError> {
-        let reward_index = self.reward_index_mut(reward_mint);
-        let amount = reward_index.rewards;
-        reward_index.rewards = 0;
+    /// Flush rewards up to the max amount
+    pub fn flush_rewards(
+        &mut self,
+        reward_mint: Pubkey,
+        max_amount: u64,
+    ) -> Result<u64, ProgramError> {
+        let reward_index = self.reward_index_mut(reward_mint)?;
+        let amount = cmp::min(reward_index.rewards, max_amount);
+        reward_index.rewards -= amount;
         reward_index.claimed_total_rewards = reward_index
             .claimed_total_rewards
             .checked_add(amount)
@@ -105,100 +367,161 @@ impl Mining {
         Ok(amount)
     }
 
-    /// Refresh rewards
-    pub fn refresh_rewards(
+    /// Adds rewards to the reward index
+    pub fn credit_rewards(&mut self, reward_mint: Pubkey, amount: u64) -> ProgramResult {
+        let reward_index = self.reward_index_mut(reward_mint)?;
+        reward_index.rewards = reward_index
+            .rewards
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Takes rewards accrued for the referrer by reward mint
+    pub fn take_referral_rewards(&mut self) -> Vec<(Pubkey, u64)> {
+        let len = self.mining.indexes_len as usize;
+        self.indexes[..len]
+            .iter_mut()
+            .filter(|i| i.referral_rewards > 0)
+            .map(|i| (i.reward_mint, std::mem::take(&mut i.referral_rewards)))
+            .collect()
+    }
+
+    /// Refresh rewards, a bought tier falls back to the base tier after the expiry
+    /// and the warming amount starts earning in full after the warm-up
+    pub fn refresh_rewards<'v>(
         &mut self,
-        vaults: Iter<RewardVault>,
+        vaults: impl Iterator<Item = VaultRates<'v>> + Clone,
         current_timestamp: u64,
     ) -> ProgramResult {
-        let rewards_calculated_at = self.rewards_calculated_at;
-        let rewards_tier = self.reward_tier as usize;
-
         // first deposit - nothing to calculate
-        if rewards_calculated_at != 0 {
-            let amount = self.amount;
+        if self.mining.rewards_calculated_at == 0 {
+            self.mining.rewards_calculated_at = current_timestamp;
+            return Ok(());
+        }
 
-            for vault in vaults {
-                if !vault.is_enabled {
-                    continue;
-                }
+        // accrue up to each change of the position in order
+        while let Some(changed_at) = self.mining.next_change_at(current_timestamp) {
+            self.accrue_rewards(
+                vaults.clone(),
+                cmp::max(changed_at, self.mining.rewards_calculated_at),
+            )?;
 
-                let reward_index = self.reward_index_mut(vault.reward_mint);
+            if self.mining.tier_expires_at == changed_at {
+                self.mining.reward_tier = self.mining.base_tier;
+                self.mining.tier_expires_at = 0;
+            }
+            if self.mining.warm_at == changed_at {
+                self.mining.warming_amount = 0;
+                self.mining.warm_at = 0;
+            }
+        }
 
-                // how much time passed since last reward calculation
-                let reward_period_start = cmp::max(rewards_calculated_at, vault.enabled_at);
-                let reward_period = current_timestamp.saturating_sub(reward_period_start);
-                let num_periods = reward_period.div(vault.reward_period_sec as u64);
-                if num_periods == 0 {
-                    continue;
-                }
+        self.accrue_rewards(vaults, current_timestamp)
+    }
+
+    /// Sets the staked amount, the warming amount is taken out first
+    pub fn set_amount(&mut self, amount: u64) {
+        self.mining.amount = amount;
+        self.mining.warming_amount = cmp::min(self.mining.warming_amount, amount);
+
+        // the lock enforced by the funder only covers the funded stake
+        if amount == 0 {
+            self.mining.enforced_lock_ends_at = 0;
+        }
+    }
+
+    fn accrue_rewards<'v>(
+        &mut self,
+        vaults: impl Iterator<Item = VaultRates<'v>>,
+        current_timestamp: u64,
+    ) -> ProgramResult {
+        // frozen time of a position with paused accrual earns nothing
+        if bool::from(self.mining.is_accrual_paused) {
+            self.mining.rewards_calculated_at = current_timestamp;
+            return Ok(());
+        }
+
+        let rewards_calculated_at = self.mining.rewards_calculated_at;
+        let rewards_tier = self.mining.reward_tier as usize;
+        let boost_bps = self.mining.boost_bps;
+        let referral_share_bps = if self.mining.is_referred() {
+            self.mining.referral_share_bps
+        } else {
+            0
+        };
+
+        let amount = self.mining.earning_amount()?;
+
+        for rates in vaults {
+            let vault = rates.vault;
+            if !bool::from(vault.is_enabled) {
+                continue;
+            }
+
+            let reward_index = self.reward_index_mut(vault.reward_mint)?;
+
+            // rates changed since the last calculation apply from the change onwards
+            let mut accrued_from = cmp::max(rewards_calculated_at, vault.enabled_at);
+            loop {
+                let accrued_to = rates
+                    .next_change(accrued_from, current_timestamp)
+                    .unwrap_or(current_timestamp);
+                let (reward_period_sec, tiers) = rates.rates_until(accrued_to);
+                let vault = RewardVault {
+                    reward_period_sec,
+                    ..*vault
+                };
 
                 // get proper reward tier idx
-                let tier_idx = cmp::min(rewards_tier, vault.reward_tiers.len().saturating_sub(1));
+                let tier_idx = cmp::min(rewards_tier, tiers.len().saturating_sub(1));
 
-                let tier = vault
-                    .reward_tiers
+                let tier = tiers
                     .get(tier_idx)
                     .ok_or(EverlendError::InvalidRewardTier)?;
 
-                // calculate reward amount based on coefficient
-                let rewards = (num_periods as u128)
-                    .checked_mul(amount.into())
-                    .ok_or(EverlendError::MathOverflow)?
-                    .checked_mul(tier.ratio_quote.into())
-                    .ok_or(EverlendError::MathOverflow)?
-                    .checked_div(tier.ratio_base.into())
-                    .ok_or(EverlendError::MathOverflow)? as u64;
-
-                if rewards > 0 {
-                    let rewards = if tier.reward_max_amount_per_period > 0 {
-                        std::cmp::min(rewards, tier.reward_max_amount_per_period * num_periods)
-                    } else {
-                        rewards
-                    };
-
-                    reward_index.rewards = reward_index
-                        .rewards
-                        .checked_add(rewards as u64)
-                        .ok_or(EverlendError::MathOverflow)?;
+                reward_index.accrue(
+                    &vault,
+                    tier,
+                    amount.into(),
+                    boost_bps,
+                    referral_share_bps,
+                    accrued_from,
+                    accrued_to,
+                )?;
+
+                if accrued_to == current_timestamp {
+                    break;
                 }
+                accrued_from = accrued_to;
             }
         }
 
         // update rewards calculation timestamp
-        self.rewards_calculated_at = current_timestamp;
+        self.mining.rewards_calculated_at = current_timestamp;
 
         Ok(())
     }
-}
-
-impl Sealed for Mining {}
-impl Pack for Mining {
-    const LEN: usize = 1 + (32 + 1 + 8 + 8 + 32 + 8 + 1 + (4 + RewardIndex::LEN * MAX_REWARDS));
 
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
-    }
+    /// Process migrate of the deprecated reward indexes
+    pub fn migrate_indexes(&mut self, deprecated_mining: &DeprecatedMining) -> ProgramResult {
+        for i in deprecated_mining.indexes.iter() {
+            let reward_index = self.reward_index_mut(i.reward_mint)?;
+            reward_index.rewards = i.rewards;
+            reward_index.claimed_total_rewards = i.claimed_total_rewards;
+        }
 
-    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
-        let mut src_mut = src;
-        Self::deserialize(&mut src_mut).map_err(|err| {
-            msg!("Failed to deserialize");
-            msg!("{}", err.to_string());
-            ProgramError::InvalidAccountData
-        })
+        Ok(())
     }
 }
 
-impl IsInitialized for Mining {
-    fn is_initialized(&self) -> bool {
-        self.owner != Pubkey::default()
-    }
-}
+/// Precision of the reward fraction carried between accruals
+pub const REMAINDER_SCALER: u128 = 1_000_000_000;
 
 /// Reward index
-#[derive(Debug, BorshSerialize, BorshDeserialize, BorshSchema, Default, Clone)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable)]
 pub struct RewardIndex {
     /// Reward mint
     pub reward_mint: Pubkey,
@@ -206,9 +529,217 @@ pub struct RewardIndex {
     pub rewards: u64,
     /// claimed_total_rewards
     pub claimed_total_rewards: u64,
+    /// Rewards accrued for the referrer and not settled yet
+    pub referral_rewards: u64,
+    /// Start of the running reward period
+    pub period_started_at: u64,
+    /// Rewards of the running period, credited once it's over
+    pub pending_rewards: u64,
+    /// Fraction of a reward unit carried between accruals, scaled by `REMAINDER_SCALER`
+    pub rewards_remainder: u64,
 }
 
 impl RewardIndex {
     ///
-    pub const LEN: usize = 32 + 8 + 8;
+    pub const LEN: usize = 32 + 8 + 8 + 8 + 8 + 8 + 8;
+
+    /// Accrues rewards of the amount since the timestamp,
+    /// rewards of the running period are credited once the period is over
+    #[allow(clippy::too_many_arguments)]
+    fn accrue(
+        &mut self,
+        vault: &RewardVault,
+        tier: &RewardTier,
+        amount: u128,
+        boost_bps: u16,
+        referral_share_bps: u16,
+        accrued_from: u64,
+        current_timestamp: u64,
+    ) -> ProgramResult {
+        let reward_period_sec = vault.reward_period_sec as u64;
+        if self.period_started_at == 0 {
+            self.period_started_at = accrued_from;
+        }
+
+        // rate of the position per reward period, boosted by held sources
+        let rate = amount
+            .checked_mul(tier.ratio_quote.into())
+            .ok_or(EverlendError::MathOverflow)?
+            .checked_mul(BPS_SCALER + boost_bps as u128)
+            .ok_or(EverlendError::MathOverflow)?;
+        let period_scale = (tier.ratio_base as u128)
+            .checked_mul(BPS_SCALER)
+            .ok_or(EverlendError::MathOverflow)?
+            .checked_mul(reward_period_sec.into())
+            .ok_or(EverlendError::MathOverflow)?;
+
+        // time rewards weren't accrued for, e.g. while the vault was disabled, is skipped
+        let mut period_end = vault.period_end(self.period_started_at)?;
+        if accrued_from >= period_end {
+            self.settle_period(vault, tier, referral_share_bps)?;
+
+            self.period_started_at = if bool::from(vault.is_epoch_aligned) {
+                accrued_from
+            } else {
+                let skipped_periods = (accrued_from - self.period_started_at)
+                    .checked_div(reward_period_sec)
+                    .ok_or(EverlendError::MathOverflow)?;
+                self.period_started_at + skipped_periods * reward_period_sec
+            };
+            period_end = vault.period_end(self.period_started_at)?;
+        }
+
+        if current_timestamp < period_end {
+            return self.accrue_running_period(
+                vault,
+                rate,
+                period_scale,
+                accrued_from,
+                current_timestamp,
+            );
+        }
+
+        self.accrue_running_period(vault, rate, period_scale, accrued_from, period_end)?;
+        self.settle_period(vault, tier, referral_share_bps)?;
+
+        // whole periods passed since the running one
+        let num_periods = (current_timestamp - period_end)
+            .checked_div(reward_period_sec)
+            .ok_or(EverlendError::MathOverflow)?;
+        let rewards = self.rewards_for(rate, period_scale, num_periods * reward_period_sec)?;
+        let rewards = if tier.reward_max_amount_per_period > 0 {
+            let max_amount = tier
+                .reward_max_amount_per_period
+                .checked_mul(num_periods)
+                .ok_or(EverlendError::MathOverflow)?;
+            cmp::min(rewards, max_amount)
+        } else {
+            rewards
+        };
+        self.credit(rewards, referral_share_bps)?;
+
+        self.period_started_at = period_end + num_periods * reward_period_sec;
+        self.accrue_running_period(
+            vault,
+            rate,
+            period_scale,
+            self.period_started_at,
+            current_timestamp,
+        )
+    }
+
+    fn accrue_running_period(
+        &mut self,
+        vault: &RewardVault,
+        rate: u128,
+        period_scale: u128,
+        accrued_from: u64,
+        accrued_to: u64,
+    ) -> ProgramResult {
+        if !vault.is_partial_epoch_excluded() {
+            let rewards =
+                self.rewards_for(rate, period_scale, accrued_to.saturating_sub(accrued_from))?;
+            return self.add_pending_rewards(rewards);
+        }
+
+        // the epoch pays for the smallest position held through it
+        let epoch_rewards = if rate == 0 {
+            0
+        } else {
+            let epoch_rewards = rate
+                .checked_mul(vault.reward_period_sec.into())
+                .ok_or(EverlendError::MathOverflow)?
+                .checked_div(period_scale)
+                .ok_or(EverlendError::MathOverflow)?;
+            u64::try_from(epoch_rewards).map_err(|_| EverlendError::MathOverflow)?
+        };
+        self.pending_rewards = if accrued_from == self.period_started_at {
+            epoch_rewards
+        } else {
+            cmp::min(self.pending_rewards, epoch_rewards)
+        };
+
+        Ok(())
+    }
+
+    /// Rewards for the duration, the fraction of a reward unit is carried to the next accrual
+    fn rewards_for(
+        &mut self,
+        rate: u128,
+        period_scale: u128,
+        duration: u64,
+    ) -> Result<u64, ProgramError> {
+        let accrued = rate
+            .checked_mul(duration.into())
+            .ok_or(EverlendError::MathOverflow)?;
+        if accrued == 0 {
+            return Ok(0);
+        }
+
+        let rewards = accrued
+            .checked_div(period_scale)
+            .ok_or(EverlendError::MathOverflow)?;
+        let remainder = (accrued % period_scale)
+            .checked_mul(REMAINDER_SCALER)
+            .ok_or(EverlendError::MathOverflow)?
+            / period_scale
+            + self.rewards_remainder as u128;
+
+        self.rewards_remainder = (remainder % REMAINDER_SCALER) as u64;
+
+        let rewards = rewards
+            .checked_add(remainder / REMAINDER_SCALER)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        u64::try_from(rewards).map_err(|_| EverlendError::MathOverflow.into())
+    }
+
+    fn add_pending_rewards(&mut self, rewards: u64) -> ProgramResult {
+        self.pending_rewards = self
+            .pending_rewards
+            .checked_add(rewards)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Credits rewards of the finished period up to the tier max amount
+    fn settle_period(
+        &mut self,
+        vault: &RewardVault,
+        tier: &RewardTier,
+        referral_share_bps: u16,
+    ) -> ProgramResult {
+        let rewards = std::mem::take(&mut self.pending_rewards);
+        // the position joined in the middle of the epoch
+        let rewards = if vault.is_partial_epoch_excluded()
+            && !vault.is_whole_period(self.period_started_at)?
+        {
+            0
+        } else {
+            rewards
+        };
+        let rewards = if tier.reward_max_amount_per_period > 0 {
+            cmp::min(rewards, tier.reward_max_amount_per_period)
+        } else {
+            rewards
+        };
+
+        self.credit(rewards, referral_share_bps)
+    }
+
+    fn credit(&mut self, rewards: u64, referral_share_bps: u16) -> ProgramResult {
+        self.rewards = self
+            .rewards
+            .checked_add(rewards)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        // referrer share is paid on top of the referee rewards
+        self.referral_rewards = self
+            .referral_rewards
+            .checked_add(bps_floor(rewards, referral_share_bps)?)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
 }
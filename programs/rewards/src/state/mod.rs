@@ -5,16 +5,30 @@ mod reward_pool;
 mod rewards_root;
 mod deprecated_reward_pool;
 mod deprecated_mining;
+mod stakers_index;
+mod funder_contribution;
+mod referrer;
+mod boost_source;
+mod denylist;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use solana_program::msg;
+use solana_program::program_error::ProgramError;
 pub use mining::*;
 pub use reward_pool::*;
 pub use rewards_root::*;
 pub use deprecated_reward_pool::*;
 pub use deprecated_mining::*;
+pub use stakers_index::*;
+pub use funder_contribution::*;
+pub use referrer::*;
+pub use boost_source::*;
+pub use denylist::*;
 
 /// Enum representing the account type managed by the program
-#[derive(Clone, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub enum AccountType {
     /// If the account has not been initialized, the enum will be 0
     Uninitialized,
@@ -24,4 +38,111 @@ pub enum AccountType {
     RewardPool,
     /// Mining account
     Mining,
+    /// Stakers index
+    StakersIndex,
+    /// Funder contribution
+    FunderContribution,
+    /// Referrer
+    Referrer,
+    /// Boost source
+    BoostSource,
+    /// Denylist
+    Denylist,
+}
+
+/// Boolean flag usable inside zero-copy layouts
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct PodBool(u8);
+
+impl From<bool> for PodBool {
+    fn from(value: bool) -> Self {
+        PodBool(value as u8)
+    }
+}
+
+impl From<PodBool> for bool {
+    fn from(value: PodBool) -> Self {
+        value.0 != 0
+    }
+}
+
+impl PartialEq<bool> for PodBool {
+    fn eq(&self, other: &bool) -> bool {
+        bool::from(*self) == *other
+    }
+}
+
+/// Account state that is read and written in place instead of being deserialized
+pub trait ZeroCopy: Pod {
+    /// Account type stored in the first byte of the layout
+    const ACCOUNT_TYPE: AccountType;
+    /// Account data length
+    const LEN: usize = std::mem::size_of::<Self>();
+
+    /// Borrows account data in place without checking the account type
+    fn load_unchecked_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let data = data.get_mut(..Self::LEN).ok_or(ProgramError::AccountDataTooSmall)?;
+        bytemuck::try_from_bytes_mut(data).map_err(|err| {
+            msg!("Failed to load account data: {}", err);
+            ProgramError::InvalidAccountData
+        })
+    }
+
+    /// Borrows initialized account data in place
+    fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        let data = data.get(..Self::LEN).ok_or(ProgramError::AccountDataTooSmall)?;
+        if data[0] != Self::ACCOUNT_TYPE as u8 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        bytemuck::try_from_bytes(data).map_err(|err| {
+            msg!("Failed to load account data: {}", err);
+            ProgramError::InvalidAccountData
+        })
+    }
+
+    /// Mutably borrows initialized account data in place
+    fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.first() != Some(&(Self::ACCOUNT_TYPE as u8)) {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Self::load_unchecked_mut(data)
+    }
+
+    /// Copies account state out of the account data
+    fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        let data = data.get(..Self::LEN).ok_or(ProgramError::AccountDataTooSmall)?;
+        if data[0] != Self::ACCOUNT_TYPE as u8 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        bytemuck::try_pod_read_unaligned(data).map_err(|err| {
+            msg!("Failed to read account data: {}", err);
+            ProgramError::InvalidAccountData
+        })
+    }
+}
+
+/// Borrows a run of zero-copy records stored after the account header
+fn load_records<T: Pod>(data: &[u8], len: usize) -> Result<&[T], ProgramError> {
+    let data = data
+        .get(..len * std::mem::size_of::<T>())
+        .ok_or(ProgramError::AccountDataTooSmall)?;
+    bytemuck::try_cast_slice(data).map_err(|err| {
+        msg!("Failed to load account records: {}", err);
+        ProgramError::InvalidAccountData
+    })
+}
+
+/// Mutably borrows a run of zero-copy records stored after the account header
+fn load_records_mut<T: Pod>(data: &mut [u8], len: usize) -> Result<&mut [T], ProgramError> {
+    let data = data
+        .get_mut(..len * std::mem::size_of::<T>())
+        .ok_or(ProgramError::AccountDataTooSmall)?;
+    bytemuck::try_cast_slice_mut(data).map_err(|err| {
+        msg!("Failed to load account records: {}", err);
+        ProgramError::InvalidAccountData
+    })
 }
@@ -0,0 +1,54 @@
+use super::AccountType;
+use crate::state::ZeroCopy;
+use bytemuck::{Pod, Zeroable};
+use everlend_utils::EverlendError;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Referrals of a wallet in a single reward pool
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Referrer {
+    /// Account type - Referrer
+    pub account_type: u8,
+    /// Saved bump for referrer account
+    pub bump: u8,
+    /// Padding
+    pub _padding: [u8; 6],
+    /// Reward pool address
+    pub reward_pool: Pubkey,
+    /// Referrer wallet
+    pub owner: Pubkey,
+    /// Number of referred minings
+    pub referrals_count: u64,
+    /// Timestamp of the last referral
+    pub last_referred_at: u64,
+}
+
+impl ZeroCopy for Referrer {
+    const ACCOUNT_TYPE: AccountType = AccountType::Referrer;
+}
+
+impl Referrer {
+    /// Initialize a Referrer
+    pub fn initialize(reward_pool: Pubkey, owner: Pubkey, bump: u8) -> Referrer {
+        Referrer {
+            account_type: AccountType::Referrer as u8,
+            bump,
+            reward_pool,
+            owner,
+            ..Zeroable::zeroed()
+        }
+    }
+
+    /// Records a referral
+    pub fn record(&mut self, timestamp: u64) -> Result<(), ProgramError> {
+        self.referrals_count = self
+            .referrals_count
+            .checked_add(1)
+            .ok_or(EverlendError::MathOverflow)?;
+        self.last_referred_at = timestamp;
+
+        Ok(())
+    }
+}
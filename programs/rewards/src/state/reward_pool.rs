@@ -1,27 +1,50 @@
-use crate::state::{AccountType, Mining};
+use super::{load_records, load_records_mut};
+use crate::find_vault_spl_token_account;
+use crate::state::{
+    AccountType, DeprecatedRewardPool, Mining, MiningMut, PodBool, ZeroCopy,
+    DEPRECATED_MAX_REWARDS, DEPRECATED_MAX_TIERS,
+};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use bytemuck::{Pod, Zeroable};
+use everlend_utils::cpi::spl_token::find_associated_token_account;
 use everlend_utils::EverlendError;
 use solana_program::entrypoint::ProgramResult;
-use solana_program::msg;
 use solana_program::program_error::ProgramError;
-use solana_program::program_pack::{IsInitialized, Pack, Sealed};
 use solana_program::pubkey::Pubkey;
-use crate::state::deprecated_reward_pool::DeprecatedRewardPool;
+use std::cmp;
 
-/// Max reward vaults
-pub const MAX_REWARDS: usize = 3;
-/// Max reward tiers
-pub const MAX_TIERS: usize = 5;
+/// Max number of reward tiers with a burn price
+pub const MAX_TIER_PRICES: usize = 8;
+
+/// Max number of rate changes kept per vault
+pub const MAX_RATE_CHECKPOINTS: usize = 4;
 
 /// Reward pool
-#[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema)]
+///
+/// The account data holds this header followed by `vaults_capacity` vault records,
+/// `vaults_capacity * tiers_capacity` reward tier records, `MAX_RATE_CHECKPOINTS`
+/// rate checkpoints per vault and `tiers_capacity` reward tier records per checkpoint.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct RewardPool {
     /// Account type - RewardPool
-    pub account_type: AccountType,
-    /// Rewards root account (ex-Config program account)
-    pub rewards_root: Pubkey,
+    pub account_type: u8,
     /// Saved bump for reward pool account
     pub bump: u8,
+    /// Saved bump for reward pool authority
+    pub authority_bump: u8,
+    /// Saved bump for reward pool token account
+    pub spl_bump: u8,
+    /// Number of initialized vaults
+    pub vaults_len: u8,
+    /// Max number of vaults
+    pub vaults_capacity: u8,
+    /// Max number of reward tiers per vault
+    pub tiers_capacity: u8,
+    /// Pool status
+    pub status: u8,
+    /// Rewards root account (ex-Config program account)
+    pub rewards_root: Pubkey,
     /// Liquidity mint
     pub liquidity_mint: Pubkey,
     /// max stakers
@@ -32,56 +55,646 @@ pub struct RewardPool {
     pub total_amount: u64,
     /// staking lock time
     pub lock_time_sec: u64,
-    /// A set of all possible rewards that we can get for this pool
-    pub vaults: Vec<RewardVault>,
+    /// Min amount of a single deposit
+    pub min_deposit_amount: u64,
+    /// Max amount of a single deposit, zero means no limit
+    pub max_deposit_amount: u64,
+    /// Min position size, smaller positions can be evicted
+    pub min_stake_amount: u64,
+    /// Max position size per wallet, zero means no limit
+    pub max_stake_amount: u64,
+    /// Larger stakers can displace the smallest one when the pool is full
+    pub is_competitive: PodBool,
+    /// Saved bump for stakers index account
+    pub stakers_index_bump: u8,
+    /// Unbonding amount is released linearly instead of at the end of the period
+    pub is_linear_unbonding: PodBool,
+    /// Deposits are checked against the denylist of the rewards root
+    pub has_denylist: PodBool,
+    /// Saved bump for denylist account of the rewards root
+    pub denylist_bump: u8,
+    /// Padding
+    pub _padding: [u8; 3],
+    /// Unbonding period, zero means withdrawals don't need unbonding
+    pub unbonding_period_sec: u64,
+    /// Treasury wallet, fees go to its associated token accounts
+    pub treasury: Pubkey,
+    /// Total fees charged on deposits
+    pub total_deposit_fees: u64,
+    /// Total fees charged on withdrawals
+    pub total_withdraw_fees: u64,
+    /// Deposit fee in basis points
+    pub deposit_fee_bps: u16,
+    /// Withdraw fee in basis points
+    pub withdraw_fee_bps: u16,
+    /// Claim fee in basis points
+    pub claim_fee_bps: u16,
+    /// Deposit and withdraw fees fill the liquidity mint reward vault instead of the treasury
+    pub is_fee_to_vault: PodBool,
+    /// Padding
+    pub _fees_padding: [u8; 1],
+    /// Share of the referee rewards accrued for the referrer in basis points
+    pub referral_share_bps: u16,
+    /// Number of registered boost sources
+    pub boost_sources_len: u8,
+    /// Padding
+    pub _boost_padding: [u8; 5],
+    /// Mint burned to buy reward tiers
+    pub burn_mint: Pubkey,
+    /// How long a bought tier lasts, zero means forever
+    pub tier_duration_sec: u64,
+    /// Burn price of each reward tier, zero means the tier can't be bought
+    pub tier_prices: [u64; MAX_TIER_PRICES],
+    /// Warm-up period of new deposits, zero means deposits earn right away
+    pub warmup_period_sec: u64,
+    /// Reward rate during the warm-up in basis points
+    pub warmup_rate_bps: u16,
+    /// Padding
+    pub _warmup_padding: [u8; 6],
+    /// Transferable receipt mint of staked amounts, default when the pool has no receipts
+    pub receipt_mint: Pubkey,
+    /// Receipt mint bump
+    pub receipt_bump: u8,
+    /// Padding
+    pub _receipt_padding: [u8; 7],
+    /// End of the closing grace period, positions left after it can be force unstaked
+    pub closes_at: u64,
+    /// Total amount of pending unstake requests
+    pub total_unbonding_amount: u64,
+    /// Max lock a funder can enforce on a position, zero means funders can't enforce locks
+    pub max_funder_lock_sec: u64,
+    /// Mining accounts open in the pool, empty ones can still hold unclaimed rewards
+    pub total_minings: u64,
+}
+
+/// Reward pool status
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum PoolStatus {
+    /// Deposits, withdrawals and claims are allowed
+    Active,
+    /// New deposits are rejected, withdrawals and claims are allowed
+    Paused,
+    /// Pool is being sunset, stakers exit during the grace period and the rest is force unstaked
+    Closing,
+}
+
+/// Reward vault kind
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, BorshDeserialize, BorshSerialize, BorshSchema)]
+pub enum VaultKind {
+    /// Rewards are transferred from the pre-funded vault token account
+    Transfer,
+    /// Rewards are minted on claim, the reward pool is the mint authority
+    Mint,
+    /// Rewards are paid in lamports held by the program owned vault account
+    Native,
+}
+
+impl ZeroCopy for RewardPool {
+    const ACCOUNT_TYPE: AccountType = AccountType::RewardPool;
 }
 
 impl RewardPool {
     /// Init reward pool
     pub fn init(params: InitRewardPoolParams) -> RewardPool {
         RewardPool {
-            account_type: AccountType::RewardPool,
+            account_type: AccountType::RewardPool as u8,
             rewards_root: params.rewards_root,
             bump: params.bump,
+            authority_bump: params.authority_bump,
+            spl_bump: params.spl_bump,
             liquidity_mint: params.liquidity_mint,
-            total_amount: 0,
-            total_stakers: 0,
             lock_time_sec: params.lock_time_sec,
-            vaults: vec![],
             max_stakers: params.max_stakers,
+            vaults_capacity: params.vaults_capacity,
+            tiers_capacity: params.tiers_capacity,
+            ..Zeroable::zeroed()
         }
     }
 
-    /// Returns reward index
-    pub fn update_vault_totals(&mut self, reward_mint: Pubkey, amount: u64) -> ProgramResult {
-        match self
+    /// Account data length for the given capacity
+    pub fn account_len(vaults_capacity: u8, tiers_capacity: u8) -> usize {
+        let vaults_capacity = vaults_capacity as usize;
+        let checkpoints_capacity = vaults_capacity * MAX_RATE_CHECKPOINTS;
+
+        Self::LEN
+            + vaults_capacity * RewardVault::LEN
+            + vaults_capacity * tiers_capacity as usize * RewardTier::LEN
+            + checkpoints_capacity * RateCheckpoint::LEN
+            + checkpoints_capacity * tiers_capacity as usize * RewardTier::LEN
+    }
+
+    /// Borrows reward pool with its vaults in place
+    pub fn load_account(data: &[u8]) -> Result<RewardPoolRef<'_>, ProgramError> {
+        let pool = Self::load(data)?;
+        let vaults_capacity = pool.vaults_capacity as usize;
+        let tiers_capacity = pool.tiers_capacity as usize;
+        let checkpoints_capacity = vaults_capacity * MAX_RATE_CHECKPOINTS;
+
+        let records = &data[Self::LEN..];
+        let tiers = records
+            .get(vaults_capacity * RewardVault::LEN..)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        let checkpoints = tiers
+            .get(vaults_capacity * tiers_capacity * RewardTier::LEN..)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        let checkpoint_tiers = checkpoints
+            .get(checkpoints_capacity * RateCheckpoint::LEN..)
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+
+        Ok(RewardPoolRef {
+            pool,
+            vaults: load_records(records, vaults_capacity)?,
+            tiers: load_records(tiers, vaults_capacity * tiers_capacity)?,
+            checkpoints: load_records(checkpoints, checkpoints_capacity)?,
+            checkpoint_tiers: load_records(
+                checkpoint_tiers,
+                checkpoints_capacity * tiers_capacity,
+            )?,
+        })
+    }
+
+    /// Mutably borrows reward pool with its vaults in place
+    pub fn load_account_mut(data: &mut [u8]) -> Result<RewardPoolMut<'_>, ProgramError> {
+        let (vaults_capacity, tiers_capacity) = {
+            let pool = Self::load(data)?;
+            (pool.vaults_capacity as usize, pool.tiers_capacity as usize)
+        };
+        let checkpoints_capacity = vaults_capacity * MAX_RATE_CHECKPOINTS;
+
+        let (pool, records) = data.split_at_mut(Self::LEN);
+        let vaults_len = cmp::min(vaults_capacity * RewardVault::LEN, records.len());
+        let (vaults, tiers) = records.split_at_mut(vaults_len);
+        let tiers_len = cmp::min(
+            vaults_capacity * tiers_capacity * RewardTier::LEN,
+            tiers.len(),
+        );
+        let (tiers, checkpoints) = tiers.split_at_mut(tiers_len);
+        let checkpoints_len = cmp::min(
+            checkpoints_capacity * RateCheckpoint::LEN,
+            checkpoints.len(),
+        );
+        let (checkpoints, checkpoint_tiers) = checkpoints.split_at_mut(checkpoints_len);
+
+        Ok(RewardPoolMut {
+            pool: Self::load_mut(pool)?,
+            vaults: load_records_mut(vaults, vaults_capacity)?,
+            tiers: load_records_mut(tiers, vaults_capacity * tiers_capacity)?,
+            checkpoints: load_records_mut(checkpoints, checkpoints_capacity)?,
+            checkpoint_tiers: load_records_mut(
+                checkpoint_tiers,
+                checkpoints_capacity * tiers_capacity,
+            )?,
+        })
+    }
+
+    /// Lays vault, tier and rate checkpoint records out for a bigger capacity.
+    /// Account data must already have the new length.
+    pub fn resize(data: &mut [u8], vaults_capacity: u8, tiers_capacity: u8) -> ProgramResult {
+        let (old_vaults_capacity, old_tiers_capacity) = {
+            let pool = Self::load(data)?;
+            (pool.vaults_capacity, pool.tiers_capacity)
+        };
+
+        if vaults_capacity < old_vaults_capacity || tiers_capacity < old_tiers_capacity {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let len = Self::account_len(vaults_capacity, tiers_capacity);
+        if data.len() < len {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        // records are copied from a snapshot, the new layout shifts every record run
+        let old_data = data[..Self::account_len(old_vaults_capacity, old_tiers_capacity)].to_vec();
+        let old = Self::load_account(&old_data)?;
+
+        data[Self::LEN..len].fill(0);
+        {
+            let pool = Self::load_mut(data)?;
+            pool.vaults_capacity = vaults_capacity;
+            pool.tiers_capacity = tiers_capacity;
+        }
+
+        let new = Self::load_account_mut(data)?;
+        let old_stride = old_tiers_capacity as usize;
+        let new_stride = tiers_capacity as usize;
+
+        new.vaults[..old.vaults.len()].copy_from_slice(old.vaults);
+        new.checkpoints[..old.checkpoints.len()].copy_from_slice(old.checkpoints);
+        for (i, tiers) in old.tiers.chunks(old_stride).enumerate() {
+            new.tiers[i * new_stride..i * new_stride + old_stride].copy_from_slice(tiers);
+        }
+        for (i, tiers) in old.checkpoint_tiers.chunks(old_stride).enumerate() {
+            new.checkpoint_tiers[i * new_stride..i * new_stride + old_stride]
+                .copy_from_slice(tiers);
+        }
+
+        Ok(())
+    }
+
+    /// Pool status
+    pub fn pool_status(&self) -> PoolStatus {
+        match self.status {
+            0 => PoolStatus::Active,
+            1 => PoolStatus::Paused,
+            _ => PoolStatus::Closing,
+        }
+    }
+
+    /// Checks the pool is closing and its grace period is over
+    pub fn assert_closed(&self, timestamp: u64) -> ProgramResult {
+        if self.pool_status() != PoolStatus::Closing {
+            return Err(EverlendError::PoolIsNotClosing.into());
+        }
+        if timestamp < self.closes_at {
+            return Err(EverlendError::ClosingGracePeriodStillActive.into());
+        }
+
+        Ok(())
+    }
+
+    /// Checks the pool mints receipts of staked amounts
+    pub fn has_receipt(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+
+    /// Burn price of the reward tier
+    pub fn tier_price(&self, tier: u8) -> Option<u64> {
+        if tier >= self.tiers_capacity {
+            return None;
+        }
+
+        self.tier_prices
+            .get(tier as usize)
+            .copied()
+            .filter(|price| *price > 0)
+    }
+
+    /// Token account receiving deposit and withdraw fees
+    pub fn liquidity_fee_token_account(&self, program_id: &Pubkey, reward_pool: &Pubkey) -> Pubkey {
+        if bool::from(self.is_fee_to_vault) {
+            find_vault_spl_token_account(program_id, reward_pool, &self.liquidity_mint).0
+        } else {
+            find_associated_token_account(&self.treasury, &self.liquidity_mint).0
+        }
+    }
+
+    /// Process opening of a mining account
+    pub fn open_mining(&mut self) -> ProgramResult {
+        self.total_minings = self
+            .total_minings
+            .checked_add(1)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Process closing of a mining account
+    pub fn close_mining(&mut self) -> ProgramResult {
+        self.total_minings = self
+            .total_minings
+            .checked_sub(1)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Process withdraw
+    pub fn withdraw(&mut self, amount: u64) -> ProgramResult {
+        self.total_amount = self
+            .total_amount
+            .checked_sub(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        // evicted positions are already excluded from stakers
+        if amount > 0 {
+            self.total_stakers = self
+                .total_stakers
+                .checked_sub(1)
+                .ok_or(EverlendError::MathOverflow)?;
+        }
+
+        Ok(())
+    }
+
+    /// Process release of the unbonded amount
+    pub fn finalize_unstake(
+        &mut self,
+        mining: &mut Mining,
+        timestamp: u64,
+    ) -> Result<u64, ProgramError> {
+        mining.assert_owner_controlled()?;
+        mining.assert_not_frozen()?;
+
+        let amount = mining.unbonding_amount;
+        if amount == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        // closing pools release everything right away
+        let released = if timestamp >= mining.unlock_at || self.pool_status() == PoolStatus::Closing
+        {
+            amount
+        } else if bool::from(self.is_linear_unbonding) {
+            // the rest keeps being released at the same rate until unlock
+            let elapsed = timestamp.saturating_sub(mining.unbonding_started_at);
+            let period = mining.unlock_at - mining.unbonding_started_at;
+            (amount as u128 * elapsed as u128 / period as u128) as u64
+        } else {
+            0
+        };
+
+        if released == 0 {
+            return Err(EverlendError::UnbondingStillActive.into());
+        }
+
+        self.total_unbonding_amount = self
+            .total_unbonding_amount
+            .checked_sub(released)
+            .ok_or(EverlendError::MathOverflow)?;
+        mining.unbonding_amount -= released;
+        if mining.unbonding_amount == 0 {
+            mining.unbonding_started_at = 0;
+            mining.unlock_at = 0;
+        } else {
+            mining.unbonding_started_at = timestamp;
+        }
+
+        Ok(released)
+    }
+
+    /// Process migrate
+    pub fn migrate(
+        deprecated_pool: &DeprecatedRewardPool,
+        authority_bump: u8,
+        spl_bump: u8,
+    ) -> RewardPool {
+        let tiers_capacity = deprecated_pool
             .vaults
             .iter()
-            .position(|mi| mi.reward_mint == reward_mint)
-        {
-            Some(i) => {
-                self.vaults[i].claimed_total_amount += amount;
-                Ok(())
+            .map(|v| v.reward_tiers.len())
+            .fold(DEPRECATED_MAX_TIERS, cmp::max);
+
+        let mut reward_pool = RewardPool::init(InitRewardPoolParams {
+            rewards_root: deprecated_pool.rewards_root,
+            bump: deprecated_pool.bump,
+            authority_bump,
+            spl_bump,
+            liquidity_mint: deprecated_pool.liquidity_mint,
+            lock_time_sec: deprecated_pool.lock_time_sec,
+            max_stakers: deprecated_pool.max_stakers,
+            vaults_capacity: cmp::max(deprecated_pool.vaults.len(), DEPRECATED_MAX_REWARDS) as u8,
+            tiers_capacity: tiers_capacity as u8,
+        });
+        reward_pool.total_stakers = deprecated_pool.total_stakers;
+        reward_pool.total_amount = deprecated_pool.total_amount;
+        // minings are counted as they're migrated
+
+        reward_pool
+    }
+}
+
+/// Reward pool with its vault records
+#[derive(Clone, Copy)]
+pub struct RewardPoolRef<'a> {
+    /// Reward pool header
+    pub pool: &'a RewardPool,
+    vaults: &'a [RewardVault],
+    tiers: &'a [RewardTier],
+    checkpoints: &'a [RateCheckpoint],
+    checkpoint_tiers: &'a [RewardTier],
+}
+
+impl<'a> RewardPoolRef<'a> {
+    /// Initialized vaults
+    pub fn vaults(&self) -> &'a [RewardVault] {
+        &self.vaults[..self.pool.vaults_len as usize]
+    }
+
+    /// Reward tiers of the vault at index
+    pub fn vault_tiers(&self, index: usize) -> &'a [RewardTier] {
+        let start = index * self.pool.tiers_capacity as usize;
+        &self.tiers[start..start + self.vaults[index].tiers_len as usize]
+    }
+
+    /// Initialized vaults along with their reward tiers and rate history
+    pub fn iter_vaults(self) -> impl Iterator<Item = VaultRates<'a>> + Clone {
+        self.vaults()
+            .iter()
+            .enumerate()
+            .map(move |(i, vault)| VaultRates {
+                vault,
+                tiers: self.vault_tiers(i),
+                checkpoints: &self.checkpoints
+                    [i * MAX_RATE_CHECKPOINTS..(i + 1) * MAX_RATE_CHECKPOINTS],
+                checkpoint_tiers: self.vault_checkpoint_tiers(i),
+                tiers_capacity: self.pool.tiers_capacity as usize,
+            })
+    }
+
+    fn vault_checkpoint_tiers(&self, index: usize) -> &'a [RewardTier] {
+        let stride = MAX_RATE_CHECKPOINTS * self.pool.tiers_capacity as usize;
+        &self.checkpoint_tiers[index * stride..(index + 1) * stride]
+    }
+
+    /// Returns vault by reward mint
+    pub fn find_vault(&self, reward_mint: &Pubkey) -> Option<&'a RewardVault> {
+        self.vaults().iter().find(|v| &v.reward_mint == reward_mint)
+    }
+
+    fn vault_index(&self, reward_mint: &Pubkey) -> Option<usize> {
+        self.vaults()
+            .iter()
+            .position(|v| &v.reward_mint == reward_mint)
+    }
+}
+
+/// Mutable reward pool with its vault records
+pub struct RewardPoolMut<'a> {
+    /// Reward pool header
+    pub pool: &'a mut RewardPool,
+    vaults: &'a mut [RewardVault],
+    tiers: &'a mut [RewardTier],
+    checkpoints: &'a mut [RateCheckpoint],
+    checkpoint_tiers: &'a mut [RewardTier],
+}
+
+impl RewardPoolMut<'_> {
+    /// Reborrows as read only
+    pub fn to_ref(&self) -> RewardPoolRef<'_> {
+        RewardPoolRef {
+            pool: self.pool,
+            vaults: self.vaults,
+            tiers: self.tiers,
+            checkpoints: self.checkpoints,
+            checkpoint_tiers: self.checkpoint_tiers,
+        }
+    }
+
+    /// Returns mutable vault by reward mint
+    pub fn find_vault_mut(&mut self, reward_mint: &Pubkey) -> Option<&mut RewardVault> {
+        let index = self.to_ref().vault_index(reward_mint)?;
+        Some(&mut self.vaults[index])
+    }
+
+    /// Replaces reward tiers of the vault
+    pub fn set_vault_tiers(&mut self, reward_mint: &Pubkey, tiers: &[RewardTier]) -> ProgramResult {
+        let index = self
+            .to_ref()
+            .vault_index(reward_mint)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+
+        self.write_tiers(index, tiers)
+    }
+
+    /// Keeps the current reward period and tiers of the vault for the time before the change,
+    /// the oldest checkpoint is replaced when the history is full
+    pub fn checkpoint_rates(&mut self, reward_mint: &Pubkey, timestamp: u64) -> ProgramResult {
+        let index = self
+            .to_ref()
+            .vault_index(reward_mint)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+
+        let checkpoints =
+            &mut self.checkpoints[index * MAX_RATE_CHECKPOINTS..(index + 1) * MAX_RATE_CHECKPOINTS];
+        // rates before the timestamp are already kept
+        if checkpoints.iter().any(|c| c.changed_at == timestamp) {
+            return Ok(());
+        }
+
+        let slot = checkpoints
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, c)| c.changed_at)
+            .map(|(slot, _)| slot)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+
+        let vault = &self.vaults[index];
+        checkpoints[slot] = RateCheckpoint {
+            changed_at: timestamp,
+            reward_period_sec: vault.reward_period_sec,
+            tiers_len: vault.tiers_len,
+            ..Default::default()
+        };
+
+        let capacity = self.pool.tiers_capacity as usize;
+        let start = (index * MAX_RATE_CHECKPOINTS + slot) * capacity;
+        self.checkpoint_tiers[start..start + capacity]
+            .copy_from_slice(&self.tiers[index * capacity..(index + 1) * capacity]);
+
+        Ok(())
+    }
+
+    fn write_tiers(&mut self, index: usize, tiers: &[RewardTier]) -> ProgramResult {
+        let capacity = self.pool.tiers_capacity as usize;
+        if tiers.len() > capacity {
+            return Err(EverlendError::InvalidRewardTier.into());
+        }
+
+        let start = index * capacity;
+        let slots = &mut self.tiers[start..start + capacity];
+        slots.fill(RewardTier::default());
+        slots[..tiers.len()].copy_from_slice(tiers);
+        self.vaults[index].tiers_len = tiers.len() as u8;
+
+        Ok(())
+    }
+
+    /// Returns reward index
+    pub fn update_vault_totals(
+        &mut self,
+        reward_mint: Pubkey,
+        amount: u64,
+        timestamp: u64,
+    ) -> ProgramResult {
+        let vault = self
+            .find_vault_mut(&reward_mint)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+        vault.claimed_total_amount += amount;
+
+        if vault.vault_kind() == VaultKind::Mint {
+            if vault.is_new_mint_period(timestamp) {
+                vault.period_started_at = timestamp;
+                vault.period_minted_amount = 0;
             }
-            None => Err(EverlendError::InvalidRewardVault.into()),
+            vault.period_minted_amount += amount;
+            vault.total_minted_amount = vault
+                .total_minted_amount
+                .checked_add(amount)
+                .ok_or(EverlendError::MathOverflow)?;
         }
+
+        Ok(())
+    }
+
+    /// Records fee charged on deposit
+    pub fn record_deposit_fee(&mut self, fee: u64) -> ProgramResult {
+        self.pool.total_deposit_fees = self
+            .pool
+            .total_deposit_fees
+            .checked_add(fee)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        self.fund_fee_vault(fee)
+    }
+
+    /// Records fee charged on withdraw
+    pub fn record_withdraw_fee(&mut self, fee: u64) -> ProgramResult {
+        self.pool.total_withdraw_fees = self
+            .pool
+            .total_withdraw_fees
+            .checked_add(fee)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        self.fund_fee_vault(fee)
+    }
+
+    fn fund_fee_vault(&mut self, fee: u64) -> ProgramResult {
+        if !bool::from(self.pool.is_fee_to_vault) {
+            return Ok(());
+        }
+
+        let liquidity_mint = self.pool.liquidity_mint;
+        let vault = self
+            .find_vault_mut(&liquidity_mint)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+        vault.total_funded = vault
+            .total_funded
+            .checked_add(fee)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
+    }
+
+    /// Records fee charged on claim
+    pub fn record_claim_fee(&mut self, reward_mint: Pubkey, fee: u64) -> ProgramResult {
+        let vault = self
+            .find_vault_mut(&reward_mint)
+            .ok_or(EverlendError::InvalidRewardVault)?;
+        vault.total_claim_fees = vault
+            .total_claim_fees
+            .checked_add(fee)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        Ok(())
     }
 
     /// Process add vault
-    pub fn add_vault(&mut self, reward: RewardVault) -> ProgramResult {
-        if self
-            .vaults
-            .iter()
-            .any(|v| v.reward_mint == reward.reward_mint)
-        {
+    pub fn add_vault(&mut self, reward: RewardVault, tiers: &[RewardTier]) -> ProgramResult {
+        if self.to_ref().find_vault(&reward.reward_mint).is_some() {
             return Err(ProgramError::InvalidArgument);
         }
 
-        if self.vaults.len() == MAX_REWARDS {
+        let index = self.pool.vaults_len as usize;
+        if index == self.vaults.len() {
             return Err(EverlendError::InvalidRewardVault.into());
         }
 
-        self.vaults.push(reward);
+        self.vaults[index] = reward;
+        self.write_tiers(index, tiers)?;
+        self.pool.vaults_len += 1;
 
         Ok(())
     }
@@ -89,69 +702,388 @@ impl RewardPool {
     /// Process deposit
     pub fn deposit(
         &mut self,
-        mining: &mut Mining,
+        mining: &mut MiningMut,
         amount: u64,
-        is_first_deposit: bool,
         timestamp: u64,
     ) -> ProgramResult {
-        mining.refresh_rewards(self.vaults.iter(), timestamp)?;
+        if self.pool.pool_status() != PoolStatus::Active {
+            return Err(EverlendError::PoolIsNotActive.into());
+        }
+        mining.mining.assert_owner_controlled()?;
+        mining.mining.assert_not_frozen()?;
+
+        if amount < self.pool.min_deposit_amount
+            || (self.pool.max_deposit_amount > 0 && amount > self.pool.max_deposit_amount)
+        {
+            return Err(EverlendError::InvalidDepositAmount.into());
+        }
+
+        if mining.mining.amount.saturating_add(amount) < self.pool.min_stake_amount {
+            return Err(EverlendError::InvalidStakeAmount.into());
+        }
+
+        self.stake(mining, amount, timestamp)?;
+        self.lock_deposit(mining, amount, timestamp)
+    }
+
+    /// Process sync of the position to the receipt balance of the owner,
+    /// received receipts are staked like a deposit and sent ones are unstaked
+    pub fn sync_receipt(
+        &mut self,
+        mining: &mut MiningMut,
+        balance: u64,
+        receipt_supply: u64,
+        timestamp: u64,
+    ) -> ProgramResult {
+        // receipts sent from or to a frozen position can't be synced
+        mining.mining.assert_not_frozen()?;
+
+        let amount = mining.mining.amount;
+        if balance > amount {
+            let received = balance - amount;
+            // receipts back one position only, so the sender has to be synced first
+            let total_amount = self
+                .pool
+                .total_amount
+                .checked_add(received)
+                .ok_or(EverlendError::MathOverflow)?;
+            if total_amount > receipt_supply {
+                return Err(EverlendError::ReceiptNotSynced.into());
+            }
+
+            self.stake(mining, received, timestamp)?;
+            self.lock_deposit(mining, received, timestamp)?;
+        } else if balance < amount {
+            mining.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
+
+            self.pool.total_amount = self
+                .pool
+                .total_amount
+                .checked_sub(amount - balance)
+                .ok_or(EverlendError::MathOverflow)?;
+            if balance == 0 {
+                self.pool.total_stakers = self
+                    .pool
+                    .total_stakers
+                    .checked_sub(1)
+                    .ok_or(EverlendError::MathOverflow)?;
+            }
+
+            mining.set_amount(balance);
+        }
+
+        Ok(())
+    }
+
+    /// Starts the warm-up and the lock of the deposited amount
+    fn lock_deposit(&self, mining: &mut MiningMut, amount: u64, timestamp: u64) -> ProgramResult {
+        // a new deposit restarts the warm-up of the whole warming amount
+        if self.pool.warmup_period_sec > 0 {
+            mining.mining.warming_amount = mining
+                .mining
+                .warming_amount
+                .checked_add(amount)
+                .ok_or(EverlendError::MathOverflow)?;
+            mining.mining.warm_at = timestamp
+                .checked_add(self.pool.warmup_period_sec)
+                .ok_or(EverlendError::MathOverflow)?;
+            mining.mining.warmup_rate_bps = self.pool.warmup_rate_bps;
+        }
+
+        mining.mining.last_deposit_time = timestamp;
+        mining.mining.lock_time_sec = self.pool.lock_time_sec;
+
+        Ok(())
+    }
+
+    /// Adds amount to the position
+    fn stake(&mut self, mining: &mut MiningMut, amount: u64, timestamp: u64) -> ProgramResult {
+        let stake_amount = mining
+            .mining
+            .amount
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        if self.pool.max_stake_amount > 0 && stake_amount > self.pool.max_stake_amount {
+            return Err(EverlendError::InvalidStakeAmount.into());
+        }
+
+        mining.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
 
-        if is_first_deposit {
-            if self.max_stakers > 0 && self.total_stakers >= self.max_stakers {
+        // empty positions (new, evicted or unbonded) don't occupy a staker slot
+        if mining.mining.amount == 0 && amount > 0 {
+            if self.pool.max_stakers > 0 && self.pool.total_stakers >= self.pool.max_stakers {
                 return Err(EverlendError::PoolIsFull.into());
             }
 
-            self.total_stakers += 1;
+            self.pool.total_stakers += 1;
         }
 
-        self.total_amount = self
+        self.pool.total_amount = self
+            .pool
             .total_amount
             .checked_add(amount)
             .ok_or(EverlendError::MathOverflow)?;
 
-        mining.amount = mining
+        mining.mining.amount = stake_amount;
+
+        Ok(())
+    }
+
+    /// Process eviction of a position below the min stake amount
+    pub fn evict(&mut self, mining: &mut MiningMut, timestamp: u64) -> Result<u64, ProgramError> {
+        mining.mining.assert_not_frozen()?;
+
+        let amount = mining.mining.amount;
+        if amount == 0 || amount >= self.pool.min_stake_amount {
+            return Err(EverlendError::PositionIsNotDust.into());
+        }
+
+        self.unstake(mining, timestamp)
+    }
+
+    /// Process displacement of the smallest position by a new staker in a full pool
+    pub fn displace(
+        &mut self,
+        mining: &mut MiningMut,
+        timestamp: u64,
+    ) -> Result<u64, ProgramError> {
+        // frozen positions leave the stakers index, they stay until the admin unfreezes
+        // or force withdraws them
+        mining.mining.assert_not_frozen()?;
+
+        self.unstake(mining, timestamp)
+    }
+
+    /// Process unstake request, the amount stops accruing rewards until it's unbonded
+    pub fn request_unstake(
+        &mut self,
+        mining: &mut MiningMut,
+        amount: u64,
+        timestamp: u64,
+    ) -> ProgramResult {
+        mining.mining.assert_owner_controlled()?;
+        mining.mining.assert_not_frozen()?;
+
+        let stake_amount = mining
+            .mining
             .amount
-            .checked_add(amount)
+            .checked_sub(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        if amount == 0 || (stake_amount > 0 && stake_amount < self.pool.min_stake_amount) {
+            return Err(EverlendError::InvalidStakeAmount.into());
+        }
+
+        mining.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
+
+        self.pool.total_amount = self
+            .pool
+            .total_amount
+            .checked_sub(amount)
             .ok_or(EverlendError::MathOverflow)?;
+        if stake_amount == 0 {
+            self.pool.total_stakers = self
+                .pool
+                .total_stakers
+                .checked_sub(1)
+                .ok_or(EverlendError::MathOverflow)?;
+        }
 
-        mining.last_deposit_time = timestamp;
+        // a new request restarts the unbonding period of the whole pending amount
+        mining.set_amount(stake_amount);
+        mining.mining.unbonding_amount = mining
+            .mining
+            .unbonding_amount
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        mining.mining.unbonding_started_at = timestamp;
+        mining.mining.unlock_at = timestamp
+            .checked_add(self.pool.unbonding_period_sec)
+            .ok_or(EverlendError::MathOverflow)?;
+        self.pool.total_unbonding_amount = self
+            .pool
+            .total_unbonding_amount
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
 
         Ok(())
     }
 
-    /// Process withdraw
-    pub fn withdraw(&mut self, amount: u64) -> ProgramResult {
-        self.total_amount = self
-            .total_amount
+    /// Process move of the amount between positions of the same owner,
+    /// both positions keep their tiers and the destination takes the later lock
+    pub fn move_stake(
+        &mut self,
+        source: &mut MiningMut,
+        destination: &mut MiningMut,
+        amount: u64,
+        timestamp: u64,
+    ) -> ProgramResult {
+        // positions of a competitive pool are indexed one by one
+        if bool::from(self.pool.is_competitive) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        source.mining.assert_owner_controlled()?;
+        source.mining.assert_not_frozen()?;
+        destination.mining.assert_owner_controlled()?;
+        destination.mining.assert_not_frozen()?;
+
+        let source_amount = source
+            .mining
+            .amount
             .checked_sub(amount)
             .ok_or(EverlendError::MathOverflow)?;
+        let destination_amount = destination
+            .mining
+            .amount
+            .checked_add(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+
+        // tokens still warming up stay in their position
+        if amount == 0
+            || source_amount < source.mining.warming_amount
+            || (source_amount > 0 && source_amount < self.pool.min_stake_amount)
+            || destination_amount < self.pool.min_stake_amount
+            || (self.pool.max_stake_amount > 0 && destination_amount > self.pool.max_stake_amount)
+        {
+            return Err(EverlendError::InvalidStakeAmount.into());
+        }
+
+        source.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
+        destination.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
+
+        if destination.mining.amount == 0 {
+            if source_amount > 0
+                && self.pool.max_stakers > 0
+                && self.pool.total_stakers >= self.pool.max_stakers
+            {
+                return Err(EverlendError::PoolIsFull.into());
+            }
+
+            self.pool.total_stakers += 1;
+        }
+        if source_amount == 0 {
+            self.pool.total_stakers = self
+                .pool
+                .total_stakers
+                .checked_sub(1)
+                .ok_or(EverlendError::MathOverflow)?;
+        }
+
+        // the moved amount keeps its lock
+        if source.mining.lock_ends_at(self.pool.lock_time_sec)
+            > destination.mining.lock_ends_at(self.pool.lock_time_sec)
+        {
+            destination.mining.last_deposit_time = source.mining.last_deposit_time;
+            destination.mining.lock_time_sec = source.mining.lock_time_sec;
+        }
+        destination
+            .mining
+            .enforce_lock(source.mining.enforced_lock_ends_at);
 
-        self.total_stakers -= 1;
+        source.set_amount(source_amount);
+        destination.set_amount(destination_amount);
 
         Ok(())
     }
 
-    /// Process migrate
-    pub fn migrate(deprecated_pool: &DeprecatedRewardPool, max_stakers: u64, total_stakers: u64) -> RewardPool {
-        Self {
-            account_type: deprecated_pool.account_type.clone(),
-            rewards_root: deprecated_pool.rewards_root,
-            bump: deprecated_pool.bump,
-            liquidity_mint: deprecated_pool.liquidity_mint,
-            max_stakers,
-            total_stakers,
-            total_amount: deprecated_pool.total_amount,
-            lock_time_sec: deprecated_pool.lock_time_sec,
-            vaults: deprecated_pool.vaults.iter().map(|v| RewardVault{
+    /// Process cancel of the pending unstake, the amount is staked again
+    pub fn cancel_unstake(&mut self, mining: &mut MiningMut, timestamp: u64) -> ProgramResult {
+        mining.mining.assert_owner_controlled()?;
+        mining.mining.assert_not_frozen()?;
+
+        let amount = mining.mining.unbonding_amount;
+        if amount == 0 {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        self.stake(mining, amount, timestamp)?;
+
+        self.pool.total_unbonding_amount = self
+            .pool
+            .total_unbonding_amount
+            .checked_sub(amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        mining.mining.unbonding_amount = 0;
+        mining.mining.unbonding_started_at = 0;
+        mining.mining.unlock_at = 0;
+
+        Ok(())
+    }
+
+    /// Process force unstake of the position left in a closed pool,
+    /// returns the staked and the pending unstake amount, accrued rewards are refreshed to be paid
+    pub fn force_unstake(
+        &mut self,
+        mining: &mut MiningMut,
+        timestamp: u64,
+    ) -> Result<u64, ProgramError> {
+        self.pool.assert_closed(timestamp)?;
+        mining.mining.assert_not_frozen()?;
+
+        let amount = self.unstake(mining, timestamp)?;
+
+        amount
+            .checked_add(self.take_unbonding(mining)?)
+            .ok_or_else(|| EverlendError::MathOverflow.into())
+    }
+
+    /// Process withdraw of the whole position by the root authority regardless of the lock,
+    /// returns the staked and the pending unstake amount, accrued rewards stay claimable
+    pub fn force_withdraw(
+        &mut self,
+        mining: &mut MiningMut,
+        timestamp: u64,
+    ) -> Result<u64, ProgramError> {
+        let amount = self.unstake(mining, timestamp)?;
+
+        amount
+            .checked_add(self.take_unbonding(mining)?)
+            .ok_or_else(|| EverlendError::MathOverflow.into())
+    }
+
+    /// Takes the pending unstake amount of the position
+    fn take_unbonding(&mut self, mining: &mut MiningMut) -> Result<u64, ProgramError> {
+        let unbonding_amount = mining.mining.unbonding_amount;
+
+        self.pool.total_unbonding_amount = self
+            .pool
+            .total_unbonding_amount
+            .checked_sub(unbonding_amount)
+            .ok_or(EverlendError::MathOverflow)?;
+        mining.mining.unbonding_amount = 0;
+        mining.mining.unbonding_started_at = 0;
+        mining.mining.unlock_at = 0;
+
+        Ok(unbonding_amount)
+    }
+
+    /// Process force unstake of the whole position, accrued rewards stay claimable
+    pub fn unstake(&mut self, mining: &mut MiningMut, timestamp: u64) -> Result<u64, ProgramError> {
+        let amount = mining.mining.amount;
+        mining.refresh_rewards(self.to_ref().iter_vaults(), timestamp)?;
+
+        self.pool.withdraw(amount)?;
+        mining.set_amount(0);
+
+        Ok(amount)
+    }
+
+    /// Process migrate of the deprecated vaults
+    pub fn migrate_vaults(&mut self, deprecated_pool: &DeprecatedRewardPool) -> ProgramResult {
+        for v in deprecated_pool.vaults.iter() {
+            let vault = RewardVault {
                 vault_token_account_bump: v.vault_token_account_bump,
                 reward_mint: v.reward_mint,
                 reward_period_sec: v.reward_period_sec,
-                is_enabled: v.is_enabled,
+                is_enabled: v.is_enabled.into(),
                 enabled_at: v.enabled_at,
-                claimed_total_amount: 0,
-                reward_tiers: v.reward_tiers.clone(),
-            }).collect(),
+                claimed_total_amount: v.claimed_total_amount,
+                ..Default::default()
+            };
+
+            self.add_vault(vault, &v.reward_tiers)?;
         }
+
+        Ok(())
     }
 }
 
@@ -161,65 +1093,170 @@ pub struct InitRewardPoolParams {
     pub rewards_root: Pubkey,
     /// Saved bump for reward pool account
     pub bump: u8,
+    /// Saved bump for reward pool authority
+    pub authority_bump: u8,
+    /// Saved bump for reward pool token account
+    pub spl_bump: u8,
     /// Liquidity mint
     pub liquidity_mint: Pubkey,
     /// staking lock time
     pub lock_time_sec: u64,
     /// max stakers
     pub max_stakers: u64,
-}
-
-impl Sealed for RewardPool {}
-impl Pack for RewardPool {
-    const LEN: usize = 1 + (32 + 1 + 32 + 8 + 8 + 8 + 8 + (4 + RewardVault::LEN * MAX_REWARDS));
-
-    fn pack_into_slice(&self, dst: &mut [u8]) {
-        let mut slice = dst;
-        self.serialize(&mut slice).unwrap()
-    }
-
-    fn unpack_from_slice(src: &[u8]) -> Result<RewardPool, ProgramError> {
-        let mut src_mut = src;
-        Self::deserialize(&mut src_mut).map_err(|err| {
-            msg!("Failed to deserialize");
-            msg!("{}", err.to_string());
-            ProgramError::InvalidAccountData
-        })
-    }
-}
-
-impl IsInitialized for RewardPool {
-    fn is_initialized(&self) -> bool {
-        self.account_type == AccountType::RewardPool
-    }
+    /// Max number of vaults
+    pub vaults_capacity: u8,
+    /// Max number of reward tiers per vault
+    pub tiers_capacity: u8,
 }
 
 /// Reward vault
-#[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema, Default, Clone)]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable)]
 pub struct RewardVault {
-    /// Bump of vault account
-    pub vault_token_account_bump: u8,
     /// Reward mint address
     pub reward_mint: Pubkey,
-    /// Time period for reward calculation
-    pub reward_period_sec: u32,
-    /// Is distribution enabled
-    pub is_enabled: bool,
+    /// Vault manager allowed to fill a permissioned vault, default if none
+    pub manager: Pubkey,
     /// Timestamp since when distribution begins
     pub enabled_at: u64,
     /// Total rewards
     pub claimed_total_amount: u64,
-    /// Reward tiers
-    pub reward_tiers: Vec<RewardTier>,
+    /// Total amount the vault was filled with
+    pub total_funded: u64,
+    /// Time period for reward calculation
+    pub reward_period_sec: u32,
+    /// Bump of vault account
+    pub vault_token_account_bump: u8,
+    /// Is distribution enabled
+    pub is_enabled: PodBool,
+    /// Number of initialized reward tiers
+    pub tiers_len: u8,
+    /// Only the root authority and the vault manager can fill the vault
+    pub is_fill_permissioned: PodBool,
+    /// Max amount a mint vault can mint in total, 0 if unlimited
+    pub max_mint_amount: u64,
+    /// Max amount a mint vault can mint per reward period, 0 if unlimited
+    pub max_mint_amount_per_period: u64,
+    /// Amount minted in the current reward period
+    pub period_minted_amount: u64,
+    /// Timestamp since when the current reward period counts mints
+    pub period_started_at: u64,
+    /// Total fees charged on claims
+    pub total_claim_fees: u64,
+    /// Vault kind
+    pub kind: u8,
+    /// Reward periods are epochs aligned to the epoch anchor
+    pub is_epoch_aligned: PodBool,
+    /// Epochs a position wasn't staked through pay nothing instead of being pro-rated
+    pub is_partial_epoch_excluded: PodBool,
+    /// Padding
+    pub _padding: [u8; 5],
+    /// Start of any epoch, e.g. 00:00 UTC of some day
+    pub epoch_anchor: u64,
+    /// Amount minted since the vault became a mint vault
+    pub total_minted_amount: u64,
 }
 
 impl RewardVault {
     /// LEN
-    pub const LEN: usize = 1 + 32 + 4 + 1 + 8 + 8 + (4 + RewardTier::LEN * MAX_TIERS);
+    pub const LEN: usize = std::mem::size_of::<RewardVault>();
+
+    /// Vault kind
+    pub fn vault_kind(&self) -> VaultKind {
+        match self.kind {
+            0 => VaultKind::Transfer,
+            1 => VaultKind::Mint,
+            _ => VaultKind::Native,
+        }
+    }
+
+    /// End of the reward period started at the timestamp,
+    /// periods of an epoch aligned vault end on the epoch boundary
+    pub fn period_end(&self, started_at: u64) -> Result<u64, ProgramError> {
+        let offset = if bool::from(self.is_epoch_aligned) {
+            self.epoch_offset(started_at)?
+        } else {
+            0
+        };
+
+        started_at
+            .checked_add(self.reward_period_sec as u64 - offset)
+            .ok_or_else(|| EverlendError::MathOverflow.into())
+    }
+
+    /// Checks the reward period started at the timestamp is a whole epoch
+    pub fn is_whole_period(&self, started_at: u64) -> Result<bool, ProgramError> {
+        Ok(!bool::from(self.is_epoch_aligned) || self.epoch_offset(started_at)? == 0)
+    }
+
+    /// Rewards of an epoch are paid for the smallest position held through it
+    pub fn is_partial_epoch_excluded(&self) -> bool {
+        bool::from(self.is_epoch_aligned) && bool::from(self.is_partial_epoch_excluded)
+    }
+
+    /// Time passed since the start of the epoch
+    fn epoch_offset(&self, timestamp: u64) -> Result<u64, ProgramError> {
+        (timestamp as i128 - self.epoch_anchor as i128)
+            .checked_rem_euclid(self.reward_period_sec.into())
+            .map(|offset| offset as u64)
+            .ok_or_else(|| EverlendError::MathOverflow.into())
+    }
+
+    /// Amount the vault can still pay out at the timestamp
+    pub fn claim_allowance(&self, timestamp: u64) -> u64 {
+        if self.vault_kind() != VaultKind::Mint {
+            return u64::MAX;
+        }
+
+        let total_allowance = if self.max_mint_amount == 0 {
+            u64::MAX
+        } else {
+            self.max_mint_amount
+                .saturating_sub(self.total_minted_amount)
+        };
+
+        let period_allowance = if self.max_mint_amount_per_period == 0 {
+            u64::MAX
+        } else if self.is_new_mint_period(timestamp) {
+            self.max_mint_amount_per_period
+        } else {
+            self.max_mint_amount_per_period
+                .saturating_sub(self.period_minted_amount)
+        };
+
+        cmp::min(total_allowance, period_allowance)
+    }
+
+    fn is_new_mint_period(&self, timestamp: u64) -> bool {
+        timestamp
+            >= self
+                .period_started_at
+                .saturating_add(self.reward_period_sec as u64)
+    }
+
+    /// Checks the authority is allowed to fill the vault
+    pub fn can_fill(&self, authority: &Pubkey, root_authority: &Pubkey) -> bool {
+        !bool::from(self.is_fill_permissioned)
+            || authority == root_authority
+            || (self.manager != Pubkey::default() && *authority == self.manager)
+    }
 }
 
 /// Reward vault
-#[derive(Debug, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq, Eq, Clone)]
+#[repr(C)]
+#[derive(
+    Debug,
+    BorshDeserialize,
+    BorshSerialize,
+    BorshSchema,
+    PartialEq,
+    Eq,
+    Clone,
+    Copy,
+    Default,
+    Pod,
+    Zeroable,
+)]
 pub struct RewardTier {
     /// Reward ratio of deposit currency
     pub ratio_base: u64,
@@ -233,3 +1270,77 @@ impl RewardTier {
     /// LEN
     pub const LEN: usize = 8 + 8 + 8;
 }
+
+/// Reward period and tiers of a vault before a rate change
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct RateCheckpoint {
+    /// Time the rates were replaced at, zero if the slot is empty
+    pub changed_at: u64,
+    /// Reward period before the change
+    pub reward_period_sec: u32,
+    /// Number of reward tiers before the change
+    pub tiers_len: u8,
+    /// Padding
+    pub _padding: [u8; 3],
+}
+
+impl RateCheckpoint {
+    /// LEN
+    pub const LEN: usize = 8 + 4 + 1 + 3;
+}
+
+/// Reward vault along with its current reward tiers and rate history
+#[derive(Clone, Copy)]
+pub struct VaultRates<'a> {
+    /// Reward vault
+    pub vault: &'a RewardVault,
+    /// Current reward tiers
+    pub tiers: &'a [RewardTier],
+    checkpoints: &'a [RateCheckpoint],
+    checkpoint_tiers: &'a [RewardTier],
+    tiers_capacity: usize,
+}
+
+impl<'a> VaultRates<'a> {
+    /// Vault rates without rate history
+    pub fn new(vault: &'a RewardVault, tiers: &'a [RewardTier]) -> VaultRates<'a> {
+        VaultRates {
+            vault,
+            tiers,
+            checkpoints: &[],
+            checkpoint_tiers: &[],
+            tiers_capacity: tiers.len(),
+        }
+    }
+
+    /// First rate change after the timestamp and before the end
+    pub fn next_change(&self, timestamp: u64, end: u64) -> Option<u64> {
+        self.checkpoints
+            .iter()
+            .map(|c| c.changed_at)
+            .filter(|changed_at| *changed_at > timestamp && *changed_at < end)
+            .min()
+    }
+
+    /// Reward period and tiers in effect up to the timestamp
+    pub fn rates_until(&self, timestamp: u64) -> (u32, &'a [RewardTier]) {
+        let checkpoint = self
+            .checkpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.changed_at >= timestamp)
+            .min_by_key(|(_, c)| c.changed_at);
+
+        match checkpoint {
+            Some((slot, c)) => {
+                let start = slot * self.tiers_capacity;
+                (
+                    c.reward_period_sec,
+                    &self.checkpoint_tiers[start..start + c.tiers_len as usize],
+                )
+            }
+            None => (self.vault.reward_period_sec, self.tiers),
+        }
+    }
+}
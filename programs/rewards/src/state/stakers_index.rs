@@ -0,0 +1,207 @@
+use super::{load_records, load_records_mut, AccountType};
+use crate::state::ZeroCopy;
+use bytemuck::{Pod, Zeroable};
+use everlend_utils::EverlendError;
+use solana_program::entrypoint::ProgramResult;
+use solana_program::program_error::ProgramError;
+use solana_program::pubkey::Pubkey;
+
+/// Stakers index of a competitive reward pool
+///
+/// The account data holds this header followed by `capacity` staker entries
+/// kept as a min-heap by staked amount, so the smallest position is always first.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct StakersIndex {
+    /// Account type - StakersIndex
+    pub account_type: u8,
+    /// Saved bump for stakers index account
+    pub bump: u8,
+    /// Padding
+    pub _padding: [u8; 6],
+    /// Reward pool address
+    pub reward_pool: Pubkey,
+    /// Number of initialized entries
+    pub len: u64,
+    /// Max number of entries
+    pub capacity: u64,
+}
+
+impl ZeroCopy for StakersIndex {
+    const ACCOUNT_TYPE: AccountType = AccountType::StakersIndex;
+}
+
+impl StakersIndex {
+    /// Initialize a Stakers Index
+    pub fn initialize(reward_pool: Pubkey, bump: u8, capacity: u64) -> StakersIndex {
+        StakersIndex {
+            account_type: AccountType::StakersIndex as u8,
+            bump,
+            reward_pool,
+            capacity,
+            ..Zeroable::zeroed()
+        }
+    }
+
+    /// Account data length for the given number of entries
+    pub fn account_len(capacity: u64) -> usize {
+        Self::LEN + capacity as usize * StakerEntry::LEN
+    }
+
+    /// Borrows stakers index with its entries in place
+    pub fn load_account(data: &[u8]) -> Result<StakersIndexRef<'_>, ProgramError> {
+        let index = Self::load(data)?;
+
+        Ok(StakersIndexRef {
+            index,
+            entries: load_records(&data[Self::LEN..], index.len as usize)?,
+        })
+    }
+
+    /// Mutably borrows stakers index with its entries in place
+    pub fn load_account_mut(data: &mut [u8]) -> Result<StakersIndexMut<'_>, ProgramError> {
+        if data.len() < Self::LEN {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+
+        let (index, records) = data.split_at_mut(Self::LEN);
+        let index = Self::load_mut(index)?;
+        let entries = load_records_mut(records, index.capacity as usize)?;
+
+        Ok(StakersIndexMut { index, entries })
+    }
+}
+
+/// Stakers index with its entries
+#[derive(Clone, Copy)]
+pub struct StakersIndexRef<'a> {
+    /// Stakers index header
+    pub index: &'a StakersIndex,
+    entries: &'a [StakerEntry],
+}
+
+impl<'a> StakersIndexRef<'a> {
+    /// Initialized entries in heap order
+    pub fn entries(&self) -> &'a [StakerEntry] {
+        self.entries
+    }
+
+    /// Smallest position
+    pub fn min(&self) -> Option<&'a StakerEntry> {
+        self.entries.first()
+    }
+}
+
+/// Mutable stakers index with its entries
+pub struct StakersIndexMut<'a> {
+    /// Stakers index header
+    pub index: &'a mut StakersIndex,
+    entries: &'a mut [StakerEntry],
+}
+
+impl StakersIndexMut<'_> {
+    /// Reborrows as read only
+    pub fn to_ref(&self) -> StakersIndexRef<'_> {
+        StakersIndexRef {
+            index: self.index,
+            entries: &self.entries[..self.index.len as usize],
+        }
+    }
+
+    /// Inserts a position or updates its amount
+    pub fn upsert(&mut self, mining: Pubkey, amount: u64) -> ProgramResult {
+        let len = self.index.len as usize;
+        match self.position(&mining) {
+            Some(i) => {
+                let prev_amount = self.entries[i].amount;
+                self.entries[i].amount = amount;
+                if amount < prev_amount {
+                    self.sift_up(i);
+                } else {
+                    self.sift_down(i);
+                }
+            }
+            None => {
+                if len == self.entries.len() {
+                    return Err(EverlendError::PoolIsFull.into());
+                }
+
+                self.entries[len] = StakerEntry { mining, amount };
+                self.index.len += 1;
+                self.sift_up(len);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a position, does nothing if it's not indexed
+    pub fn remove(&mut self, mining: &Pubkey) {
+        let i = match self.position(mining) {
+            Some(i) => i,
+            None => return,
+        };
+
+        let last = self.index.len as usize - 1;
+        self.entries.swap(i, last);
+        self.entries[last] = StakerEntry::default();
+        self.index.len -= 1;
+
+        if i < last {
+            self.sift_down(i);
+            self.sift_up(i);
+        }
+    }
+
+    fn position(&self, mining: &Pubkey) -> Option<usize> {
+        self.entries[..self.index.len as usize]
+            .iter()
+            .position(|e| e.mining == *mining)
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.entries[parent].amount <= self.entries[i].amount {
+                break;
+            }
+
+            self.entries.swap(parent, i);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        let len = self.index.len as usize;
+        loop {
+            let mut smallest = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < len && self.entries[child].amount < self.entries[smallest].amount {
+                    smallest = child;
+                }
+            }
+
+            if smallest == i {
+                break;
+            }
+
+            self.entries.swap(smallest, i);
+            i = smallest;
+        }
+    }
+}
+
+/// Staker entry
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct StakerEntry {
+    /// Mining account address
+    pub mining: Pubkey,
+    /// Staked amount
+    pub amount: u64,
+}
+
+impl StakerEntry {
+    /// LEN
+    pub const LEN: usize = std::mem::size_of::<StakerEntry>();
+}
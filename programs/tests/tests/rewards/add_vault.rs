@@ -1,9 +1,8 @@
 use crate::utils::*;
 use everlend_rewards::state::{RewardPool, RewardTier};
-use solana_program::program_pack::Pack;
 use solana_program_test::*;
 use solana_sdk::{signature::Keypair, signer::Signer};
-use std::{borrow::Borrow, vec};
+use std::vec;
 
 use super::TestRewards;
 
@@ -36,13 +35,14 @@ async fn success() {
         )
         .await;
 
-    let reward_pool_account =
-        RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
-    let vault = reward_pool_account.vaults.get(0).unwrap();
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    let vault = reward_pool_account.vaults().get(0).unwrap();
+    let tiers = reward_pool_account.vault_tiers(0);
 
     assert_eq!(vault.reward_mint, reward_mint.pubkey());
-    assert_eq!(vault.reward_tiers[0].ratio_base, 125);
-    assert_eq!(vault.reward_tiers[0].ratio_quote, 36);
+    assert_eq!(tiers[0].ratio_base, 125);
+    assert_eq!(tiers[0].ratio_quote, 36);
     assert_eq!(vault.reward_period_sec, 60);
     assert_eq!(vault.is_enabled, true);
     assert_eq!(vault.enabled_at, clock.unix_timestamp as u64);
@@ -56,16 +56,19 @@ async fn success() {
             None,
             Some(false),
             None,
+            None,
+            None,
         )
         .await;
 
-    let reward_pool_account =
-        RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
-    let vault = reward_pool_account.vaults.get(0).unwrap();
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    let vault = reward_pool_account.vaults().get(0).unwrap();
+    let tiers = reward_pool_account.vault_tiers(0);
 
     assert_eq!(vault.reward_mint, reward_mint.pubkey());
-    assert_eq!(vault.reward_tiers[0].ratio_base, 125);
-    assert_eq!(vault.reward_tiers[0].ratio_quote, 36);
+    assert_eq!(tiers[0].ratio_base, 125);
+    assert_eq!(tiers[0].ratio_quote, 36);
     assert_eq!(vault.reward_period_sec, 60);
     assert_eq!(vault.is_enabled, false);
     assert_eq!(vault.enabled_at, clock.unix_timestamp as u64);
@@ -90,20 +93,23 @@ async fn success() {
                     reward_max_amount_per_period: 6,
                 },
             ]),
+            None,
+            None,
         )
         .await;
 
-    let reward_pool_account =
-        RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
-    let vault = reward_pool_account.vaults.get(0).unwrap();
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    let vault = reward_pool_account.vaults().get(0).unwrap();
+    let tiers = reward_pool_account.vault_tiers(0);
 
     assert_eq!(vault.reward_mint, reward_mint.pubkey());
-    assert_eq!(vault.reward_tiers[0].ratio_base, 1);
-    assert_eq!(vault.reward_tiers[0].ratio_quote, 2);
-    assert_eq!(vault.reward_tiers[0].reward_max_amount_per_period, 3);
-    assert_eq!(vault.reward_tiers[1].ratio_base, 4);
-    assert_eq!(vault.reward_tiers[1].ratio_quote, 5);
-    assert_eq!(vault.reward_tiers[1].reward_max_amount_per_period, 6);
+    assert_eq!(tiers[0].ratio_base, 1);
+    assert_eq!(tiers[0].ratio_quote, 2);
+    assert_eq!(tiers[0].reward_max_amount_per_period, 3);
+    assert_eq!(tiers[1].ratio_base, 4);
+    assert_eq!(tiers[1].ratio_quote, 5);
+    assert_eq!(tiers[1].reward_max_amount_per_period, 6);
     assert_eq!(vault.reward_period_sec, 120);
     assert_eq!(vault.is_enabled, true);
     assert_eq!(vault.enabled_at, clock.unix_timestamp as u64);
@@ -0,0 +1,352 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    // tier 1 costs 300, tier 2 costs 600, both last for a reward period
+    let burn_mint = Keypair::new();
+    create_mint(&mut context, &burn_mint).await.unwrap();
+    test_reward_pool
+        .set_tier_prices(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            vec![0, 300, 600],
+            reward_period as u64,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    let burn_token_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &burn_token_account,
+        &burn_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    mint_tokens(
+        &mut context,
+        &burn_mint.pubkey(),
+        &burn_token_account.pubkey(),
+        1_000,
+    )
+    .await
+    .unwrap();
+
+    // tier 3 is not for sale
+    assert!(test_reward_pool
+        .buy_tier(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            &burn_token_account.pubkey(),
+            &token_holder.owner,
+            3,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .buy_tier(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            &burn_token_account.pubkey(),
+            &token_holder.owner,
+            1,
+        )
+        .await
+        .unwrap();
+    test_reward_pool
+        .buy_tier(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            &burn_token_account.pubkey(),
+            &token_holder.owner,
+            2,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &burn_token_account.pubkey()).await,
+        100
+    );
+    assert_eq!(
+        get_mint_data(&mut context, &burn_mint.pubkey())
+            .await
+            .supply,
+        100
+    );
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.reward_tier, 2);
+    assert_eq!(
+        mining.tier_expires_at,
+        clock.unix_timestamp as u64 + reward_period as u64
+    );
+
+    // a lower tier can't be bought
+    context.warp_to_slot(5).unwrap();
+    assert!(test_reward_pool
+        .buy_tier(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            &burn_token_account.pubkey(),
+            &token_holder.owner,
+            1,
+        )
+        .await
+        .is_err());
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // the bought tier has expired, the position falls back to the base tier
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.reward_tier, 0);
+    assert_eq!(mining.tier_expires_at, 0);
+}
+
+#[tokio::test]
+async fn expires_to_permanent_tier() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let burn_mint = Keypair::new();
+    create_mint(&mut context, &burn_mint).await.unwrap();
+    test_reward_pool
+        .set_tier_prices(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            vec![0, 300, 600],
+            reward_period as u64,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    // the admin grants tier 1 for good
+    test_reward_pool
+        .upgrade_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.owner.pubkey(),
+            1,
+        )
+        .await;
+
+    let burn_token_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &burn_token_account,
+        &burn_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    mint_tokens(
+        &mut context,
+        &burn_mint.pubkey(),
+        &burn_token_account.pubkey(),
+        600,
+    )
+    .await
+    .unwrap();
+
+    test_reward_pool
+        .buy_tier(
+            &mut context,
+            &pool_mint.pubkey(),
+            &burn_mint.pubkey(),
+            &burn_token_account.pubkey(),
+            &token_holder.owner,
+            2,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.reward_tier, 2);
+    assert_eq!(mining.base_tier, 1);
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // the bought tier has expired, the position keeps the tier granted by the admin
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.reward_tier, 1);
+    assert_eq!(mining.tier_expires_at, 0);
+}
@@ -1,5 +1,5 @@
 use crate::utils::*;
-use everlend_rewards::state::{Mining, RewardPool, RewardTier, RewardVault};
+use everlend_rewards::state::{Mining, RewardPool, RewardTier, RewardVault, VaultRates, ZeroCopy};
 use solana_program::program_pack::Pack;
 use solana_program_test::*;
 use solana_sdk::sysvar::clock;
@@ -131,94 +131,951 @@ async fn success() {
 
     assert_eq!(user_reward.amount, exp_reward_amount);
 
-    let mining = Mining::unpack(
-        &get_account(&mut context, &mining_account)
+    let mining_account = get_account(&mut context, &mining_account).await;
+    let mining = Mining::load_account(&mining_account.data).unwrap();
+
+    assert_eq!(mining.indexes()[0].rewards, 0);
+    assert_eq!(mining.indexes()[0].claimed_total_rewards, exp_reward_amount);
+
+    let vault_acc = Account::unpack(get_account(&mut context, &vault).await.data.borrow()).unwrap();
+    assert_eq!(vault_acc.amount, reward_amount - exp_reward_amount);
+
+    let vault_acc = get_account(&mut context, &reward_pool_pubkey).await;
+    let vault_acc = RewardPool::load_account(&vault_acc.data).unwrap();
+    assert_eq!(
+        vault_acc.vaults()[0].claimed_total_amount,
+        exp_reward_amount
+    );
+
+    println!("vault: {:?}", vault_acc.pool);
+}
+
+#[tokio::test]
+async fn success_with_mint_vault() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+
+    let (reward_pool_pubkey, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    // the reward pool has to be the mint authority
+    assert!(test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            500,
+            300
+        )
+        .await
+        .is_err());
+
+    set_mint_authority(&mut context, &reward_mint.pubkey(), &reward_pool_pubkey)
+        .await
+        .unwrap();
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            500,
+            300,
+        )
+        .await
+        .unwrap();
+
+    let deposit_amount = 50_000;
+    let token_holder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &pool_mint.pubkey(),
+            10_000_000_000,
+            deposit_amount,
+        )
+        .await;
+
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    // 500 accrued, the period cap is 300
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        300
+    );
+
+    // 700 accrued, 200 left under the total cap
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        500
+    );
+
+    let mining_account = get_account(&mut context, &mining_account).await;
+    let mining = Mining::load_account(&mining_account.data).unwrap();
+    assert_eq!(mining.indexes()[0].rewards, 500);
+    assert_eq!(mining.indexes()[0].claimed_total_rewards, 500);
+
+    let reward_pool_account = get_account(&mut context, &reward_pool_pubkey).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.vaults()[0].claimed_total_amount, 500);
+}
+
+#[tokio::test]
+async fn success_with_converted_mint_vault() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+
+    let (reward_pool_pubkey, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let rewarder = test_reward_pool
+        .create_token_holder(&mut context, &reward_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            1000,
+        )
+        .await
+        .unwrap();
+
+    let deposit_amount = 50_000;
+    let token_holder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &pool_mint.pubkey(),
+            10_000_000_000,
+            deposit_amount,
+        )
+        .await;
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        500
+    );
+
+    // transferred claims don't count against the mint cap
+    set_mint_authority(&mut context, &reward_mint.pubkey(), &reward_pool_pubkey)
+        .await
+        .unwrap();
+    test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            500,
+            0,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        1000
+    );
+
+    let reward_pool_account = get_account(&mut context, &reward_pool_pubkey).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.vaults()[0].claimed_total_amount, 1000);
+    assert_eq!(reward_pool_account.vaults()[0].total_minted_amount, 500);
+}
+
+#[tokio::test]
+async fn success_with_native_vault() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    let vault = test_reward_pool
+        .add_native_vault(&mut context, &pool_mint.pubkey(), 1, 100, reward_period)
+        .await
+        .unwrap();
+    let vault_rent = get_account(&mut context, &vault).await.lamports;
+
+    let reward_amount = 10_000_000;
+    let funder = Keypair::new();
+    transfer_sol(&mut context, &funder.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+    test_reward_pool
+        .fill_native_vault(&mut context, &funder, &pool_mint.pubkey(), reward_amount)
+        .await
+        .unwrap();
+
+    let deposit_amount = 50_000;
+    let exp_reward_amount = 5_000_000;
+    let token_holder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &pool_mint.pubkey(),
+            10_000_000_000,
+            deposit_amount,
+        )
+        .await;
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    // lamports are paid without a wrapped SOL account
+    let recipient = Keypair::new();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &spl_token::native_mint::id(),
+            &recipient.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_account(&mut context, &recipient.pubkey())
             .await
-            .data
-            .borrow(),
+            .lamports,
+        exp_reward_amount
+    );
+    assert_eq!(
+        get_account(&mut context, &vault).await.lamports,
+        vault_rent + reward_amount - exp_reward_amount
+    );
+}
+
+#[tokio::test]
+async fn reward_calculation() {
+    let base = 100_000_000;
+    let quote = 1000;
+    let period = 60_u32;
+
+    check_mining_maths(base, quote, period, period, 0, 100_000_000, 1000);
+    check_mining_maths(base, quote, period, period, 20, 100_000_000, 20);
+    check_mining_maths(base, quote, period, period * 10, 20, 10_000_000_000, 200);
+
+    check_mining_maths(base, quote, period, period / 2, 0, 100_000_000, 0);
+    check_mining_maths(base, quote, period, period * 5, 0, 25_000_000, 1250);
+
+    let base = 1_000;
+    let quote = 1;
+
+    check_mining_maths(base, quote, period, period, 0, 1_250, 1);
+    check_mining_maths(base, quote, period, period * 2, 0, 1_250, 2);
+    check_mining_maths(base, quote, period, period * 4, 0, 1_250, 5); // we round to the end of period
+}
+
+fn check_mining_maths(
+    base: u64,
+    quote: u64,
+    period: u32,
+    add_time: u32,
+    max_amount: u64,
+    deposit: u64,
+    reward: u64,
+) {
+    let reward_pool = Keypair::new();
+    let owner = Keypair::new();
+
+    let current_timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_secs();
+
+    let vault = RewardVault {
+        vault_token_account_bump: 0,
+        reward_mint: Keypair::new().pubkey(),
+
+        reward_period_sec: period,
+        is_enabled: true.into(),
+        enabled_at: current_timestamp,
+        claimed_total_amount: 0,
+        ..Default::default()
+    };
+    let tiers = [RewardTier {
+        ratio_base: base,
+        ratio_quote: quote,
+        reward_max_amount_per_period: max_amount,
+    }];
+
+    let mut mining_data = vec![0; Mining::account_len(1)];
+    let mining = Mining::load_unchecked_mut(&mut mining_data).unwrap();
+    *mining = Mining::initialize(reward_pool.pubkey(), 0, owner.pubkey());
+    mining.amount = deposit;
+    mining.rewards_calculated_at = current_timestamp;
+
+    let mut mining = Mining::load_account_mut(&mut mining_data).unwrap();
+    let new_timestamp = current_timestamp + add_time as u64;
+    mining
+        .refresh_rewards(
+            vec![VaultRates::new(&vault, &tiers)].into_iter(),
+            new_timestamp,
+        )
+        .unwrap();
+
+    assert_eq!(mining.to_ref().indexes()[0].rewards, reward);
+    assert_eq!(mining.mining.rewards_calculated_at, new_timestamp);
+}
+
+#[tokio::test]
+async fn success_with_claim_fee() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+
+    let (reward_pool_pubkey, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let treasury = Keypair::new();
+    let treasury_reward_account =
+        create_associated_token_account(&mut context, &treasury.pubkey(), &reward_mint.pubkey())
+            .await
+            .unwrap();
+    test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &pool_mint.pubkey(),
+            &treasury.pubkey(),
+            0,
+            0,
+            1000,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let deposit_amount = 50_000;
+    let token_holder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &pool_mint.pubkey(),
+            10_000_000_000,
+            deposit_amount,
+        )
+        .await;
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
     )
+    .await
     .unwrap();
 
-    assert_eq!(mining.indexes[0].rewards, 0);
-    assert_eq!(mining.indexes[0].claimed_total_rewards, exp_reward_amount);
+    test_reward_pool
+        .claim_with_fee(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+            &treasury_reward_account,
+        )
+        .await
+        .unwrap();
+
+    // 10% of 500 rewards goes to the treasury
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        450
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &treasury_reward_account).await,
+        50
+    );
 
-    let vault_acc = Account::unpack(get_account(&mut context, &vault).await.data.borrow()).unwrap();
-    assert_eq!(vault_acc.amount, reward_amount - exp_reward_amount);
+    let reward_pool_account = get_account(&mut context, &reward_pool_pubkey).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.vaults()[0].claimed_total_amount, 500);
+    assert_eq!(reward_pool_account.vaults()[0].total_claim_fees, 50);
+}
+
+#[tokio::test]
+async fn frequent_claims_earn_as_much_as_passive_staking() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
 
-    let vault_acc = RewardPool::unpack(
-        get_account(&mut context, &reward_pool_pubkey)
+    // both positions earn 500.5 per period
+    let deposit_amount = 50_050;
+    let mut reward_accounts = vec![];
+    let mut owners = vec![];
+    for _ in 0..2 {
+        let token_holder = test_reward_pool
+            .create_token_holder(
+                &mut context,
+                &pool_mint.pubkey(),
+                10_000_000_000,
+                deposit_amount,
+            )
+            .await;
+        test_reward_pool
+            .deposit_mining(
+                &mut context,
+                &pool_mint.pubkey(),
+                &token_holder.token_account,
+                &token_holder.owner,
+                deposit_amount,
+            )
             .await
-            .data
-            .borrow(),
-    )
-    .unwrap();
-    assert_eq!(vault_acc.vaults[0].claimed_total_amount, exp_reward_amount);
+            .unwrap();
+
+        let user_reward_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_reward_account,
+            &reward_mint.pubkey(),
+            &token_holder.owner.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+
+        reward_accounts.push(user_reward_account.pubkey());
+        owners.push(token_holder.owner);
+    }
+
+    // the frequent claimer claims in the middle of the second period
+    clock.unix_timestamp += (reward_period + reward_period / 2) as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
 
-    println!("vault: {:?}", vault_acc);
+    test_reward_pool
+        .claim(
+            &mut context,
+            &owners[0],
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &reward_accounts[0],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &reward_accounts[0]).await,
+        500
+    );
+
+    clock.unix_timestamp += (2 * reward_period + reward_period / 2) as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    for (owner, reward_account) in owners.iter().zip(reward_accounts.iter()) {
+        test_reward_pool
+            .claim(
+                &mut context,
+                owner,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                reward_account,
+            )
+            .await
+            .unwrap();
+
+        // neither the unelapsed half period nor the half reward unit is lost
+        assert_eq!(get_token_balance(&mut context, reward_account).await, 2_002);
+    }
 }
 
 #[tokio::test]
-async fn reward_calculation() {
-    let base = 100_000_000;
-    let quote = 1000;
-    let period = 60_u32;
+async fn success_with_epochs() {
+    let mut context = program_test().start_with_context().await;
 
-    check_mining_maths(base, quote, period, period, 0, 100_000_000, 1000);
-    check_mining_maths(base, quote, period, period, 20, 100_000_000, 20);
-    check_mining_maths(base, quote, period, period * 10, 20, 10_000_000_000, 200);
+    let test_reward_pool = TestRewards::new(&mut context).await;
 
-    check_mining_maths(base, quote, period, period / 2, 0, 100_000_000, 0);
-    check_mining_maths(base, quote, period, period * 5, 0, 25_000_000, 1250);
+    let pool_mint = Keypair::new();
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
 
-    let base = 1_000;
-    let quote = 1;
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+    let reward_period = 3600;
+    let epoch_anchor = clock.unix_timestamp as u64 - reward_period as u64 / 2;
 
-    check_mining_maths(base, quote, period, period, 0, 1_250, 1);
-    check_mining_maths(base, quote, period, period * 2, 0, 1_250, 2);
-    check_mining_maths(base, quote, period, period * 4, 0, 1_250, 5); // we round to the end of period
+    // the first vault pro-rates partial epochs, the second one excludes them
+    let mut reward_mints = vec![];
+    for is_partial_epoch_excluded in [false, true] {
+        let reward_mint = Keypair::new();
+        create_mint(&mut context, &reward_mint).await.unwrap();
+        test_reward_pool
+            .add_vault(
+                &mut context,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                100,
+                1,
+                reward_period,
+            )
+            .await;
+        test_reward_pool
+            .set_vault_epochs(
+                &mut context,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                epoch_anchor,
+                is_partial_epoch_excluded,
+            )
+            .await
+            .unwrap();
+
+        let reward_amount = 1_000_000;
+        let rewarder = test_reward_pool
+            .create_token_holder(
+                &mut context,
+                &reward_mint.pubkey(),
+                10_000_000_000,
+                reward_amount,
+            )
+            .await;
+        test_reward_pool
+            .fill_vault(
+                &mut context,
+                &rewarder,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                reward_amount,
+            )
+            .await
+            .unwrap();
+
+        reward_mints.push(reward_mint.pubkey());
+    }
+
+    // staked in the middle of the first epoch
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += (reward_period / 2 + reward_period + 100) as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let mut balances = vec![];
+    for reward_mint in reward_mints.iter() {
+        let user_reward_account = Keypair::new();
+        create_token_account(
+            &mut context,
+            &user_reward_account,
+            reward_mint,
+            &token_holder.owner.pubkey(),
+            0,
+        )
+        .await
+        .unwrap();
+        test_reward_pool
+            .claim(
+                &mut context,
+                &token_holder.owner,
+                &pool_mint.pubkey(),
+                reward_mint,
+                &user_reward_account.pubkey(),
+            )
+            .await
+            .unwrap();
+
+        balances.push(get_token_balance(&mut context, &user_reward_account.pubkey()).await);
+    }
+
+    // half of the first epoch and the whole second one, the running one isn't over
+    assert_eq!(balances, vec![750, 500]);
 }
 
-fn check_mining_maths(
-    base: u64,
-    quote: u64,
-    period: u32,
-    add_time: u32,
-    max_amount: u64,
-    deposit: u64,
-    reward: u64,
-) {
-    let reward_pool = Keypair::new();
-    let owner = Keypair::new();
+#[tokio::test]
+async fn success_after_rate_change() {
+    let mut context = program_test().start_with_context().await;
 
-    let current_timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("Time went backwards")
-        .as_secs();
+    let test_reward_pool = TestRewards::new(&mut context).await;
 
-    let vault = RewardVault {
-        vault_token_account_bump: 0,
-        reward_mint: Keypair::new().pubkey(),
+    let pool_mint = Keypair::new();
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
 
-        reward_period_sec: period,
-        reward_tiers: vec![RewardTier {
-            ratio_base: base,
-            ratio_quote: quote,
-            reward_max_amount_per_period: max_amount,
-        }],
-        is_enabled: true,
-        enabled_at: current_timestamp,
-        claimed_total_amount: 0,
-    };
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
 
-    let mut mining = Mining::initialize(reward_pool.pubkey(), 0, owner.pubkey());
-    mining.amount = deposit;
-    mining.rewards_calculated_at = current_timestamp;
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
 
-    let new_timestamp = current_timestamp + add_time as u64;
-    mining
-        .refresh_rewards(vec![vault].iter(), new_timestamp)
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    // the rate doubles after two periods
+    clock.unix_timestamp += 2 * reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .update_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            None,
+            None,
+            Some(vec![RewardTier {
+                ratio_base: 50,
+                ratio_quote: 1,
+                reward_max_amount_per_period: 0,
+            }]),
+            None,
+            None,
+        )
+        .await;
+
+    clock.unix_timestamp += 2 * reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
         .unwrap();
 
-    assert_eq!(mining.indexes[0].rewards, reward);
-    assert_eq!(mining.rewards_calculated_at, new_timestamp);
+    // the old rate applies up to the change
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        2 * 500 + 2 * 1_000
+    );
 }
@@ -0,0 +1,494 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::find_vault_spl_token_account;
+use solana_program::program_option::COption;
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, reward_pool_spl) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 3600, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            3600,
+        )
+        .await;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            1_000_000,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            1_000_000,
+        )
+        .await
+        .unwrap();
+
+    let leaver = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let stayer = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 500)
+        .await;
+    for holder in [&leaver, &stayer] {
+        let amount = get_token_balance(&mut context, &holder.token_account).await;
+        test_reward_pool
+            .deposit_mining(
+                &mut context,
+                &liquidity_mint.pubkey(),
+                &holder.token_account,
+                &holder.owner,
+                amount,
+            )
+            .await
+            .unwrap();
+    }
+
+    let grace_period_sec = 3600;
+    test_reward_pool
+        .start_pool_closing(&mut context, &liquidity_mint.pubkey(), grace_period_sec)
+        .await
+        .unwrap();
+
+    // stakers exit during the grace period regardless of the lock
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &leaver.token_account,
+            &leaver.owner,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &leaver.token_account).await,
+        1000
+    );
+
+    let stayer_token_account = create_associated_token_account(
+        &mut context,
+        &stayer.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    assert!(test_reward_pool
+        .force_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &stayer.owner.pubkey(),
+            &stayer_token_account,
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .is_err());
+
+    create_associated_token_account(
+        &mut context,
+        &test_reward_pool.root_authority.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let stayer_reward_account = create_associated_token_account(
+        &mut context,
+        &stayer.owner.pubkey(),
+        &reward_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let authority_reward_account = create_associated_token_account(
+        &mut context,
+        &test_reward_pool.root_authority.pubkey(),
+        &reward_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    clock.unix_timestamp += grace_period_sec as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    // the remaining position has to be unstaked first
+    assert!(test_reward_pool
+        .close_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &[reward_mint.pubkey()]
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .force_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &stayer.owner.pubkey(),
+            &stayer_token_account,
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &stayer_token_account).await,
+        500
+    );
+    // accrued rewards are paid with the stake
+    assert_eq!(
+        get_token_balance(&mut context, &stayer_reward_account).await,
+        5
+    );
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .close_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    // undistributed rewards go back to the authority
+    assert_eq!(
+        get_token_balance(&mut context, &authority_reward_account).await,
+        999_995
+    );
+
+    let (vault, _) =
+        find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, &reward_mint.pubkey());
+    for account in [reward_pool, reward_pool_spl, vault] {
+        assert!(context
+            .banks_client
+            .get_account(account)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[tokio::test]
+async fn mint_vault() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+    set_mint_authority(&mut context, &reward_mint.pubkey(), &reward_pool)
+        .await
+        .unwrap();
+    test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            500,
+            300,
+        )
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            10_000_000_000,
+            50_000,
+        )
+        .await;
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .start_pool_closing(&mut context, &liquidity_mint.pubkey(), reward_period as u64)
+        .await
+        .unwrap();
+
+    let user_token_account = create_associated_token_account(
+        &mut context,
+        &user.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let user_reward_account =
+        create_associated_token_account(&mut context, &user.owner.pubkey(), &reward_mint.pubkey())
+            .await
+            .unwrap();
+    let authority = test_reward_pool.root_authority.pubkey();
+    create_associated_token_account(&mut context, &authority, &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+    create_associated_token_account(&mut context, &authority, &reward_mint.pubkey())
+        .await
+        .unwrap();
+
+    // 500 accrued, the period cap is 300
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .force_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            &user_token_account,
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_token_account).await,
+        50_000
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account).await,
+        300
+    );
+
+    test_reward_pool
+        .close_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    // minting goes back to the root authority
+    let mint = get_mint_data(&mut context, &reward_mint.pubkey()).await;
+    assert_eq!(mint.mint_authority, COption::Some(authority));
+}
+
+#[tokio::test]
+async fn empty_and_underfunded_minings() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 60, 0)
+        .await
+        .unwrap();
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(1000),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+    let rewarder = test_reward_pool
+        .create_token_holder(&mut context, &reward_mint.pubkey(), 10_000_000_000, 100)
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+        )
+        .await
+        .unwrap();
+
+    let referrer = Keypair::new();
+    let user = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            10_000_000_000,
+            50_000,
+        )
+        .await;
+    test_reward_pool
+        .deposit_mining_referred(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            &referrer.pubkey(),
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    // stakers get at least the pool lock to exit
+    for grace_period_sec in [0, 30] {
+        assert!(test_reward_pool
+            .start_pool_closing(&mut context, &liquidity_mint.pubkey(), grace_period_sec)
+            .await
+            .is_err());
+    }
+    test_reward_pool
+        .start_pool_closing(&mut context, &liquidity_mint.pubkey(), reward_period as u64)
+        .await
+        .unwrap();
+
+    let user_token_account = create_associated_token_account(
+        &mut context,
+        &user.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let user_reward_account =
+        create_associated_token_account(&mut context, &user.owner.pubkey(), &reward_mint.pubkey())
+            .await
+            .unwrap();
+    let referrer_token_account =
+        create_associated_token_account(&mut context, &referrer.pubkey(), &liquidity_mint.pubkey())
+            .await
+            .unwrap();
+    let authority = test_reward_pool.root_authority.pubkey();
+    create_associated_token_account(&mut context, &authority, &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+    create_associated_token_account(&mut context, &authority, &reward_mint.pubkey())
+        .await
+        .unwrap();
+
+    // 500 accrued, the vault only holds 100
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .force_unstake_referred(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            &user_token_account,
+            &[reward_mint.pubkey()],
+            &referrer.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_token_account).await,
+        50_000
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account).await,
+        100
+    );
+
+    // the empty mining of the referrer still holds the settled referral rewards
+    assert!(test_reward_pool
+        .close_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .is_err());
+
+    // nothing is left in the vault and the referrer has no reward account
+    test_reward_pool
+        .force_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &referrer.pubkey(),
+            &referrer_token_account,
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .close_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+}
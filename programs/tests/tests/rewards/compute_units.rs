@@ -0,0 +1,177 @@
+use crate::utils::*;
+use everlend_rewards::{find_mining_program_address, find_vault_spl_token_account};
+use everlend_utils::find_program_address;
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use super::{TestRewards, VAULTS_CAPACITY};
+
+// Compute units are only metered for the BPF build, run with `cargo test-bpf`
+const MAX_DEPOSIT_UNITS: u64 = 40_000;
+const MAX_CLAIM_UNITS: u64 = 40_000;
+const MAX_WITHDRAW_UNITS: u64 = 40_000;
+
+#[tokio::test]
+#[cfg_attr(not(feature = "test-bpf"), ignore)]
+async fn within_budget() {
+    let mut context = bpf_program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+    let (reward_pool, reward_pool_spl) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_period = 60;
+    let reward_amount = 1_000_000;
+    let mut reward_mints = vec![];
+    for _ in 0..VAULTS_CAPACITY {
+        let reward_mint = Keypair::new();
+        create_mint(&mut context, &reward_mint).await.unwrap();
+
+        test_reward_pool
+            .add_vault(
+                &mut context,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                100,
+                1,
+                reward_period,
+            )
+            .await;
+
+        let rewarder = test_reward_pool
+            .create_token_holder(
+                &mut context,
+                &reward_mint.pubkey(),
+                10_000_000_000,
+                reward_amount,
+            )
+            .await;
+
+        test_reward_pool
+            .fill_vault(
+                &mut context,
+                &rewarder,
+                &pool_mint.pubkey(),
+                &reward_mint.pubkey(),
+                reward_amount,
+            )
+            .await
+            .unwrap();
+
+        reward_mints.push(reward_mint);
+    }
+
+    let deposit_amount = 50_000;
+    let token_holder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &pool_mint.pubkey(),
+            10_000_000_000,
+            deposit_amount * 2,
+        )
+        .await;
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            deposit_amount,
+        )
+        .await
+        .unwrap();
+
+    // let rewards accrue in every vault
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let (mining, _) = find_mining_program_address(
+        &everlend_rewards::id(),
+        &token_holder.owner.pubkey(),
+        &reward_pool,
+    );
+
+    let deposit_units = get_units_consumed(
+        &mut context,
+        &[everlend_rewards::instruction::deposit_mining(
+            &everlend_rewards::id(),
+            None,
+            &reward_pool,
+            &reward_pool_spl,
+            &pool_mint.pubkey(),
+            &mining,
+            &token_holder.token_account,
+            &token_holder.owner.pubkey(),
+            deposit_amount,
+        )],
+        &[&token_holder.owner],
+    )
+    .await;
+
+    let reward_mint = reward_mints.last().unwrap().pubkey();
+    let (vault, _) =
+        find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, &reward_mint);
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint,
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+
+    let claim_units = get_units_consumed(
+        &mut context,
+        &[everlend_rewards::instruction::claim(
+            &everlend_rewards::id(),
+            &reward_pool,
+            &reward_mint,
+            &vault,
+            &mining,
+            &token_holder.owner.pubkey(),
+            &user_reward_account.pubkey(),
+        )],
+        &[&token_holder.owner],
+    )
+    .await;
+
+    let (reward_pool_authority, _) = find_program_address(&everlend_rewards::id(), &reward_pool);
+    let withdraw_units = get_units_consumed(
+        &mut context,
+        &[everlend_rewards::instruction::withdraw_mining(
+            &everlend_rewards::id(),
+            &reward_pool,
+            &reward_pool_spl,
+            &reward_pool_authority,
+            &pool_mint.pubkey(),
+            &mining,
+            &token_holder.token_account,
+            &token_holder.owner.pubkey(),
+        )],
+        &[&token_holder.owner],
+    )
+    .await;
+
+    assert!(
+        deposit_units <= MAX_DEPOSIT_UNITS,
+        "DepositMining: {} CU",
+        deposit_units
+    );
+    assert!(claim_units <= MAX_CLAIM_UNITS, "Claim: {} CU", claim_units);
+    assert!(
+        withdraw_units <= MAX_WITHDRAW_UNITS,
+        "WithdrawMining: {} CU",
+        withdraw_units
+    );
+}
@@ -0,0 +1,231 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 60, 0)
+        .await
+        .unwrap();
+
+    let funder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let beneficiary = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 0)
+        .await;
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    // funders can't enforce locks until the admin allows them
+    assert!(test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &funder.token_account,
+            &funder.owner,
+            &beneficiary.owner.pubkey(),
+            600,
+            Some(3600),
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(3600),
+        )
+        .await
+        .unwrap();
+
+    // the enforced lock can't be longer than the max funder lock
+    assert!(test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &funder.token_account,
+            &funder.owner,
+            &beneficiary.owner.pubkey(),
+            600,
+            Some(3601),
+        )
+        .await
+        .is_err());
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let mining = test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &funder.token_account,
+            &funder.owner,
+            &beneficiary.owner.pubkey(),
+            600,
+            Some(3600),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &funder.token_account).await,
+        400
+    );
+
+    let mining_data = get_account(&mut context, &mining).await.data;
+    let position = Mining::load(&mining_data).unwrap();
+    assert_eq!(position.owner, beneficiary.owner.pubkey());
+    assert_eq!(position.amount, 600);
+
+    // the position of the beneficiary can't be topped up by a funder
+    assert!(test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &funder.token_account,
+            &funder.owner,
+            &beneficiary.owner.pubkey(),
+            400,
+            None,
+        )
+        .await
+        .is_err());
+
+    clock.unix_timestamp += 60;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    // the pool lock is over but the enforced one isn't
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &beneficiary.token_account,
+            &beneficiary.owner,
+        )
+        .await
+        .is_err());
+
+    clock.unix_timestamp += 3540;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &beneficiary.token_account,
+            &beneficiary.owner,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &beneficiary.token_account).await,
+        600
+    );
+}
+
+#[tokio::test]
+async fn third_party() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 60, 0)
+        .await
+        .unwrap();
+
+    let stranger = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    // a stranger can't lock the stake of the user with a dust deposit
+    assert!(test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &stranger.token_account,
+            &stranger.owner,
+            &user.owner.pubkey(),
+            1,
+            Some(60),
+        )
+        .await
+        .is_err());
+
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+    clock.unix_timestamp += 60;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.token_account).await,
+        1000
+    );
+
+    // an emptied position can't be locked beyond the pool lock either
+    assert!(test_reward_pool
+        .deposit_for(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &stranger.token_account,
+            &stranger.owner,
+            &user.owner.pubkey(),
+            1,
+            Some(u64::MAX),
+        )
+        .await
+        .is_err());
+    assert_eq!(
+        get_token_balance(&mut context, &stranger.token_account).await,
+        1000
+    );
+}
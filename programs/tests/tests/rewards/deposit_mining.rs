@@ -1,9 +1,10 @@
 use std::borrow::Borrow;
 
 use crate::{rewards::TestRewards, utils::*};
-use everlend_rewards::state::{Mining, RewardPool};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
 use solana_program_test::*;
-use solana_sdk::{program_pack::Pack, signature::Keypair, signer::Signer};
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
 
 #[tokio::test]
 async fn success() {
@@ -131,3 +132,131 @@ async fn success() {
         RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
     assert_eq!(reward_pool_account.total_stakers, 1);
 }
+
+#[tokio::test]
+async fn success_with_warmup() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    // new deposits earn half the rate for two reward periods
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &pool_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(2 * reward_period as u64),
+            Some(5000),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.warming_amount, 50_000);
+    assert_eq!(
+        mining.warm_at,
+        clock.unix_timestamp as u64 + 2 * reward_period as u64
+    );
+
+    clock.unix_timestamp += 3 * reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // two warm-up periods at half the rate and one in full
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        1_000
+    );
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.warming_amount, 0);
+    assert_eq!(mining.warm_at, 0);
+}
@@ -0,0 +1,317 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::find_position_mint_program_address;
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 2)
+        .await
+        .unwrap();
+
+    let dust_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let new_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let dust_mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &dust_holder.token_account,
+            &dust_holder.owner,
+            1,
+        )
+        .await
+        .unwrap();
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &holder.token_account,
+            &holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(50),
+            Some(500),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // the pool is full
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            100,
+        )
+        .await
+        .is_err());
+
+    let holder_ata = create_associated_token_account(
+        &mut context,
+        &holder.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    assert!(test_reward_pool
+        .evict_dust(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &holder.owner.pubkey(),
+            &holder_ata,
+        )
+        .await
+        .is_err());
+
+    let dust_holder_ata = create_associated_token_account(
+        &mut context,
+        &dust_holder.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // only the owner's associated token account can receive evicted tokens
+    assert!(test_reward_pool
+        .evict_dust(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &dust_holder.owner.pubkey(),
+            &dust_holder.token_account,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .evict_dust(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &dust_holder.owner.pubkey(),
+            &dust_holder_ata,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(get_token_balance(&mut context, &dust_holder_ata).await, 1);
+
+    let mining = Mining::unpack(&get_account(&mut context, &dust_mining).await.data).unwrap();
+    assert_eq!(mining.amount, 0);
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_stakers, 1);
+    assert_eq!(reward_pool_account.total_amount, 100);
+
+    // the freed slot is available
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    // positions are checked against the pool limits
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            401,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &dust_holder.token_account,
+            &dust_holder.owner,
+            10,
+        )
+        .await
+        .is_err());
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_stakers, 2);
+    assert_eq!(reward_pool_account.total_amount, 200);
+}
+
+#[tokio::test]
+async fn tokenized() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let seller = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let buyer = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 0)
+        .await;
+
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &seller.token_account,
+            &seller.owner,
+            10,
+        )
+        .await
+        .unwrap();
+    let seller_position_account = test_reward_pool
+        .tokenize_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &seller.owner,
+        )
+        .await
+        .unwrap();
+
+    let (position_mint, _) = find_position_mint_program_address(&everlend_rewards::id(), &mining);
+    let buyer_position_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &buyer_position_account,
+        &position_mint,
+        &buyer.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    token_transfer(
+        &mut context,
+        &seller_position_account,
+        &buyer_position_account.pubkey(),
+        &seller.owner,
+        1,
+    )
+    .await
+    .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(50),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let seller_ata = create_associated_token_account(
+        &mut context,
+        &seller.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let buyer_ata = create_associated_token_account(
+        &mut context,
+        &buyer.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // evicted tokens go to the holder of the position NFT only
+    assert!(test_reward_pool
+        .evict_dust(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &seller.owner.pubkey(),
+            &seller_ata,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .evict_dust_tokenized(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &seller_ata,
+            &seller_position_account,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .evict_dust_tokenized(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &buyer_ata,
+            &buyer_position_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(get_token_balance(&mut context, &buyer_ata).await, 10);
+
+    let position = Mining::unpack(&get_account(&mut context, &mining).await.data).unwrap();
+    assert_eq!(position.amount, 0);
+    assert!(bool::from(position.is_tokenized));
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_stakers, 0);
+    assert_eq!(reward_pool_account.total_amount, 0);
+}
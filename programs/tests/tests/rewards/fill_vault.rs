@@ -1,4 +1,6 @@
 use crate::utils::*;
+use everlend_rewards::find_funder_contribution_program_address;
+use everlend_rewards::state::{FunderContribution, RewardPool, ZeroCopy};
 use solana_program::program_pack::Pack;
 use solana_program_test::*;
 use solana_sdk::signature::Keypair;
@@ -16,7 +18,7 @@ async fn success() {
 
     let pool_mint = Keypair::new();
 
-    test_reward_pool
+    let (reward_pool, _) = test_reward_pool
         .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
         .await
         .unwrap();
@@ -64,4 +66,96 @@ async fn success() {
 
     assert_eq!(vault.amount, initial_balance / 2);
     assert_eq!(rewarder.amount, initial_balance / 2);
+
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    let vault = reward_pool_account.vaults().first().unwrap();
+    assert_eq!(vault.total_funded, initial_balance / 2);
+
+    // only the root authority and the vault manager can fill a permissioned vault
+    test_reward_pool
+        .update_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            Some(true),
+        )
+        .await;
+
+    let sponsor = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            initial_balance,
+        )
+        .await;
+    assert!(test_reward_pool
+        .fill_vault(
+            &mut context,
+            &sponsor,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .update_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            None,
+            None,
+            None,
+            Some(sponsor.owner.pubkey()),
+            None,
+        )
+        .await;
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &sponsor,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+        )
+        .await
+        .unwrap();
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &sponsor,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            200,
+        )
+        .await
+        .unwrap();
+
+    let (funder_contribution, _) = find_funder_contribution_program_address(
+        &everlend_rewards::id(),
+        &reward_pool,
+        &reward_mint.pubkey(),
+        &sponsor.owner.pubkey(),
+    );
+    let funder_contribution =
+        FunderContribution::unpack(&get_account(&mut context, &funder_contribution).await.data)
+            .unwrap();
+    assert_eq!(funder_contribution.funder, sponsor.owner.pubkey());
+    assert_eq!(funder_contribution.total_amount, 300);
+    assert_eq!(funder_contribution.fills_count, 2);
+    assert_eq!(funder_contribution.last_amount, 200);
+
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    let vault = reward_pool_account.vaults().first().unwrap();
+    assert_eq!(vault.total_funded, initial_balance / 2 + 300);
 }
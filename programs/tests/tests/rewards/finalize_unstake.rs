@@ -0,0 +1,93 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, sysvar::clock};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 5)
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(100),
+            Some(true),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+    let unbonding_started_at = clock.unix_timestamp;
+
+    test_reward_pool
+        .request_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    // the amount is released linearly over the unbonding period
+    for (slot, elapsed, balance) in [(10, 25, 250), (20, 50, 500), (30, 150, 1000)] {
+        clock.unix_timestamp = unbonding_started_at + elapsed;
+        clock_account.data = bincode::serialize(&clock).unwrap();
+        context.set_account(&clock::id(), &clock_account.clone().into());
+        context.warp_to_slot(slot).unwrap();
+
+        test_reward_pool
+            .finalize_unstake(
+                &mut context,
+                &liquidity_mint.pubkey(),
+                &token_holder.token_account,
+                &token_holder.owner,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_token_balance(&mut context, &token_holder.token_account).await,
+            balance
+        );
+    }
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.unbonding_amount, 0);
+    assert_eq!(mining.unlock_at, 0);
+}
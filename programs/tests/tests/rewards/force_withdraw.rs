@@ -0,0 +1,257 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::find_mining_program_address;
+use everlend_rewards::state::Mining;
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 7200, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    // the position is still locked for the owner
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+        )
+        .await
+        .is_err());
+
+    let owner_token_account = create_associated_token_account(
+        &mut context,
+        &user.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    let owner_reward_account =
+        create_associated_token_account(&mut context, &user.owner.pubkey(), &reward_mint.pubkey())
+            .await
+            .unwrap();
+
+    test_reward_pool
+        .force_withdraw(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &owner_token_account).await,
+        1000
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &owner_reward_account).await,
+        10
+    );
+
+    let (mining_account, _) =
+        find_mining_program_address(&everlend_rewards::id(), &user.owner.pubkey(), &reward_pool);
+    assert_eq!(mining, mining_account);
+    assert!(context
+        .banks_client
+        .get_account(mining)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn referred() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 7200, 0)
+        .await
+        .unwrap();
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(1000),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let referrer = Keypair::new();
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    test_reward_pool
+        .deposit_mining_referred(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            &referrer.pubkey(),
+            1000,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let owner_reward_account =
+        create_associated_token_account(&mut context, &user.owner.pubkey(), &reward_mint.pubkey())
+            .await
+            .unwrap();
+    create_associated_token_account(&mut context, &user.owner.pubkey(), &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+
+    // the referrer accounts are required to settle its share
+    assert!(test_reward_pool
+        .force_withdraw(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            &[reward_mint.pubkey()],
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .force_withdraw_referred(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            &[reward_mint.pubkey()],
+            &referrer.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let (referrer_mining, _) =
+        find_mining_program_address(&everlend_rewards::id(), &referrer.pubkey(), &reward_pool);
+    let referrer_mining = get_account(&mut context, &referrer_mining).await;
+    let referrer_mining = Mining::load_account(&referrer_mining.data).unwrap();
+    assert_eq!(referrer_mining.indexes()[0].rewards, 1);
+    // the owner keeps its full rewards
+    assert_eq!(
+        get_token_balance(&mut context, &owner_reward_account).await,
+        10
+    );
+}
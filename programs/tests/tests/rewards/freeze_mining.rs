@@ -0,0 +1,159 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .freeze_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner.pubkey(),
+            7,
+            true,
+        )
+        .await
+        .unwrap();
+
+    let mining_data = get_account(&mut context, &mining).await.data;
+    let frozen_mining = Mining::load(&mining_data).unwrap();
+    assert_eq!(frozen_mining.freeze_reason, 7);
+    assert!(bool::from(frozen_mining.is_accrual_paused));
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &user.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    assert!(test_reward_pool
+        .claim(
+            &mut context,
+            &user.owner,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+        )
+        .await
+        .is_err());
+
+    // the frozen period earns nothing
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    test_reward_pool
+        .unfreeze_mining(&mut context, &liquidity_mint.pubkey(), &user.owner.pubkey())
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .claim(
+            &mut context,
+            &user.owner,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        10
+    );
+
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.token_account).await,
+        1000
+    );
+}
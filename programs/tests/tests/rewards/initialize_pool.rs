@@ -1,5 +1,5 @@
 use crate::utils::*;
-use everlend_rewards::state::RewardPool;
+use everlend_rewards::state::{RewardPool, ZeroCopy};
 use everlend_utils::find_program_address;
 use solana_program::program_pack::Pack;
 use solana_program_test::*;
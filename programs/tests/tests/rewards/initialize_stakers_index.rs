@@ -0,0 +1,215 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, RewardPool, StakersIndex, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 2)
+        .await
+        .unwrap();
+
+    let stakers_index = test_reward_pool
+        .initialize_stakers_index(&mut context, &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.is_competitive, true);
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    assert_eq!(
+        stakers_index_account.data.len(),
+        StakersIndex::account_len(2)
+    );
+
+    let small_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let new_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let small_mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &small_holder.token_account,
+            &small_holder.owner,
+            10,
+        )
+        .await
+        .unwrap();
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &holder.token_account,
+            &holder.owner,
+            50,
+        )
+        .await
+        .unwrap();
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    let stakers_index_account = StakersIndex::load_account(&stakers_index_account.data).unwrap();
+    assert_eq!(stakers_index_account.entries().len(), 2);
+    assert_eq!(stakers_index_account.min().unwrap().mining, small_mining);
+
+    // max stakers are fixed by the stakers index
+    assert!(test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            Some(3),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .is_err());
+
+    let small_holder_ata = create_associated_token_account(
+        &mut context,
+        &small_holder.owner.pubkey(),
+        &liquidity_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // a full pool needs a larger position to displace the smallest one
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            20,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .deposit_mining_displacing(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            &small_holder.owner.pubkey(),
+            &small_holder_ata,
+            10,
+        )
+        .await
+        .is_err());
+
+    // frozen positions leave the index and can't be displaced
+    test_reward_pool
+        .freeze_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &small_holder.owner.pubkey(),
+            1,
+            false,
+        )
+        .await
+        .unwrap();
+    assert!(test_reward_pool
+        .deposit_mining_displacing(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            &small_holder.owner.pubkey(),
+            &small_holder_ata,
+            20,
+        )
+        .await
+        .is_err());
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    let stakers_index_account = StakersIndex::load_account(&stakers_index_account.data).unwrap();
+    assert_eq!(stakers_index_account.entries().len(), 1);
+    assert_eq!(stakers_index_account.min().unwrap().mining, mining);
+
+    test_reward_pool
+        .unfreeze_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &small_holder.owner.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    let stakers_index_account = StakersIndex::load_account(&stakers_index_account.data).unwrap();
+    assert_eq!(stakers_index_account.entries().len(), 2);
+    assert_eq!(stakers_index_account.min().unwrap().mining, small_mining);
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let new_mining = test_reward_pool
+        .deposit_mining_displacing(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+            &small_holder.owner.pubkey(),
+            &small_holder_ata,
+            20,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(get_token_balance(&mut context, &small_holder_ata).await, 10);
+
+    let small_mining_account =
+        Mining::unpack(&get_account(&mut context, &small_mining).await.data).unwrap();
+    assert_eq!(small_mining_account.amount, 0);
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_stakers, 2);
+    assert_eq!(reward_pool_account.total_amount, 70);
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    let stakers_index_account = StakersIndex::load_account(&stakers_index_account.data).unwrap();
+    assert_eq!(stakers_index_account.entries().len(), 2);
+    assert_eq!(stakers_index_account.min().unwrap().mining, new_mining);
+    assert!(stakers_index_account
+        .entries()
+        .iter()
+        .any(|e| e.mining == mining && e.amount == 50));
+
+    // positions leave the index on withdraw
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &new_holder.token_account,
+            &new_holder.owner,
+        )
+        .await
+        .unwrap();
+
+    let stakers_index_account = get_account(&mut context, &stakers_index).await;
+    let stakers_index_account = StakersIndex::load_account(&stakers_index_account.data).unwrap();
+    assert_eq!(stakers_index_account.entries().len(), 1);
+    assert_eq!(stakers_index_account.min().unwrap().mining, mining);
+}
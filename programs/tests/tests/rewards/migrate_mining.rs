@@ -5,11 +5,12 @@ use solana_sdk::{signature::Keypair};
 use std::borrow::Borrow;
 
 use solana_sdk::signature::Signer;
-use everlend_rewards::state::{Mining, DeprecatedMining, DeprecatedRewardIndex};
+use everlend_rewards::state::{
+    AccountType, DeprecatedMining, DeprecatedRewardIndex, Mining, ZeroCopy,
+};
 use crate::rewards::TestRewards;
 
-// #[tokio::test]
-
+#[tokio::test]
 async fn success() {
     let initial_balance = 100000;
 
@@ -48,32 +49,47 @@ async fn success() {
     let mining_account_info = get_account(&mut context, &mining_pubkey).await;
     let init_mining = Mining::unpack(&mining_account_info.data.borrow()).unwrap();
 
-    // Setup reward pool account as pre-migration version
+    // Setup mining account as pre-migration version
+    let reward_mint = Keypair::new().pubkey();
     let mining_old = DeprecatedMining {
-        account_type: init_mining.account_type,
+        account_type: AccountType::Mining,
         reward_pool: init_mining.reward_pool,
         bump: init_mining.bump,
         amount: init_mining.amount,
         rewards_calculated_at: init_mining.rewards_calculated_at,
         owner: init_mining.owner,
         last_deposit_time: init_mining.last_deposit_time,
-        reward_tier: init_mining.reward_tier,
-        indexes: init_mining.indexes.iter().map(|i| DeprecatedRewardIndex { reward_mint: i.reward_mint, rewards: i.rewards }).collect(),
+        reward_tier: 1,
+        indexes: vec![DeprecatedRewardIndex {
+            reward_mint,
+            rewards: 15,
+            claimed_total_rewards: 30,
+        }],
     };
 
     let mut mining_account = get_account(&mut context, &mining_pubkey).await;
-    mining_account.data = mining_account.data[0..DeprecatedMining::LEN].to_vec();
+    mining_account.data = vec![0; DeprecatedMining::LEN];
     mining_old.pack_into_slice(&mut mining_account.data);
     context.set_account(&mining_pubkey, &mining_account.into());
 
     // migrate
-    test_reward_pool.migrate_mining(&mut context, &liquidity_mint, &mining_pubkey).await.unwrap();
+    test_reward_pool
+        .migrate_mining(&mut context, &liquidity_mint, &token_holder.owner, &mining_pubkey)
+        .await
+        .unwrap();
 
     let mining_account_info = get_account(&mut context, &mining_pubkey).await;
-    let mining = Mining::unpack(&mining_account_info.data.borrow()).unwrap();
+    assert_eq!(mining_account_info.data.len(), Mining::account_len(1));
+    let mining_account = Mining::load_account(&mining_account_info.data).unwrap();
+    let mining = mining_account.mining;
 
     assert_eq!(mining.reward_pool, init_mining.reward_pool);
     assert_eq!(mining.bump, init_mining.bump);
     assert_eq!(mining.owner, init_mining.owner);
     assert_eq!(mining.amount, init_mining.amount);
+    assert_eq!(mining.reward_tier, 1);
+    assert_eq!(mining_account.indexes().len(), 1);
+    assert_eq!(mining_account.indexes()[0].reward_mint, reward_mint);
+    assert_eq!(mining_account.indexes()[0].rewards, 15);
+    assert_eq!(mining_account.indexes()[0].claimed_total_rewards, 30);
 }
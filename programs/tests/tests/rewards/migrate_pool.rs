@@ -5,10 +5,12 @@ use solana_sdk::{signature::Keypair};
 use std::borrow::Borrow;
 
 use solana_sdk::signature::Signer;
-use everlend_rewards::state::{DeprecatedRewardPool, RewardPool, InitRewardPoolParams};
+use everlend_rewards::state::{
+    AccountType, DeprecatedRewardPool, DeprecatedRewardVault, RewardPool, RewardTier, ZeroCopy,
+};
 use crate::rewards::TestRewards;
 
-// #[tokio::test]
+#[tokio::test]
 async fn success() {
     let mut context = program_test().start_with_context().await;
 
@@ -25,28 +27,58 @@ async fn success() {
     let init_reward_pool_account =
         RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
 
+    let total_stakers = 3;
+    let reward_mint = Keypair::new().pubkey();
+    let reward_tiers = vec![
+        RewardTier {
+            ratio_base: 1000,
+            ratio_quote: 1,
+            reward_max_amount_per_period: 2,
+        },
+        RewardTier {
+            ratio_base: 1000,
+            ratio_quote: 2,
+            reward_max_amount_per_period: 4,
+        },
+    ];
+
     // Setup reward pool account as pre-migration version
-    let reward_pool_old = DeprecatedRewardPool::init(InitRewardPoolParams {
+    let reward_pool_old = DeprecatedRewardPool {
+        account_type: AccountType::RewardPool,
         rewards_root: init_reward_pool_account.rewards_root,
         bump: init_reward_pool_account.bump,
         liquidity_mint: init_reward_pool_account.liquidity_mint,
-        lock_time_sec: init_reward_pool_account.lock_time_sec,
         max_stakers,
-    });
+        total_stakers,
+        total_amount: 1000,
+        lock_time_sec,
+        vaults: vec![DeprecatedRewardVault {
+            vault_token_account_bump: 254,
+            reward_mint,
+            reward_period_sec: 60,
+            is_enabled: true,
+            enabled_at: 100,
+            claimed_total_amount: 10,
+            reward_tiers: reward_tiers.clone(),
+        }],
+    };
 
     let mut reward_pool_account = get_account(&mut context, &reward_pool).await;
-    reward_pool_account.data = reward_pool_account.data[0..DeprecatedRewardPool::LEN].to_vec();
+    reward_pool_account.data = vec![0; DeprecatedRewardPool::LEN];
     reward_pool_old.pack_into_slice(&mut reward_pool_account.data);
     context.set_account(&reward_pool, &reward_pool_account.into());
 
-    let max_stakers = 10;
-    let total_stakers = 5;
-
     // migrate
-    test_reward_pool.migrate_pool(&mut context, &pool_mint, max_stakers, total_stakers).await.unwrap();
+    test_reward_pool.migrate_pool(&mut context, &pool_mint).await.unwrap();
 
-    let reward_pool_account =
-        RewardPool::unpack(get_account(&mut context, &reward_pool).await.data.borrow()).unwrap();
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    assert_eq!(
+        reward_pool_account.data.len(),
+        RewardPool::account_len(3, 5)
+    );
+    let reward_pool_data = reward_pool_account.data;
+    let reward_pool = RewardPool::load_account(&reward_pool_data).unwrap();
+    let reward_pool_account = reward_pool.pool;
 
     assert_eq!(
         reward_pool_account.rewards_root,
@@ -55,7 +87,23 @@ async fn success() {
 
     assert_eq!(reward_pool_account.liquidity_mint, pool_mint.pubkey());
     assert_eq!(reward_pool_account.bump, init_reward_pool_account.bump);
+    assert_eq!(reward_pool_account.authority_bump, init_reward_pool_account.authority_bump);
+    assert_eq!(reward_pool_account.spl_bump, init_reward_pool_account.spl_bump);
     assert_eq!(reward_pool_account.lock_time_sec, lock_time_sec);
     assert_eq!(reward_pool_account.max_stakers, max_stakers);
     assert_eq!(reward_pool_account.total_stakers, total_stakers);
+    assert_eq!(reward_pool_account.total_amount, 1000);
+
+    let vault = reward_pool.vaults()[0];
+    assert_eq!(reward_pool.vaults().len(), 1);
+    assert_eq!(vault.reward_mint, reward_mint);
+    assert_eq!(vault.vault_token_account_bump, 254);
+    assert_eq!(vault.is_enabled, true);
+    assert_eq!(vault.enabled_at, 100);
+    assert_eq!(vault.claimed_total_amount, 10);
+    assert_eq!(reward_pool.vault_tiers(0), reward_tiers.as_slice());
+
+    // already migrated
+    context.warp_to_slot(3).unwrap();
+    test_reward_pool.migrate_pool(&mut context, &pool_mint).await.unwrap_err();
 }
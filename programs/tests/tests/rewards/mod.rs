@@ -1,5 +1,6 @@
 pub mod add_vault;
 pub mod claim;
+pub mod compute_units;
 pub mod deposit_mining;
 pub mod fill_vault;
 pub mod initialize_pool;
@@ -7,22 +8,47 @@ pub mod upgrade_mining;
 pub mod withdraw_mining;
 pub mod migrate_pool;
 pub mod migrate_mining;
+pub mod resize_pool;
+pub mod update_pool;
+pub mod evict_dust;
+pub mod initialize_stakers_index;
+pub mod request_unstake;
+pub mod finalize_unstake;
+pub mod set_pool_fees;
+pub mod settle_referral_rewards;
+pub mod refresh_boost;
+pub mod buy_tier;
+pub mod sync_receipt;
+pub mod move_position;
+pub mod tokenize_position;
+pub mod close_pool;
+pub mod force_withdraw;
+pub mod freeze_mining;
+pub mod update_denylist;
+pub mod deposit_for;
 
 use crate::utils::{
     add_token_holder, create_mint, get_account, get_token_balance, transfer_sol, BanksClientResult,
     TokenHolder,
 };
-use everlend_rewards::state::RewardTier;
+use everlend_rewards::state::{BoostKind, PoolStatus, RewardPool, RewardTier, VaultKind, ZeroCopy};
 use everlend_rewards::{
-    find_mining_program_address, find_reward_pool_program_address,
-    find_reward_pool_spl_token_account, find_vault_spl_token_account,
+    find_denylist_program_address, find_mining_position_address, find_mining_program_address,
+    find_receipt_mint_program_address,
+    find_reward_pool_program_address,
+    find_reward_pool_spl_token_account, find_stakers_index_program_address,
+    find_vault_spl_token_account,
 };
+use everlend_utils::cpi::spl_token::find_associated_token_account;
 use everlend_utils::find_program_address;
 use solana_program::pubkey::Pubkey;
 use solana_program_test::ProgramTestContext;
 use solana_sdk::signature::{Keypair, Signer};
 use solana_sdk::transaction::Transaction;
 
+pub const VAULTS_CAPACITY: u8 = 3;
+pub const TIERS_CAPACITY: u8 = 5;
+
 #[derive(Debug)]
 pub struct TestRewards {
     pub rewards_root: Keypair,
@@ -38,298 +64,1993 @@ impl TestRewards {
             .await
             .unwrap();
 
-        Self {
-            rewards_root,
-            root_authority,
-        }
+        Self {
+            rewards_root,
+            root_authority,
+        }
+    }
+
+    pub fn get_pool_addresses(&self, liquidity_mint: &Pubkey) -> (Pubkey, Pubkey) {
+        let (reward_pool, _) = find_reward_pool_program_address(
+            &everlend_rewards::id(),
+            &self.rewards_root.pubkey(),
+            liquidity_mint,
+        );
+
+        let (reward_pool_spl, _) = find_reward_pool_spl_token_account(
+            &everlend_rewards::id(),
+            &reward_pool,
+            liquidity_mint,
+        );
+
+        (reward_pool, reward_pool_spl)
+    }
+
+    pub async fn get_denylist(
+        &self,
+        context: &mut ProgramTestContext,
+        reward_pool: &Pubkey,
+    ) -> Option<Pubkey> {
+        let reward_pool_account = get_account(context, reward_pool).await;
+        let reward_pool_account = RewardPool::load(&reward_pool_account.data).unwrap();
+
+        bool::from(reward_pool_account.has_denylist).then(|| {
+            find_denylist_program_address(&everlend_rewards::id(), &self.rewards_root.pubkey()).0
+        })
+    }
+
+    pub async fn create_mint_and_initialize_pool(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Keypair,
+        lock_time_sec: u64,
+        max_stakers: u64,
+    ) -> BanksClientResult<(Pubkey, Pubkey)> {
+        create_mint(context, liquidity_mint).await.unwrap();
+
+        self.initialize_pool(context, &liquidity_mint.pubkey(), lock_time_sec, max_stakers)
+            .await
+    }
+
+    pub async fn initialize_pool(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        lock_time_sec: u64,
+        max_stakers: u64,
+    ) -> BanksClientResult<(Pubkey, Pubkey)> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                everlend_rewards::instruction::initialize_root(
+                    &everlend_rewards::id(),
+                    &self.rewards_root.pubkey(),
+                    &self.root_authority.pubkey(),
+                ),
+                everlend_rewards::instruction::initialize_pool(
+                    &everlend_rewards::id(),
+                    &self.rewards_root.pubkey(),
+                    &reward_pool,
+                    &reward_pool_spl,
+                    &reward_pool_authority,
+                    liquidity_mint,
+                    &self.root_authority.pubkey(),
+                    lock_time_sec,
+                    max_stakers,
+                    VAULTS_CAPACITY,
+                    TIERS_CAPACITY,
+                ),
+            ],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority, &self.rewards_root],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok((reward_pool, reward_pool_spl))
+    }
+
+    pub async fn resize_pool(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        vaults_capacity: u8,
+        tiers_capacity: u8,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::resize_pool(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+                vaults_capacity,
+                tiers_capacity,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_pool(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        lock_time_sec: Option<u64>,
+        max_stakers: Option<u64>,
+        min_deposit_amount: Option<u64>,
+        max_deposit_amount: Option<u64>,
+        min_stake_amount: Option<u64>,
+        max_stake_amount: Option<u64>,
+        unbonding_period_sec: Option<u64>,
+        is_linear_unbonding: Option<bool>,
+        status: Option<PoolStatus>,
+        referral_share_bps: Option<u16>,
+        warmup_period_sec: Option<u64>,
+        warmup_rate_bps: Option<u16>,
+        max_funder_lock_sec: Option<u64>,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::update_pool(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+                lock_time_sec,
+                max_stakers,
+                min_deposit_amount,
+                max_deposit_amount,
+                min_stake_amount,
+                max_stake_amount,
+                unbonding_period_sec,
+                is_linear_unbonding,
+                status,
+                referral_share_bps,
+                warmup_period_sec,
+                warmup_rate_bps,
+                max_funder_lock_sec,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn set_pool_fees(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        treasury: &Pubkey,
+        deposit_fee_bps: u16,
+        withdraw_fee_bps: u16,
+        claim_fee_bps: u16,
+        is_fee_to_vault: bool,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::set_pool_fees(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+                treasury,
+                deposit_fee_bps,
+                withdraw_fee_bps,
+                claim_fee_bps,
+                is_fee_to_vault,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn initialize_stakers_index(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (stakers_index, _) =
+            find_stakers_index_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::initialize_stakers_index(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(stakers_index)
+    }
+
+    pub async fn deposit_mining(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                &liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn deposit_mining_referred(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        referrer: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining_referred(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                referrer,
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn deposit_mining_with_fee(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        fee_token_account: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining_with_fee(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                fee_token_account,
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn deposit_mining_displacing(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        displaced_owner: &Pubkey,
+        displaced_owner_token_account: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+        let (displaced_mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), displaced_owner, &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining_displacing(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                &displaced_mining_account,
+                displaced_owner_token_account,
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn deposit_mining_native(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &Keypair,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) =
+            self.get_pool_addresses(&spl_token::native_mint::id());
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining_native(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                &mining_account,
+                &user.pubkey(),
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn withdraw_mining_native(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &Keypair,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) =
+            self.get_pool_addresses(&spl_token::native_mint::id());
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining_native(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                &mining_account,
+                &user.pubkey(),
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn withdraw_mining(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                &liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn withdraw_mining_with_fee(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        fee_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining_with_fee(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                fee_token_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn withdraw_mining_referred(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        referrer: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining_referred(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                referrer,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn evict_dust(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        owner: &Pubkey,
+        owner_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::evict_dust(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                owner_token_account,
+                &context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn evict_dust_tokenized(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        holder_liquidity_account: &Pubkey,
+        holder_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::evict_dust_tokenized(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                mining,
+                holder_liquidity_account,
+                &context.payer.pubkey(),
+                holder_token_account,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn request_unstake(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::request_unstake(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &mining_account,
+                &user.pubkey(),
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn finalize_unstake(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::finalize_unstake(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn cancel_unstake(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Keypair,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::cancel_unstake(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &mining_account,
+                &user.pubkey(),
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn add_vault(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        ratio_base: u64,
+        ratio_quote: u64,
+        reward_period_sec: u32,
+    ) -> Pubkey {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (vault_pubkey, _) =
+            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::add_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                reward_mint,
+                &vault_pubkey,
+                &self.root_authority.pubkey(),
+                reward_period_sec,
+                vec![RewardTier {
+                    ratio_base,
+                    ratio_quote,
+                    reward_max_amount_per_period: 0,
+                }],
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        vault_pubkey
+    }
+
+    pub async fn add_native_vault(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        ratio_base: u64,
+        ratio_quote: u64,
+        reward_period_sec: u32,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (vault_pubkey, _) = find_vault_spl_token_account(
+            &everlend_rewards::id(),
+            &reward_pool,
+            &spl_token::native_mint::id(),
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::add_native_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &vault_pubkey,
+                &self.root_authority.pubkey(),
+                reward_period_sec,
+                vec![RewardTier {
+                    ratio_base,
+                    ratio_quote,
+                    reward_max_amount_per_period: 0,
+                }],
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(vault_pubkey)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn update_vault(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        reward_period_sec: Option<u32>,
+        is_enabled: Option<bool>,
+        tiers: Option<Vec<RewardTier>>,
+        manager: Option<Pubkey>,
+        is_fill_permissioned: Option<bool>,
+    ) {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::update_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                reward_mint,
+                &self.root_authority.pubkey(),
+                reward_period_sec,
+                is_enabled,
+                tiers,
+                manager,
+                is_fill_permissioned,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    pub async fn set_vault_minting(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        max_mint_amount: u64,
+        max_mint_amount_per_period: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::update_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                reward_mint,
+                &self.root_authority.pubkey(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(VaultKind::Mint),
+                Some(max_mint_amount),
+                Some(max_mint_amount_per_period),
+                None,
+                None,
+                None,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_vault_epochs(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        epoch_anchor: u64,
+        is_partial_epoch_excluded: bool,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::update_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                reward_mint,
+                &self.root_authority.pubkey(),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+                Some(true),
+                Some(epoch_anchor),
+                Some(is_partial_epoch_excluded),
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn upgrade_mining(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Pubkey,
+        tier: u8,
+    ) {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user, &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::upgrade_mining(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &mining_account,
+                &user,
+                &self.root_authority.pubkey(),
+                tier,
+            )],
+            None,
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await.unwrap();
+    }
+
+    pub async fn upgrade_mining_legacy(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Pubkey,
+        tier: u8,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), user, &reward_pool);
+
+        // clients built before the mining could grow don't pass the system program
+        let mut instruction = everlend_rewards::instruction::upgrade_mining(
+            &everlend_rewards::id(),
+            &self.rewards_root.pubkey(),
+            &reward_pool,
+            &mining_account,
+            user,
+            &self.root_authority.pubkey(),
+            tier,
+        );
+        instruction.accounts.pop();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            None,
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn fill_vault(
+        &self,
+        context: &mut ProgramTestContext,
+        from: &TokenHolder,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (vault_pubkey, _) =
+            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::fill_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                reward_mint,
+                &vault_pubkey,
+                &from.token_account,
+                &from.owner.pubkey(),
+                amount,
+            )],
+            None,
+            &[&from.owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn fill_native_vault(
+        &self,
+        context: &mut ProgramTestContext,
+        funder: &Keypair,
+        liquidity_mint: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (vault_pubkey, _) = find_vault_spl_token_account(
+            &everlend_rewards::id(),
+            &reward_pool,
+            &spl_token::native_mint::id(),
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::fill_vault(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &spl_token::native_mint::id(),
+                &vault_pubkey,
+                &funder.pubkey(),
+                &funder.pubkey(),
+                amount,
+            )],
+            None,
+            &[funder],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn claim(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &Keypair,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        user_reward_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (vault_pubkey, _) =
+            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::claim(
+                &everlend_rewards::id(),
+                &reward_pool,
+                reward_mint,
+                &vault_pubkey,
+                &mining_account,
+                &user.pubkey(),
+                &user_reward_token_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn claim_with_fee(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &Keypair,
+        liquidity_mint: &Pubkey,
+        reward_mint: &Pubkey,
+        user_reward_token_account: &Pubkey,
+        fee_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (vault_pubkey, _) =
+            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::claim_with_fee(
+                &everlend_rewards::id(),
+                &reward_pool,
+                reward_mint,
+                &vault_pubkey,
+                &mining_account,
+                &user.pubkey(),
+                user_reward_token_account,
+                fee_token_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn settle_referral_rewards(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        referrer: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::settle_referral_rewards(
+                &everlend_rewards::id(),
+                &reward_pool,
+                mining,
+                referrer,
+                &context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_boost_source(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        index: u8,
+        kind: BoostKind,
+        multiplier_bps: u16,
+        min_amount: u64,
+        mints: Vec<Pubkey>,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::add_boost_source(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+                index,
+                kind,
+                multiplier_bps,
+                min_amount,
+                mints,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn refresh_boost(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        holder_token_accounts: &[Pubkey],
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::refresh_boost(
+                &everlend_rewards::id(),
+                &reward_pool,
+                mining,
+                &context.payer.pubkey(),
+                holder_token_accounts,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn refresh_boost_by_owner(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        owner: &Keypair,
+        holder_token_accounts: &[Pubkey],
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::refresh_boost(
+                &everlend_rewards::id(),
+                &reward_pool,
+                mining,
+                &owner.pubkey(),
+                holder_token_accounts,
+            )],
+            Some(&owner.pubkey()),
+            &[owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn set_tier_prices(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        burn_mint: &Pubkey,
+        prices: Vec<u64>,
+        tier_duration_sec: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::set_tier_prices(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                burn_mint,
+                &self.root_authority.pubkey(),
+                prices,
+                tier_duration_sec,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn buy_tier(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        burn_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        tier: u8,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::buy_tier(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &mining_account,
+                burn_mint,
+                user_token_account,
+                &user.pubkey(),
+                tier,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn initialize_receipt_mint(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (receipt_mint, _) =
+            find_receipt_mint_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::initialize_receipt_mint(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                liquidity_mint,
+                &self.root_authority.pubkey(),
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(receipt_mint)
+    }
+
+    pub async fn deposit_mining_with_receipt(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        user_receipt_account: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let denylist = self.get_denylist(context, &reward_pool).await;
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::deposit_mining_with_receipt(
+                &everlend_rewards::id(),
+                denylist.as_ref(),
+                &reward_pool,
+                &reward_pool_spl,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                user_receipt_account,
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn withdraw_mining_with_receipt(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        user_receipt_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining_with_receipt(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                user_token_account,
+                &user.pubkey(),
+                user_receipt_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn request_unstake_with_receipt(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Keypair,
+        user_receipt_account: &Pubkey,
+        amount: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::request_unstake_with_receipt(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &mining_account,
+                &user.pubkey(),
+                user_receipt_account,
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn sync_receipt(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        owner: &Pubkey,
+        owner_receipt_account: &Pubkey,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::sync_receipt(
+                &everlend_rewards::id(),
+                &reward_pool,
+                owner,
+                owner_receipt_account,
+                &context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn open_position(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        user: &Keypair,
+        index: u16,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let (mining_account, _) = find_mining_position_address(
+            &everlend_rewards::id(),
+            &user.pubkey(),
+            &reward_pool,
+            index,
+        );
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::open_position(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &user.pubkey(),
+                index,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
+    }
+
+    pub async fn split_position(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        source_mining: &Pubkey,
+        destination_mining: &Pubkey,
+        user: &Keypair,
+        amount: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::split_position(
+                &everlend_rewards::id(),
+                &reward_pool,
+                source_mining,
+                destination_mining,
+                &user.pubkey(),
+                amount,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn merge_position(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        source_mining: &Pubkey,
+        destination_mining: &Pubkey,
+        user: &Keypair,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::merge_position(
+                &everlend_rewards::id(),
+                &reward_pool,
+                source_mining,
+                destination_mining,
+                &user.pubkey(),
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn tokenize_position(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        user: &Keypair,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        let user_position_account = Keypair::new();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::tokenize_position(
+                &everlend_rewards::id(),
+                &reward_pool,
+                mining,
+                &user_position_account.pubkey(),
+                &user.pubkey(),
+            )],
+            Some(&user.pubkey()),
+            &[user, &user_position_account],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(user_position_account.pubkey())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn claim_tokenized(
+        &self,
+        context: &mut ProgramTestContext,
+        user: &Keypair,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        reward_mint: &Pubkey,
+        user_reward_token_account: &Pubkey,
+        holder_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
+        let (vault_pubkey, _) =
+            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::claim_tokenized(
+                &everlend_rewards::id(),
+                &reward_pool,
+                reward_mint,
+                &vault_pubkey,
+                mining,
+                &user.pubkey(),
+                user_reward_token_account,
+                holder_token_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn withdraw_mining_tokenized(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        mining: &Pubkey,
+        user_token_account: &Pubkey,
+        user: &Keypair,
+        holder_token_account: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::withdraw_mining_tokenized(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                mining,
+                user_token_account,
+                &user.pubkey(),
+                holder_token_account,
+            )],
+            None,
+            &[user],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub fn get_pool_addresses(&self, liquidity_mint: &Pubkey) -> (Pubkey, Pubkey) {
-        let (reward_pool, _) = find_reward_pool_program_address(
-            &everlend_rewards::id(),
-            &self.rewards_root.pubkey(),
-            liquidity_mint,
-        );
+    pub async fn start_pool_closing(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        grace_period_sec: u64,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
 
-        let (reward_pool_spl, _) = find_reward_pool_spl_token_account(
-            &everlend_rewards::id(),
-            &reward_pool,
-            liquidity_mint,
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::start_pool_closing(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &self.root_authority.pubkey(),
+                grace_period_sec,
+            )],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
+            context.last_blockhash,
         );
 
-        (reward_pool, reward_pool_spl)
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn create_mint_and_initialize_pool(
+    pub async fn force_unstake(
         &self,
         context: &mut ProgramTestContext,
-        liquidity_mint: &Keypair,
-        lock_time_sec: u64,
-        max_stakers: u64,
-    ) -> BanksClientResult<(Pubkey, Pubkey)> {
-        create_mint(context, liquidity_mint).await.unwrap();
+        liquidity_mint: &Pubkey,
+        owner: &Pubkey,
+        owner_token_account: &Pubkey,
+        reward_mints: &[Pubkey],
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
 
-        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(&liquidity_mint.pubkey());
         let (reward_pool_authority, _) =
             find_program_address(&everlend_rewards::id(), &reward_pool);
 
+        let rewards: Vec<(Pubkey, Pubkey)> = reward_mints
+            .iter()
+            .map(|reward_mint| {
+                (
+                    *reward_mint,
+                    find_associated_token_account(owner, reward_mint).0,
+                )
+            })
+            .collect();
+
         let tx = Transaction::new_signed_with_payer(
-            &[
-                everlend_rewards::instruction::initialize_root(
-                    &everlend_rewards::id(),
-                    &self.rewards_root.pubkey(),
-                    &self.root_authority.pubkey(),
-                ),
-                everlend_rewards::instruction::initialize_pool(
-                    &everlend_rewards::id(),
-                    &self.rewards_root.pubkey(),
-                    &reward_pool,
-                    &reward_pool_spl,
-                    &reward_pool_authority,
-                    &liquidity_mint.pubkey(),
-                    &self.root_authority.pubkey(),
-                    lock_time_sec,
-                    max_stakers,
-                ),
-            ],
-            Some(&self.root_authority.pubkey()),
-            &[&self.root_authority, &self.rewards_root],
+            &[everlend_rewards::instruction::force_unstake(
+                &everlend_rewards::id(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                owner,
+                owner_token_account,
+                &context.payer.pubkey(),
+                &rewards,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await?;
-
-        Ok((reward_pool, reward_pool_spl))
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn deposit_mining(
+    pub async fn force_unstake_referred(
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Pubkey,
-        user_token_account: &Pubkey,
-        user: &Keypair,
-        amount: u64,
-    ) -> BanksClientResult<Pubkey> {
+        owner: &Pubkey,
+        owner_token_account: &Pubkey,
+        reward_mints: &[Pubkey],
+        referrer: &Pubkey,
+    ) -> BanksClientResult<()> {
         let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
 
         let (mining_account, _) =
-            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let rewards: Vec<(Pubkey, Pubkey)> = reward_mints
+            .iter()
+            .map(|reward_mint| {
+                (
+                    *reward_mint,
+                    find_associated_token_account(owner, reward_mint).0,
+                )
+            })
+            .collect();
 
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::deposit_mining(
+            &[everlend_rewards::instruction::force_unstake_referred(
                 &everlend_rewards::id(),
                 &reward_pool,
                 &reward_pool_spl,
-                &liquidity_mint,
+                &reward_pool_authority,
+                liquidity_mint,
                 &mining_account,
-                user_token_account,
-                &user.pubkey(),
-                amount,
+                owner,
+                owner_token_account,
+                &context.payer.pubkey(),
+                &rewards,
+                referrer,
             )],
-            None,
-            &[user],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await?;
+        context.banks_client.process_transaction(tx).await
+    }
 
-        Ok(mining_account)
+    pub async fn close_pool(
+        &self,
+        context: &mut ProgramTestContext,
+        liquidity_mint: &Pubkey,
+        reward_mints: &[Pubkey],
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let authority = self.root_authority.pubkey();
+        let (authority_token_account, _) =
+            find_associated_token_account(&authority, liquidity_mint);
+        let vaults: Vec<(Pubkey, Pubkey)> = reward_mints
+            .iter()
+            .map(|reward_mint| {
+                (
+                    *reward_mint,
+                    find_associated_token_account(&authority, reward_mint).0,
+                )
+            })
+            .collect();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[everlend_rewards::instruction::close_pool(
+                &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
+                &reward_pool,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                &authority,
+                &authority_token_account,
+                &vaults,
+            )],
+            Some(&authority),
+            &[&self.root_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn withdraw_mining(
+    pub async fn force_withdraw(
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Pubkey,
-        user_token_account: &Pubkey,
-        user: &Keypair,
+        owner: &Pubkey,
+        reward_mints: &[Pubkey],
     ) -> BanksClientResult<()> {
         let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
 
         let (mining_account, _) =
-            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
 
         let (reward_pool_authority, _) =
             find_program_address(&everlend_rewards::id(), &reward_pool);
 
+        let (owner_token_account, _) = find_associated_token_account(owner, liquidity_mint);
+        let rewards: Vec<(Pubkey, Pubkey)> = reward_mints
+            .iter()
+            .map(|reward_mint| {
+                (
+                    *reward_mint,
+                    find_associated_token_account(owner, reward_mint).0,
+                )
+            })
+            .collect();
+
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::withdraw_mining(
+            &[everlend_rewards::instruction::force_withdraw(
                 &everlend_rewards::id(),
+                &self.rewards_root.pubkey(),
                 &reward_pool,
                 &reward_pool_spl,
                 &reward_pool_authority,
-                &liquidity_mint,
+                liquidity_mint,
                 &mining_account,
-                user_token_account,
-                &user.pubkey(),
+                owner,
+                &owner_token_account,
+                &self.root_authority.pubkey(),
+                &rewards,
             )],
-            None,
-            &[user],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn add_vault(
+    pub async fn force_withdraw_referred(
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Pubkey,
-        reward_mint: &Pubkey,
-        ratio_base: u64,
-        ratio_quote: u64,
-        reward_period_sec: u32,
-    ) -> Pubkey {
-        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        owner: &Pubkey,
+        reward_mints: &[Pubkey],
+        referrer: &Pubkey,
+    ) -> BanksClientResult<()> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
 
-        let (vault_pubkey, _) =
-            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
+
+        let (reward_pool_authority, _) =
+            find_program_address(&everlend_rewards::id(), &reward_pool);
+
+        let (owner_token_account, _) = find_associated_token_account(owner, liquidity_mint);
+        let rewards: Vec<(Pubkey, Pubkey)> = reward_mints
+            .iter()
+            .map(|reward_mint| {
+                (
+                    *reward_mint,
+                    find_associated_token_account(owner, reward_mint).0,
+                )
+            })
+            .collect();
 
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::add_vault(
+            &[everlend_rewards::instruction::force_withdraw_referred(
                 &everlend_rewards::id(),
                 &self.rewards_root.pubkey(),
                 &reward_pool,
-                reward_mint,
-                &vault_pubkey,
+                &reward_pool_spl,
+                &reward_pool_authority,
+                liquidity_mint,
+                &mining_account,
+                owner,
+                &owner_token_account,
                 &self.root_authority.pubkey(),
-                reward_period_sec,
-                vec![RewardTier {
-                    ratio_base,
-                    ratio_quote,
-                    reward_max_amount_per_period: 0,
-                }],
+                &rewards,
+                referrer,
             )],
             Some(&self.root_authority.pubkey()),
             &[&self.root_authority],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await.unwrap();
-
-        vault_pubkey
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn update_vault(
+    pub async fn freeze_mining(
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Pubkey,
-        reward_mint: &Pubkey,
-        reward_period_sec: Option<u32>,
-        is_enabled: Option<bool>,
-        tiers: Option<Vec<RewardTier>>,
-    ) {
+        owner: &Pubkey,
+        reason: u8,
+        pause_accrual: bool,
+    ) -> BanksClientResult<()> {
         let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
 
+        let (mining_account, _) =
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
+
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::update_vault(
+            &[everlend_rewards::instruction::freeze_mining(
                 &everlend_rewards::id(),
                 &self.rewards_root.pubkey(),
                 &reward_pool,
-                reward_mint,
+                &mining_account,
                 &self.root_authority.pubkey(),
-                reward_period_sec,
-                is_enabled,
-                tiers,
+                reason,
+                pause_accrual,
             )],
             Some(&self.root_authority.pubkey()),
             &[&self.root_authority],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await.unwrap();
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn upgrade_mining(
+    pub async fn unfreeze_mining(
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Pubkey,
-        user: &Pubkey,
-        tier: u8,
-    ) {
+        owner: &Pubkey,
+    ) -> BanksClientResult<()> {
         let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+
         let (mining_account, _) =
-            find_mining_program_address(&everlend_rewards::id(), &user, &reward_pool);
+            find_mining_program_address(&everlend_rewards::id(), owner, &reward_pool);
 
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::upgrade_mining(
+            &[everlend_rewards::instruction::unfreeze_mining(
                 &everlend_rewards::id(),
                 &self.rewards_root.pubkey(),
                 &reward_pool,
                 &mining_account,
-                &user,
                 &self.root_authority.pubkey(),
-                tier,
             )],
-            None,
+            Some(&self.root_authority.pubkey()),
             &[&self.root_authority],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await.unwrap();
+        context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn fill_vault(
+    pub async fn update_denylist(
         &self,
         context: &mut ProgramTestContext,
-        from: &TokenHolder,
-        liquidity_mint: &Pubkey,
-        reward_mint: &Pubkey,
-        amount: u64,
+        wallet: &Pubkey,
+        is_denied: bool,
+        reward_pools: &[Pubkey],
     ) -> BanksClientResult<()> {
-        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
-        let (vault_pubkey, _) =
-            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
-
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::fill_vault(
+            &[everlend_rewards::instruction::update_denylist(
                 &everlend_rewards::id(),
-                &reward_pool,
-                reward_mint,
-                &vault_pubkey,
-                &from.token_account,
-                &from.owner.pubkey(),
-                amount,
+                &self.rewards_root.pubkey(),
+                &self.root_authority.pubkey(),
+                wallet,
+                is_denied,
+                reward_pools,
             )],
-            None,
-            &[&from.owner],
+            Some(&self.root_authority.pubkey()),
+            &[&self.root_authority],
             context.last_blockhash,
         );
 
         context.banks_client.process_transaction(tx).await
     }
 
-    pub async fn claim(
+    #[allow(clippy::too_many_arguments)]
+    pub async fn deposit_for(
         &self,
         context: &mut ProgramTestContext,
-        user: &Keypair,
         liquidity_mint: &Pubkey,
-        reward_mint: &Pubkey,
-        user_reward_token_account: &Pubkey,
-    ) -> BanksClientResult<()> {
-        let (reward_pool, _) = self.get_pool_addresses(liquidity_mint);
+        funder_token_account: &Pubkey,
+        funder: &Keypair,
+        beneficiary: &Pubkey,
+        amount: u64,
+        min_lock_sec: Option<u64>,
+    ) -> BanksClientResult<Pubkey> {
+        let (reward_pool, reward_pool_spl) = self.get_pool_addresses(liquidity_mint);
 
         let (mining_account, _) =
-            find_mining_program_address(&everlend_rewards::id(), &user.pubkey(), &reward_pool);
-
-        let (vault_pubkey, _) =
-            find_vault_spl_token_account(&everlend_rewards::id(), &reward_pool, reward_mint);
+            find_mining_program_address(&everlend_rewards::id(), beneficiary, &reward_pool);
 
+        let denylist = self.get_denylist(context, &reward_pool).await;
         let tx = Transaction::new_signed_with_payer(
-            &[everlend_rewards::instruction::claim(
+            &[everlend_rewards::instruction::deposit_for(
                 &everlend_rewards::id(),
+                denylist.as_ref(),
                 &reward_pool,
-                reward_mint,
-                &vault_pubkey,
-                &mining_account,
-                &user.pubkey(),
-                &user_reward_token_account,
+                &reward_pool_spl,
+                liquidity_mint,
+                funder_token_account,
+                &funder.pubkey(),
+                beneficiary,
+                amount,
+                min_lock_sec,
             )],
-            None,
-            &[user],
+            Some(&funder.pubkey()),
+            &[funder],
             context.last_blockhash,
         );
 
-        context.banks_client.process_transaction(tx).await
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(mining_account)
     }
 
     pub async fn create_token_holder(
@@ -369,8 +2090,6 @@ impl TestRewards {
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Keypair,
-        max_stakers: u64,
-        total_stakers: u64,
     ) -> BanksClientResult<()> {
         let (reward_pool, _) = self.get_pool_addresses(&liquidity_mint.pubkey());
 
@@ -382,8 +2101,6 @@ impl TestRewards {
                     &reward_pool,
                     &self.root_authority.pubkey(),
                     &liquidity_mint.pubkey(),
-                    max_stakers,
-                    total_stakers,
                 ),
             ],
             Some(&self.root_authority.pubkey()),
@@ -400,7 +2117,8 @@ impl TestRewards {
         &self,
         context: &mut ProgramTestContext,
         liquidity_mint: &Keypair,
-        mining: &Pubkey
+        payer: &Keypair,
+        mining: &Pubkey,
     ) -> BanksClientResult<()> {
         let (reward_pool, _) = self.get_pool_addresses(&liquidity_mint.pubkey());
 
@@ -411,12 +2129,12 @@ impl TestRewards {
                     mining,
                     &self.rewards_root.pubkey(),
                     &reward_pool,
-                    &self.root_authority.pubkey(),
+                    &payer.pubkey(),
                     &liquidity_mint.pubkey(),
                 ),
             ],
-            Some(&self.root_authority.pubkey()),
-            &[&self.root_authority],
+            Some(&payer.pubkey()),
+            &[payer],
             context.last_blockhash,
         );
 
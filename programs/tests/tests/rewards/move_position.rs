@@ -0,0 +1,118 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 3600, 0)
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let default_mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+    let position_mining = test_reward_pool
+        .open_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.owner,
+            1,
+        )
+        .await
+        .unwrap();
+    assert_ne!(default_mining, position_mining);
+
+    // a position can't be split into itself or beyond its amount
+    assert!(test_reward_pool
+        .split_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &default_mining,
+            &default_mining,
+            &token_holder.owner,
+            400,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .split_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &default_mining,
+            &position_mining,
+            &token_holder.owner,
+            1001,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .split_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &default_mining,
+            &position_mining,
+            &token_holder.owner,
+            400,
+        )
+        .await
+        .unwrap();
+
+    let default_data = get_account(&mut context, &default_mining).await.data;
+    let default_position = Mining::load(&default_data).unwrap();
+    let position_data = get_account(&mut context, &position_mining).await.data;
+    let position = Mining::load(&position_data).unwrap();
+    assert_eq!(default_position.amount, 600);
+    assert_eq!(position.amount, 400);
+    assert_eq!(position.position_index, 1);
+    // the moved amount keeps its lock
+    assert_eq!(
+        position.last_deposit_time,
+        default_position.last_deposit_time
+    );
+    assert_eq!(position.lock_time_sec, 3600);
+
+    let reward_pool_data = get_account(&mut context, &reward_pool).await.data;
+    let reward_pool_account = RewardPool::load(&reward_pool_data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 1000);
+    assert_eq!(reward_pool_account.total_stakers, 2);
+
+    test_reward_pool
+        .merge_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &position_mining,
+            &default_mining,
+            &token_holder.owner,
+        )
+        .await
+        .unwrap();
+
+    let default_data = get_account(&mut context, &default_mining).await.data;
+    assert_eq!(Mining::load(&default_data).unwrap().amount, 1000);
+    let position_data = get_account(&mut context, &position_mining).await.data;
+    assert_eq!(Mining::load(&position_data).unwrap().amount, 0);
+
+    let reward_pool_data = get_account(&mut context, &reward_pool).await.data;
+    let reward_pool_account = RewardPool::load(&reward_pool_data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 1000);
+    assert_eq!(reward_pool_account.total_stakers, 1);
+}
@@ -0,0 +1,291 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{BoostKind, Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    // partner token holders get +50%
+    let partner_mint = Keypair::new();
+    create_mint(&mut context, &partner_mint).await.unwrap();
+    test_reward_pool
+        .add_boost_source(
+            &mut context,
+            &pool_mint.pubkey(),
+            0,
+            BoostKind::Token,
+            5000,
+            100,
+            vec![partner_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    let partner_token_account = create_associated_token_account(
+        &mut context,
+        &token_holder.owner.pubkey(),
+        &partner_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    mint_tokens(
+        &mut context,
+        &partner_mint.pubkey(),
+        &partner_token_account,
+        100,
+    )
+    .await
+    .unwrap();
+
+    // every boost source of the pool is required
+    assert!(test_reward_pool
+        .refresh_boost(&mut context, &pool_mint.pubkey(), &mining_account, &[])
+        .await
+        .is_err());
+
+    test_reward_pool
+        .refresh_boost(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &[partner_token_account],
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.boost_bps, 5000);
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        750
+    );
+
+    // only the owner associated token account proves the balance
+    assert!(test_reward_pool
+        .refresh_boost(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &[rewarder.token_account],
+        )
+        .await
+        .is_err());
+
+    // the boost is dropped once the balance is below the min amount
+    let partner_receiver = Keypair::new();
+    create_token_account(
+        &mut context,
+        &partner_receiver,
+        &partner_mint.pubkey(),
+        &rewarder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    token_transfer(
+        &mut context,
+        &partner_token_account,
+        &partner_receiver.pubkey(),
+        &token_holder.owner,
+        1,
+    )
+    .await
+    .unwrap();
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .refresh_boost(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &[partner_token_account],
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.boost_bps, 0);
+}
+
+#[tokio::test]
+async fn mint_list() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    // partner NFT holders get +20%
+    let nft_mint = Keypair::new();
+    create_mint(&mut context, &nft_mint).await.unwrap();
+    test_reward_pool
+        .add_boost_source(
+            &mut context,
+            &pool_mint.pubkey(),
+            0,
+            BoostKind::MintList,
+            2000,
+            0,
+            vec![nft_mint.pubkey()],
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    let nft_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &nft_account,
+        &nft_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    mint_tokens(&mut context, &nft_mint.pubkey(), &nft_account.pubkey(), 1)
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .refresh_boost(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &[nft_account.pubkey()],
+        )
+        .await
+        .unwrap();
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.boost_bps, 2000);
+
+    // others can't drop the boost by passing any other account
+    let stranger_account = Keypair::new().pubkey();
+    test_reward_pool
+        .refresh_boost(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &[stranger_account],
+        )
+        .await
+        .unwrap();
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.boost_bps, 2000);
+
+    test_reward_pool
+        .refresh_boost_by_owner(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &token_holder.owner,
+            &[stranger_account],
+        )
+        .await
+        .unwrap();
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.boost_bps, 0);
+}
@@ -0,0 +1,157 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer, sysvar::clock};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 5)
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(100),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    // the pool requires unbonding
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .is_err());
+
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    test_reward_pool
+        .request_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.owner,
+            30,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.amount, 70);
+    assert_eq!(mining.unbonding_amount, 30);
+    assert_eq!(mining.unlock_at, clock.unix_timestamp as u64 + 100);
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 70);
+    assert_eq!(reward_pool_account.total_stakers, 1);
+
+    assert!(test_reward_pool
+        .finalize_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .is_err());
+
+    // cancel stakes the pending amount again
+    test_reward_pool
+        .cancel_unstake(&mut context, &liquidity_mint.pubkey(), &token_holder.owner)
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.amount, 100);
+    assert_eq!(mining.unbonding_amount, 0);
+
+    test_reward_pool
+        .request_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 0);
+    assert_eq!(reward_pool_account.total_stakers, 0);
+
+    // update solana clock
+    clock.unix_timestamp += 100;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    test_reward_pool
+        .finalize_unstake(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &token_holder.token_account).await,
+        1000
+    );
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.amount, 0);
+    assert_eq!(mining.unbonding_amount, 0);
+
+    // empty mining can be closed
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .unwrap();
+}
@@ -0,0 +1,168 @@
+use crate::utils::*;
+use everlend_rewards::state::{Mining, RewardPool, RewardTier};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+use super::{TestRewards, TIERS_CAPACITY, VAULTS_CAPACITY};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            125,
+            36,
+            60,
+        )
+        .await;
+
+    let reward_tiers = vec![
+        RewardTier {
+            ratio_base: 1,
+            ratio_quote: 2,
+            reward_max_amount_per_period: 3,
+        },
+        RewardTier {
+            ratio_base: 4,
+            ratio_quote: 5,
+            reward_max_amount_per_period: 6,
+        },
+    ];
+    test_reward_pool
+        .update_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            None,
+            None,
+            Some(reward_tiers.clone()),
+            None,
+            None,
+        )
+        .await;
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 100)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    let mining_account_info = get_account(&mut context, &mining_account).await;
+    assert_eq!(mining_account_info.data.len(), Mining::account_len(1));
+
+    // grow the pool
+    let vaults_capacity = VAULTS_CAPACITY + 1;
+    let tiers_capacity = TIERS_CAPACITY + 2;
+    test_reward_pool
+        .resize_pool(
+            &mut context,
+            &pool_mint.pubkey(),
+            vaults_capacity,
+            tiers_capacity,
+        )
+        .await
+        .unwrap();
+
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    assert_eq!(
+        reward_pool_account.data.len(),
+        RewardPool::account_len(vaults_capacity, tiers_capacity)
+    );
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.pool.vaults_capacity, vaults_capacity);
+    assert_eq!(reward_pool_account.pool.tiers_capacity, tiers_capacity);
+    assert_eq!(reward_pool_account.vaults().len(), 1);
+    assert_eq!(
+        reward_pool_account.vaults()[0].reward_mint,
+        reward_mint.pubkey()
+    );
+    assert_eq!(reward_pool_account.vault_tiers(0), reward_tiers.as_slice());
+
+    // new vault can use the whole tiers capacity
+    let new_reward_mint = Keypair::new();
+    create_mint(&mut context, &new_reward_mint).await.unwrap();
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &new_reward_mint.pubkey(),
+            100,
+            1,
+            60,
+        )
+        .await;
+
+    let new_reward_tiers = vec![RewardTier::default(); tiers_capacity as usize];
+    test_reward_pool
+        .update_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &new_reward_mint.pubkey(),
+            None,
+            None,
+            Some(new_reward_tiers.clone()),
+            None,
+            None,
+        )
+        .await;
+
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.vaults().len(), 2);
+    assert_eq!(reward_pool_account.vault_tiers(0), reward_tiers.as_slice());
+    assert_eq!(
+        reward_pool_account.vault_tiers(1),
+        new_reward_tiers.as_slice()
+    );
+
+    // mining grows to track the new vault
+    test_reward_pool
+        .upgrade_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.owner.pubkey(),
+            tiers_capacity - 1,
+        )
+        .await;
+
+    let mining_account_info = get_account(&mut context, &mining_account).await;
+    assert_eq!(mining_account_info.data.len(), Mining::account_len(2));
+    let mining = Mining::load_account(&mining_account_info.data).unwrap();
+    assert_eq!(mining.mining.reward_tier, tiers_capacity - 1);
+    assert_eq!(mining.indexes().len(), 2);
+
+    // the pool can't shrink
+    context.warp_to_slot(3).unwrap();
+    assert!(test_reward_pool
+        .resize_pool(
+            &mut context,
+            &pool_mint.pubkey(),
+            VAULTS_CAPACITY,
+            TIERS_CAPACITY,
+        )
+        .await
+        .is_err());
+}
@@ -0,0 +1,240 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, reward_pool_spl) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let treasury = Keypair::new();
+    let treasury_token_account =
+        create_associated_token_account(&mut context, &treasury.pubkey(), &liquidity_mint.pubkey())
+            .await
+            .unwrap();
+
+    // fees out of range or without a receiver are rejected
+    assert!(test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &treasury.pubkey(),
+            10_001,
+            0,
+            0,
+            false,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &Pubkey::default(),
+            100,
+            0,
+            0,
+            false,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &treasury.pubkey(),
+            100,
+            200,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    // the fee token account is required once the pool charges fees
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            1000,
+        )
+        .await
+        .is_err());
+
+    let mining_account = test_reward_pool
+        .deposit_mining_with_fee(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &treasury_token_account,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &treasury_token_account).await,
+        10
+    );
+    assert_eq!(get_token_balance(&mut context, &reward_pool_spl).await, 990);
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.amount, 990);
+
+    test_reward_pool
+        .withdraw_mining_with_fee(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &treasury_token_account,
+        )
+        .await
+        .unwrap();
+
+    // 2% of 990 rounded down
+    assert_eq!(
+        get_token_balance(&mut context, &treasury_token_account).await,
+        29
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &token_holder.token_account).await,
+        971
+    );
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.treasury, treasury.pubkey());
+    assert_eq!(reward_pool_account.total_deposit_fees, 10);
+    assert_eq!(reward_pool_account.total_withdraw_fees, 19);
+}
+
+#[tokio::test]
+async fn success_fee_to_vault() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 5)
+        .await
+        .unwrap();
+
+    // fees can fill only a vault of the liquidity mint
+    assert!(test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &Pubkey::default(),
+            500,
+            0,
+            0,
+            true,
+        )
+        .await
+        .is_err());
+
+    let vault = test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &liquidity_mint.pubkey(),
+            100,
+            1,
+            3600,
+        )
+        .await;
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &Pubkey::default(),
+            500,
+            0,
+            0,
+            true,
+        )
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    test_reward_pool
+        .deposit_mining_with_fee(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &vault,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(get_token_balance(&mut context, &vault).await, 50);
+
+    let reward_pool_account = get_account(&mut context, &reward_pool).await;
+    let reward_pool_account = RewardPool::load_account(&reward_pool_account.data).unwrap();
+    assert_eq!(reward_pool_account.pool.total_deposit_fees, 50);
+    assert_eq!(reward_pool_account.vaults()[0].total_funded, 50);
+
+    // the vault taking fees can't be switched to minting
+    set_mint_authority(&mut context, &liquidity_mint.pubkey(), &reward_pool)
+        .await
+        .unwrap();
+    assert!(test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &liquidity_mint.pubkey(),
+            500,
+            300,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .set_pool_fees(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &Pubkey::default(),
+            0,
+            0,
+            0,
+            false,
+        )
+        .await
+        .unwrap();
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .set_vault_minting(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &liquidity_mint.pubkey(),
+            500,
+            300,
+        )
+        .await
+        .unwrap();
+}
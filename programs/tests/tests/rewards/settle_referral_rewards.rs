@@ -0,0 +1,235 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::find_referrer_program_address;
+use everlend_rewards::state::{Mining, Referrer, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let pool_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &pool_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(1000),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let referrer = Keypair::new();
+    transfer_sol(&mut context, &referrer.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 50_000)
+        .await;
+
+    // users can't refer themselves
+    assert!(test_reward_pool
+        .deposit_mining_referred(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &token_holder.owner.pubkey(),
+            50_000,
+        )
+        .await
+        .is_err());
+
+    let mining_account = test_reward_pool
+        .deposit_mining_referred(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &referrer.pubkey(),
+            50_000,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.referrer, referrer.pubkey());
+    assert_eq!(mining.referral_share_bps, 1000);
+
+    let (referrer_account, _) =
+        find_referrer_program_address(&everlend_rewards::id(), &reward_pool, &referrer.pubkey());
+    let referrer_data =
+        Referrer::unpack(&get_account(&mut context, &referrer_account).await.data).unwrap();
+    assert_eq!(referrer_data.referrals_count, 1);
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.clone().into());
+    context.warp_to_slot(10).unwrap();
+
+    // referred positions settle the share of the referrer on withdraw
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .settle_referral_rewards(
+            &mut context,
+            &pool_mint.pubkey(),
+            &mining_account,
+            &referrer.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    // the referrer claims its share through the normal claim
+    let referrer_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &referrer_reward_account,
+        &reward_mint.pubkey(),
+        &referrer.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &referrer,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &referrer_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &referrer_reward_account.pubkey()).await,
+        50
+    );
+
+    // the referee keeps its full rewards
+    let user_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &user_reward_account,
+        &reward_mint.pubkey(),
+        &token_holder.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &token_holder.owner,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &user_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user_reward_account.pubkey()).await,
+        500
+    );
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(20).unwrap();
+
+    test_reward_pool
+        .withdraw_mining_referred(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            &referrer.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &token_holder.token_account).await,
+        50_000
+    );
+
+    // the share accrued since the last settlement is credited to the referrer on withdraw
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &referrer,
+            &pool_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &referrer_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &referrer_reward_account.pubkey()).await,
+        100
+    );
+}
@@ -0,0 +1,321 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let receipt_mint = test_reward_pool
+        .initialize_receipt_mint(&mut context, &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        RewardPool::load(&get_account(&mut context, &reward_pool).await.data)
+            .unwrap()
+            .receipt_mint,
+        receipt_mint
+    );
+
+    let sender = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            10_000_000_000,
+            10_000,
+        )
+        .await;
+    let receiver = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 0)
+        .await;
+    let sender_receipt_account =
+        create_associated_token_account(&mut context, &sender.owner.pubkey(), &receipt_mint)
+            .await
+            .unwrap();
+    let receiver_receipt_account =
+        create_associated_token_account(&mut context, &receiver.owner.pubkey(), &receipt_mint)
+            .await
+            .unwrap();
+
+    let sender_mining = test_reward_pool
+        .deposit_mining_with_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &sender.token_account,
+            &sender.owner,
+            &sender_receipt_account,
+            10_000,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &sender_receipt_account).await,
+        10_000
+    );
+
+    token_transfer(
+        &mut context,
+        &sender_receipt_account,
+        &receiver_receipt_account,
+        &sender.owner,
+        4_000,
+    )
+    .await
+    .unwrap();
+
+    // the sent receipts still back the sender position
+    assert!(test_reward_pool
+        .sync_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &receiver.owner.pubkey(),
+            &receiver_receipt_account,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .withdraw_mining_with_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &sender.token_account,
+            &sender.owner,
+            &sender_receipt_account,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .sync_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &sender.owner.pubkey(),
+            &sender_receipt_account,
+        )
+        .await
+        .unwrap();
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    let receiver_mining = test_reward_pool
+        .sync_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &receiver.owner.pubkey(),
+            &receiver_receipt_account,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::load(&get_account(&mut context, &sender_mining).await.data)
+        .unwrap()
+        .amount;
+    assert_eq!(mining, 6_000);
+    let mining = Mining::load(&get_account(&mut context, &receiver_mining).await.data)
+        .unwrap()
+        .amount;
+    assert_eq!(mining, 4_000);
+
+    let reward_pool_data = get_account(&mut context, &reward_pool).await.data;
+    let reward_pool_account = RewardPool::load(&reward_pool_data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 10_000);
+    assert_eq!(reward_pool_account.total_stakers, 2);
+
+    // rewards follow the synced receipt balances
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    let sender_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &sender_reward_account,
+        &reward_mint.pubkey(),
+        &sender.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &sender.owner,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &sender_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    let receiver_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &receiver_reward_account,
+        &reward_mint.pubkey(),
+        &receiver.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim(
+            &mut context,
+            &receiver.owner,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            &receiver_reward_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &sender_reward_account.pubkey()).await,
+        60
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &receiver_reward_account.pubkey()).await,
+        40
+    );
+
+    test_reward_pool
+        .withdraw_mining_with_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &receiver.token_account,
+            &receiver.owner,
+            &receiver_receipt_account,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &receiver.token_account).await,
+        4_000
+    );
+    assert_eq!(
+        get_token_balance(&mut context, &receiver_receipt_account).await,
+        0
+    );
+    assert_eq!(
+        get_mint_data(&mut context, &receipt_mint).await.supply,
+        6_000
+    );
+}
+
+#[tokio::test]
+async fn success_with_unstake_request() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+    let receipt_mint = test_reward_pool
+        .initialize_receipt_mint(&mut context, &liquidity_mint.pubkey())
+        .await
+        .unwrap();
+
+    // receipt pools can't become competitive
+    assert!(test_reward_pool
+        .initialize_stakers_index(&mut context, &liquidity_mint.pubkey())
+        .await
+        .is_err());
+
+    let user = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            10_000_000_000,
+            1_000,
+        )
+        .await;
+    let user_receipt_account =
+        create_associated_token_account(&mut context, &user.owner.pubkey(), &receipt_mint)
+            .await
+            .unwrap();
+
+    let mining_account = test_reward_pool
+        .deposit_mining_with_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            &user_receipt_account,
+            1_000,
+        )
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .request_unstake_with_receipt(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.owner,
+            &user_receipt_account,
+            400,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user_receipt_account).await,
+        600
+    );
+    let mining = Mining::load(&get_account(&mut context, &mining_account).await.data)
+        .unwrap()
+        .amount;
+    assert_eq!(mining, 600);
+    assert_eq!(
+        RewardPool::load(&get_account(&mut context, &reward_pool).await.data)
+            .unwrap()
+            .total_amount,
+        600
+    );
+}
@@ -0,0 +1,273 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::find_position_mint_program_address;
+use everlend_rewards::state::{Mining, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::sysvar::clock;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let reward_mint = Keypair::new();
+    create_mint(&mut context, &reward_mint).await.unwrap();
+    let (mut clock, mut clock_account) = get_clock(&mut context).await;
+
+    let reward_period = 3600;
+    test_reward_pool
+        .add_vault(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            100,
+            1,
+            reward_period,
+        )
+        .await;
+
+    let reward_amount = 1_000_000;
+    let rewarder = test_reward_pool
+        .create_token_holder(
+            &mut context,
+            &reward_mint.pubkey(),
+            10_000_000_000,
+            reward_amount,
+        )
+        .await;
+    test_reward_pool
+        .fill_vault(
+            &mut context,
+            &rewarder,
+            &liquidity_mint.pubkey(),
+            &reward_mint.pubkey(),
+            reward_amount,
+        )
+        .await
+        .unwrap();
+
+    let seller = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let buyer = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 0)
+        .await;
+
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &seller.token_account,
+            &seller.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+    let seller_position_account = test_reward_pool
+        .tokenize_position(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &seller.owner,
+        )
+        .await
+        .unwrap();
+
+    let (position_mint, _) = find_position_mint_program_address(&everlend_rewards::id(), &mining);
+    let position_mint_data = get_mint_data(&mut context, &position_mint).await;
+    assert_eq!(position_mint_data.supply, 1);
+    assert_eq!(position_mint_data.decimals, 0);
+    assert!(position_mint_data.mint_authority.is_none());
+    assert!(bool::from(
+        Mining::load(&get_account(&mut context, &mining).await.data)
+            .unwrap()
+            .is_tokenized
+    ));
+
+    // the position is traded without unlocking the stake
+    let buyer_position_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &buyer_position_account,
+        &position_mint,
+        &buyer.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    token_transfer(
+        &mut context,
+        &seller_position_account,
+        &buyer_position_account.pubkey(),
+        &seller.owner,
+        1,
+    )
+    .await
+    .unwrap();
+
+    clock.unix_timestamp += reward_period as i64;
+    clock_account.data = bincode::serialize(&clock).unwrap();
+    context.set_account(&clock::id(), &clock_account.into());
+    context.warp_to_slot(10).unwrap();
+
+    // the former owner controls the position no more
+    let seller_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &seller_reward_account,
+        &reward_mint.pubkey(),
+        &seller.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    assert!(test_reward_pool
+        .claim_tokenized(
+            &mut context,
+            &seller.owner,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &reward_mint.pubkey(),
+            &seller_reward_account.pubkey(),
+            &seller_position_account,
+        )
+        .await
+        .is_err());
+    assert!(test_reward_pool
+        .withdraw_mining_tokenized(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &seller.token_account,
+            &seller.owner,
+            &seller_position_account,
+        )
+        .await
+        .is_err());
+
+    let buyer_reward_account = Keypair::new();
+    create_token_account(
+        &mut context,
+        &buyer_reward_account,
+        &reward_mint.pubkey(),
+        &buyer.owner.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    test_reward_pool
+        .claim_tokenized(
+            &mut context,
+            &buyer.owner,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &reward_mint.pubkey(),
+            &buyer_reward_account.pubkey(),
+            &buyer_position_account.pubkey(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &buyer_reward_account.pubkey()).await,
+        10
+    );
+
+    test_reward_pool
+        .withdraw_mining_tokenized(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &buyer.token_account,
+            &buyer.owner,
+            &buyer_position_account.pubkey(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &buyer.token_account).await,
+        1000
+    );
+    assert_eq!(get_mint_data(&mut context, &position_mint).await.supply, 0);
+}
+
+#[tokio::test]
+async fn unbonding_enabled_later() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let mining = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+    let user_position_account = test_reward_pool
+        .tokenize_position(&mut context, &liquidity_mint.pubkey(), &mining, &user.owner)
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(3600),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // the holder can't request unstake so the position is withdrawn without unbonding
+    assert!(test_reward_pool
+        .request_unstake(&mut context, &liquidity_mint.pubkey(), &user.owner, 1000)
+        .await
+        .is_err());
+    test_reward_pool
+        .withdraw_mining_tokenized(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &user.token_account,
+            &user.owner,
+            &user_position_account,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &user.token_account).await,
+        1000
+    );
+}
@@ -0,0 +1,147 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Denylist, RewardPool, ZeroCopy};
+use everlend_rewards::{find_denylist_program_address, find_mining_program_address};
+use solana_program_test::*;
+use solana_sdk::transaction::Transaction;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, reward_pool_spl) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    test_reward_pool
+        .update_denylist(&mut context, &user.owner.pubkey(), true, &[reward_pool])
+        .await
+        .unwrap();
+
+    let (denylist, bump) = find_denylist_program_address(
+        &everlend_rewards::id(),
+        &test_reward_pool.rewards_root.pubkey(),
+    );
+    let denylist_data = get_account(&mut context, &denylist).await.data;
+    assert_eq!(
+        Denylist::load_account(&denylist_data).unwrap().wallets(),
+        &[user.owner.pubkey()]
+    );
+
+    // the pool is guarded by the denylist from now on
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert!(bool::from(reward_pool_account.has_denylist));
+    assert_eq!(reward_pool_account.denylist_bump, bump);
+
+    // deposits into a guarded pool can't leave the denylist out
+    let (mining, _) =
+        find_mining_program_address(&everlend_rewards::id(), &user.owner.pubkey(), &reward_pool);
+    let tx = Transaction::new_signed_with_payer(
+        &[everlend_rewards::instruction::deposit_mining(
+            &everlend_rewards::id(),
+            None,
+            &reward_pool,
+            &reward_pool_spl,
+            &liquidity_mint.pubkey(),
+            &mining,
+            &user.token_account,
+            &user.owner.pubkey(),
+            500,
+        )],
+        Some(&user.owner.pubkey()),
+        &[&user.owner],
+        context.last_blockhash,
+    );
+    assert!(context.banks_client.process_transaction(tx).await.is_err());
+
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            500,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .update_denylist(&mut context, &user.owner.pubkey(), false, &[])
+        .await
+        .unwrap();
+    assert_eq!(
+        get_account(&mut context, &denylist).await.data.len(),
+        Denylist::account_len(0)
+    );
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            500,
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn without_denylist() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 0)
+        .await
+        .unwrap();
+
+    let user = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            500,
+        )
+        .await
+        .unwrap();
+
+    // deposits into a pool without denylist don't take it nor write its bump
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert!(!bool::from(reward_pool_account.has_denylist));
+    assert_eq!(reward_pool_account.denylist_bump, 0);
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &user.token_account,
+            &user.owner,
+            500,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context, &user.token_account).await,
+        0
+    );
+}
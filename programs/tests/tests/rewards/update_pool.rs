@@ -0,0 +1,180 @@
+use crate::{rewards::TestRewards, utils::*};
+use everlend_rewards::state::{Mining, PoolStatus, RewardPool, ZeroCopy};
+use solana_program_test::*;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+#[tokio::test]
+async fn success() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+    let liquidity_mint = Keypair::new();
+
+    let (reward_pool, _) = test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &liquidity_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &liquidity_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            100,
+        )
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            Some(1000),
+            None,
+            Some(10),
+            Some(1000),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.lock_time_sec, 1000);
+    assert_eq!(reward_pool_account.max_stakers, 5);
+    assert_eq!(reward_pool_account.min_deposit_amount, 10);
+    assert_eq!(reward_pool_account.max_deposit_amount, 1000);
+    assert_eq!(reward_pool_account.pool_status(), PoolStatus::Active);
+
+    // a longer lock doesn't apply to the existing position
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .unwrap();
+
+    // deposits are checked against the pool limits
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            5,
+        )
+        .await
+        .is_err());
+
+    test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            50,
+        )
+        .await
+        .unwrap();
+
+    let mining = Mining::unpack(&get_account(&mut context, &mining_account).await.data).unwrap();
+    assert_eq!(mining.lock_time_sec, 1000);
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    assert!(test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .is_err());
+
+    // a shorter lock applies immediately
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            Some(0),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    context.last_blockhash = context.get_new_latest_blockhash().await.unwrap();
+    test_reward_pool
+        .withdraw_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+        )
+        .await
+        .unwrap();
+
+    // paused pools don't accept deposits
+    test_reward_pool
+        .update_pool(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(PoolStatus::Paused),
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert!(test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &liquidity_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            100,
+        )
+        .await
+        .is_err());
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.pool_status(), PoolStatus::Paused);
+    assert_eq!(reward_pool_account.total_stakers, 0);
+}
@@ -1,6 +1,5 @@
 use crate::utils::*;
-use everlend_rewards::state::{Mining, RewardPool};
-use solana_program::program_pack::Pack;
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
 use solana_program_test::*;
 use solana_sdk::{signature::Keypair, signer::Signer};
 use std::borrow::Borrow;
@@ -108,3 +107,44 @@ async fn success() {
 
     println!("{:?}", mining)
 }
+
+#[tokio::test]
+async fn legacy_accounts() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let pool_mint = Keypair::new();
+
+    test_reward_pool
+        .create_mint_and_initialize_pool(&mut context, &pool_mint, 0, 5)
+        .await
+        .unwrap();
+
+    let token_holder = test_reward_pool
+        .create_token_holder(&mut context, &pool_mint.pubkey(), 10_000_000_000, 1000)
+        .await;
+    let mining_account = test_reward_pool
+        .deposit_mining(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.token_account,
+            &token_holder.owner,
+            1000,
+        )
+        .await
+        .unwrap();
+
+    test_reward_pool
+        .upgrade_mining_legacy(
+            &mut context,
+            &pool_mint.pubkey(),
+            &token_holder.owner.pubkey(),
+            2,
+        )
+        .await
+        .unwrap();
+
+    let mining_data = get_account(&mut context, &mining_account).await.data;
+    assert_eq!(Mining::load(&mining_data).unwrap().reward_tier, 2);
+}
@@ -1,9 +1,9 @@
 use std::borrow::Borrow;
 
 use crate::{rewards::TestRewards, utils::*};
-use everlend_rewards::state::{Mining, RewardPool};
+use everlend_rewards::state::{Mining, RewardPool, ZeroCopy};
 use solana_program_test::*;
-use solana_sdk::{program_pack::Pack, signature::Keypair, signer::Signer};
+use solana_sdk::{signature::Keypair, signer::Signer};
 
 #[tokio::test]
 async fn success() {
@@ -108,3 +108,54 @@ async fn success() {
         .await
         .unwrap_err();
 }
+
+#[tokio::test]
+async fn success_native() {
+    let mut context = program_test().start_with_context().await;
+
+    let test_reward_pool = TestRewards::new(&mut context).await;
+
+    let (reward_pool, reward_pool_spl) = test_reward_pool
+        .initialize_pool(&mut context, &spl_token::native_mint::id(), 0, 5)
+        .await
+        .unwrap();
+
+    let user = Keypair::new();
+    transfer_sol(&mut context, &user.pubkey(), 10_000_000_000)
+        .await
+        .unwrap();
+    let pool_spl_lamports = get_account(&mut context, &reward_pool_spl).await.lamports;
+
+    let deposit_amount = 1_000_000_000;
+    test_reward_pool
+        .deposit_mining_native(&mut context, &user, deposit_amount)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context, &reward_pool_spl).await,
+        deposit_amount
+    );
+    assert_eq!(
+        get_account(&mut context, &reward_pool_spl).await.lamports,
+        pool_spl_lamports + deposit_amount
+    );
+
+    test_reward_pool
+        .withdraw_mining_native(&mut context, &user)
+        .await
+        .unwrap();
+
+    assert_eq!(get_token_balance(&mut context, &reward_pool_spl).await, 0);
+
+    // lamports and the rent of the mining and unwrap accounts are returned, only fees are spent
+    let lamports_per_signature = 5000;
+    assert_eq!(
+        get_account(&mut context, &user.pubkey()).await.lamports,
+        10_000_000_000 - 2 * lamports_per_signature
+    );
+
+    let reward_pool_account =
+        RewardPool::unpack(&get_account(&mut context, &reward_pool).await.data).unwrap();
+    assert_eq!(reward_pool_account.total_amount, 0);
+}
@@ -1,6 +1,12 @@
 #![allow(dead_code)]
 
-use solana_program::{program_pack::Pack, pubkey::Pubkey, system_instruction};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, system_program,
+};
+use std::str::FromStr;
 use solana_program_test::*;
 use solana_program_test::{ProgramTest, ProgramTestContext};
 use solana_sdk::clock::Clock;
@@ -41,6 +47,15 @@ pub fn program_test() -> ProgramTest {
     program
 }
 
+/// Runs the BPF build of the rewards program, compute units are only metered there
+pub fn bpf_program_test() -> ProgramTest {
+    let mut program = ProgramTest::default();
+    program.prefer_bpf(true);
+    program.add_program("everlend_rewards", everlend_rewards::id(), None);
+
+    program
+}
+
 pub async fn get_account(context: &mut ProgramTestContext, pubkey: &Pubkey) -> Account {
     context
         .banks_client
@@ -78,6 +93,24 @@ pub async fn get_token_balance(context: &mut ProgramTestContext, pubkey: &Pubkey
     account_info.amount
 }
 
+pub async fn get_units_consumed(
+    context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> u64 {
+    let tx = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        context.last_blockhash,
+    );
+
+    let simulation = context.banks_client.simulate_transaction(tx).await.unwrap();
+    simulation.result.unwrap().unwrap();
+
+    simulation.simulation_details.unwrap().units_consumed
+}
+
 pub fn get_liquidity_mint() -> (Keypair, Pubkey) {
     let keypair = read_keypair_file("tests/fixtures/lending/liquidity.json").unwrap();
     let pubkey = keypair.pubkey();
@@ -218,3 +251,57 @@ pub async fn mint_tokens(
 
     context.banks_client.process_transaction(tx).await
 }
+
+pub async fn set_mint_authority(
+    context: &mut ProgramTestContext,
+    mint: &Pubkey,
+    new_authority: &Pubkey,
+) -> BanksClientResult<()> {
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token::instruction::set_authority(
+            &spl_token::id(),
+            mint,
+            Some(new_authority),
+            spl_token::instruction::AuthorityType::MintTokens,
+            &context.payer.pubkey(),
+            &[],
+        )
+        .unwrap()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await
+}
+
+pub async fn create_associated_token_account(
+    context: &mut ProgramTestContext,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> BanksClientResult<Pubkey> {
+    let (associated_token_account, _) =
+        everlend_utils::cpi::spl_token::find_associated_token_account(wallet, mint);
+
+    let tx = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            Pubkey::from_str("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL").unwrap(),
+            &[],
+            vec![
+                AccountMeta::new(context.payer.pubkey(), true),
+                AccountMeta::new(associated_token_account, false),
+                AccountMeta::new_readonly(*wallet, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_token::id(), false),
+            ],
+        )],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+
+    context.banks_client.process_transaction(tx).await?;
+
+    Ok(associated_token_account)
+}
@@ -93,6 +93,8 @@ fn send_initialize_pool(
         &reward_authority.pubkey(),
         0,
         1000,
+        3,
+        5,
     );
 
     // Build the transaction
@@ -212,6 +214,11 @@ fn send_update_vault(
                 reward_max_amount_per_period: 6,
             },
         ]),
+        None,
+        None,
+        None,
+        None,
+        None,
     );
 
     // Build the transaction
@@ -246,6 +253,7 @@ fn send_fill_vault(
 
     let ix = fill_vault(
         &everlend_rewards::id(),
+        rewards_root,
         &reward_pool,
         &reward_mint,
         &vault,
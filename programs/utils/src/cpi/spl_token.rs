@@ -43,6 +43,22 @@ pub fn initialize_account<'a>(
     invoke(&ix, &[account, mint, authority, rent])
 }
 
+/// Initialize SPL accont instruction without the rent sysvar.
+pub fn initialize_account3<'a>(
+    account: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    authority: &Pubkey,
+) -> ProgramResult {
+    let ix = spl_token::instruction::initialize_account3(
+        &spl_token::id(),
+        account.key,
+        mint.key,
+        authority,
+    )?;
+
+    invoke(&ix, &[account, mint])
+}
+
 /// SPL transfer instruction.
 pub fn transfer<'a>(
     source: AccountInfo<'a>,
@@ -121,6 +137,26 @@ pub fn close_account<'a>(
     invoke_signed(&ix, &[account, destination, authority], signers_seeds)
 }
 
+/// SPL set authority instruction.
+pub fn set_authority<'a>(
+    account: AccountInfo<'a>,
+    authority: AccountInfo<'a>,
+    new_authority: Option<&Pubkey>,
+    authority_type: spl_token::instruction::AuthorityType,
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), ProgramError> {
+    let ix = spl_token::instruction::set_authority(
+        &spl_token::id(),
+        account.key,
+        new_authority,
+        authority_type,
+        authority.key,
+        &[],
+    )?;
+
+    invoke_signed(&ix, &[account, authority], signers_seeds)
+}
+
 /// SPL sync native instruction
 pub fn sync_native(account: AccountInfo) -> Result<(), ProgramError> {
     let ix = spl_token::instruction::sync_native(&spl_token::id(), account.key)?;
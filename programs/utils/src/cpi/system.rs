@@ -2,6 +2,7 @@ use solana_program::{
     account_info::AccountInfo,
     entrypoint::ProgramResult,
     program::{invoke, invoke_signed},
+    program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
@@ -16,12 +17,24 @@ pub fn create_account<'a, S: Pack>(
     to: AccountInfo<'a>,
     signers_seeds: &[&[&[u8]]],
     rent: &Rent,
+) -> ProgramResult {
+    create_account_with_len(program_id, from, to, signers_seeds, rent, S::LEN)
+}
+
+/// Create account with explicit data length
+pub fn create_account_with_len<'a>(
+    program_id: &Pubkey,
+    from: AccountInfo<'a>,
+    to: AccountInfo<'a>,
+    signers_seeds: &[&[&[u8]]],
+    rent: &Rent,
+    len: usize,
 ) -> ProgramResult {
     let ix = system_instruction::create_account(
         from.key,
         to.key,
-        rent.minimum_balance(S::LEN),
-        S::LEN as u64,
+        rent.minimum_balance(len),
+        len as u64,
         program_id,
     );
 
@@ -62,6 +75,30 @@ pub fn realloc_with_rent<'a, 'b>(
     acc.realloc(new_len, false)
 }
 
+/// Withdraw lamports keeping the source account rent exempt
+pub fn withdraw_lamports<'a, 'b>(
+    source_account_info: &'a AccountInfo<'b>,
+    dest_account_info: &'a AccountInfo<'b>,
+    amount: u64,
+    rent: &Rent,
+) -> ProgramResult {
+    let source_lamports = source_account_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    if source_lamports < rent.minimum_balance(source_account_info.data_len()) {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    **source_account_info.lamports.borrow_mut() = source_lamports;
+    **dest_account_info.lamports.borrow_mut() = dest_account_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ProgramError::InvalidArgument)?;
+
+    Ok(())
+}
+
 pub fn close_account<'a, 'b>(
     source_account_info: &'a AccountInfo<'b>,
     dest_account_info: &'a AccountInfo<'b>,
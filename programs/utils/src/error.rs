@@ -40,6 +40,70 @@ pub enum EverlendError {
 
     #[error("Pool is full")]
     PoolIsFull,
+
+    /// Pool is not active
+    #[error("Pool is not active")]
+    PoolIsNotActive,
+
+    /// Deposit amount is out of pool limits
+    #[error("Deposit amount is out of pool limits")]
+    InvalidDepositAmount,
+
+    /// Position amount is out of pool limits
+    #[error("Position amount is out of pool limits")]
+    InvalidStakeAmount,
+
+    /// Position is not below the pool min stake amount
+    #[error("Position is not below the pool min stake amount")]
+    PositionIsNotDust,
+
+    /// Unbonding is still active
+    #[error("Unbonding is still active")]
+    UnbondingStillActive,
+
+    /// Pool requires unbonding before withdrawal
+    #[error("Pool requires unbonding before withdrawal")]
+    UnbondingRequired,
+
+    /// Vault can only be filled by the root authority or the vault manager
+    #[error("Vault can only be filled by the root authority or the vault manager")]
+    FillNotPermitted,
+
+    /// Reward pool is not the mint authority of the reward mint
+    #[error("Reward pool is not the mint authority of the reward mint")]
+    InvalidMintAuthority,
+
+    /// Received receipts exceed receipts unstaked by senders
+    #[error("Receipt sender position is not synced")]
+    ReceiptNotSynced,
+
+    /// Position is held as an NFT and controlled by its holder
+    #[error("Position is held as an NFT")]
+    PositionIsTokenized,
+
+    /// Signer doesn't hold the position NFT
+    #[error("Signer doesn't hold the position NFT")]
+    InvalidPositionHolder,
+
+    /// Pool is not closing
+    #[error("Pool is not closing")]
+    PoolIsNotClosing,
+
+    /// Closing grace period of the pool is still active
+    #[error("Closing grace period is still active")]
+    ClosingGracePeriodStillActive,
+
+    /// Mining is frozen
+    #[error("Mining is frozen")]
+    MiningIsFrozen,
+
+    /// Wallet is denied
+    #[error("Wallet is denied")]
+    WalletIsDenied,
+
+    /// Referral rewards of the mining aren't settled
+    #[error("Referral rewards not settled")]
+    ReferralRewardsNotSettled,
 }
 
 impl PrintProgramError for EverlendError {
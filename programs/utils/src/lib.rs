@@ -116,6 +116,12 @@ impl AccountLoader {
         Ok(acc)
     }
 
+    /// Number of accounts left
+    pub fn remaining_len<I: Iterator>(iter: &Enumerate<I>) -> usize {
+        let (remaining_len, _) = iter.size_hint();
+        remaining_len
+    }
+
     pub fn has_more<I: Iterator>(iter: &Enumerate<I>) -> bool {
         let (remaining_len, _) = iter.size_hint();
         remaining_len > 0
@@ -5,6 +5,9 @@ use spl_math::precise_number::PreciseNumber;
 /// Scale for precision
 pub const PRECISION_SCALER: u128 = 1_000_000_000;
 
+/// Basis points in one
+pub const BPS_SCALER: u128 = 10_000;
+
 pub fn abs_diff(a: u64, b: u64) -> Result<u64, ProgramError> {
     let res = (a as i128)
         .checked_sub(b as i128)
@@ -46,3 +49,13 @@ pub fn share_floor(amount: u64, percent: u64) -> Result<u64, ProgramError> {
 
     Ok(res as u64)
 }
+
+pub fn bps_floor(amount: u64, bps: u16) -> Result<u64, ProgramError> {
+    let res = (bps as u128)
+        .checked_mul(amount as u128)
+        .ok_or(EverlendError::MathOverflow)?
+        .checked_div(BPS_SCALER)
+        .ok_or(EverlendError::MathOverflow)?;
+
+    Ok(res as u64)
+}